@@ -29,7 +29,7 @@ fn main() {
     println!("是否为质数(17): {}", NumberUtils::is_prime(17));
     println!("最大公约数(12, 8): {}", NumberUtils::gcd(12, 8));
     println!("斐波那契(10): {}", NumberUtils::fibonacci(10));
-    println!("四舍五入(3.14159, 2位): {}", NumberUtils::round_to_decimal_places(3.14159, 2));
+    println!("四舍五入(1.23456, 2位): {}", NumberUtils::round_to_decimal_places(1.23456, 2));
     println!("百分比(0.25): {}", NumberUtils::percentage(25.0, 100.0));
     println!("进制转换(255 -> 16进制): {}", NumberUtils::to_base(255, 16));
     println!("千分位格式: {}", NumberUtils::format_with_commas(1234567));
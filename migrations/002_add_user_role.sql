@@ -0,0 +1,5 @@
+-- Add role column to users table
+ALTER TABLE users ADD COLUMN role VARCHAR(50) NOT NULL DEFAULT 'user';
+
+-- Create index on role for role-based queries
+CREATE INDEX idx_users_role ON users(role);
@@ -0,0 +1,2 @@
+-- Add email verification flag to users table
+ALTER TABLE users ADD COLUMN email_verified BOOLEAN NOT NULL DEFAULT FALSE;
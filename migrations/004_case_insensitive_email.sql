@@ -0,0 +1,5 @@
+-- Store emails in lowercase (fails if two accounts differ only by email case)
+UPDATE users SET email = LOWER(email) WHERE email <> LOWER(email);
+
+-- Enforce case-insensitive email uniqueness
+CREATE UNIQUE INDEX idx_users_email_lower ON users(LOWER(email));
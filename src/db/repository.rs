@@ -0,0 +1,434 @@
+/*!
+ * 通用数据访问层
+ *
+ * `Repository<T>` 抽象了以 UUID `id` 为主键的实体的增删改查操作；
+ * `PgRepository<T>` 是 PostgreSQL 实现，只需要表名和实现了 `FromRow` 的实体类型，
+ * 新增实体时不必再为每个查询手写 SQL。
+ *
+ * 插入和更新的字段由可序列化的结构体（或 `serde_json` 对象）描述，键为列名。
+ * 字段值通过 `jsonb_populate_record` 按表的列类型转换，因此字段值的 JSON 表示
+ * 必须能被 PostgreSQL 解析为对应的列类型（字符串、数字、布尔值、UUID、RFC 3339 时间等）。
+ */
+
+use std::marker::PhantomData;
+
+use axum::async_trait;
+use serde::Serialize;
+use sqlx::{postgres::PgRow, FromRow};
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    error::{AppError, Result},
+};
+
+/// 通用数据访问接口
+///
+/// 采用 `id` 作为主键，插入和更新的字段以可序列化的值描述（见模块文档）。
+#[async_trait]
+pub trait Repository<T>: Send + Sync {
+    /// 根据主键查询，不存在时返回 `None`
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<T>>;
+
+    /// 按默认排序查询所有记录
+    async fn find_all(&self) -> Result<Vec<T>>;
+
+    /// 按默认排序分页查询
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<T>>;
+
+    /// 统计记录总数
+    async fn count(&self) -> Result<i64>;
+
+    /// 插入一条记录并返回插入后的完整记录
+    ///
+    /// 未提供的列使用数据库默认值。
+    async fn insert<V>(&self, values: &V) -> Result<T>
+    where
+        V: Serialize + Sync;
+
+    /// 更新指定记录中提供的列，返回更新后的记录；记录不存在时返回 `None`
+    ///
+    /// 没有提供任何列时不执行更新，直接返回当前记录。
+    /// `PgRepository` 还会把 `touch` 设置的列刷新为 `NOW()`。
+    async fn update<V>(&self, id: Uuid, values: &V) -> Result<Option<T>>
+    where
+        V: Serialize + Sync;
+
+    /// 删除指定记录，返回记录是否存在
+    async fn delete(&self, id: Uuid) -> Result<bool>;
+}
+
+/// `Repository` 的 PostgreSQL 实现
+///
+/// # 示例
+///
+/// ```rust,no_run
+/// use hello_rust::{
+///     db::{DbPool, PgRepository, Repository},
+///     models::User,
+/// };
+///
+/// async fn newest_users(pool: &DbPool) -> hello_rust::Result<Vec<User>> {
+///     let users = PgRepository::<User>::new(pool.clone(), "users").order_by("created_at DESC");
+///     users.list(10, 0).await
+/// }
+/// ```
+pub struct PgRepository<T> {
+    pool: DbPool,
+    table: &'static str,
+    order_by: &'static str,
+    touch: Option<&'static str>,
+    _entity: PhantomData<fn() -> T>,
+}
+
+impl<T> PgRepository<T> {
+    /// 创建指定表的数据访问对象，默认按 `id` 排序
+    ///
+    /// # Panics
+    ///
+    /// 表名不是合法的 SQL 标识符（小写字母、数字和下划线）时 panic
+    pub fn new(pool: DbPool, table: &'static str) -> Self {
+        assert!(is_identifier(table), "invalid table name: {}", table);
+        Self {
+            pool,
+            table,
+            order_by: "id",
+            touch: None,
+            _entity: PhantomData,
+        }
+    }
+
+    /// 设置 `find_all` 和 `list` 使用的排序子句，如 `created_at DESC`
+    ///
+    /// 排序子句会直接拼接到 SQL 中，只能使用代码中的常量。
+    pub fn order_by(mut self, order_by: &'static str) -> Self {
+        self.order_by = order_by;
+        self
+    }
+
+    /// 设置 `update` 时自动刷新为数据库当前时间（`NOW()`）的列，如 `updated_at`
+    ///
+    /// 时间由数据库生成，不依赖应用服务器的时钟。
+    ///
+    /// # Panics
+    ///
+    /// 列名不是合法的 SQL 标识符时 panic
+    pub fn touch(mut self, column: &'static str) -> Self {
+        assert!(is_identifier(column), "invalid column name: {}", column);
+        self.touch = Some(column);
+        self
+    }
+}
+
+impl<T> Clone for PgRepository<T> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            table: self.table,
+            order_by: self.order_by,
+            touch: self.touch,
+            _entity: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> Repository<T> for PgRepository<T>
+where
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin + 'static,
+{
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<T>> {
+        let sql = format!("SELECT * FROM {} WHERE id = $1", self.table);
+        let row = sqlx::query_as::<_, T>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row)
+    }
+
+    async fn find_all(&self) -> Result<Vec<T>> {
+        let sql = format!("SELECT * FROM {} ORDER BY {}", self.table, self.order_by);
+        let rows = sqlx::query_as::<_, T>(&sql).fetch_all(&self.pool).await?;
+
+        Ok(rows)
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<T>> {
+        let sql = format!(
+            "SELECT * FROM {} ORDER BY {} LIMIT $1 OFFSET $2",
+            self.table, self.order_by
+        );
+        let rows = sqlx::query_as::<_, T>(&sql)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    async fn count(&self) -> Result<i64> {
+        let sql = format!("SELECT COUNT(*) FROM {}", self.table);
+        let count: i64 = sqlx::query_scalar(&sql).fetch_one(&self.pool).await?;
+
+        Ok(count)
+    }
+
+    async fn insert<V>(&self, values: &V) -> Result<T>
+    where
+        V: Serialize + Sync,
+    {
+        let (columns, record) = columns_and_record(values)?;
+
+        let row = if columns.is_empty() {
+            let sql = format!("INSERT INTO {} DEFAULT VALUES RETURNING *", self.table);
+            sqlx::query_as::<_, T>(&sql).fetch_one(&self.pool).await?
+        } else {
+            let columns = columns.join(", ");
+            let sql = format!(
+                "INSERT INTO {table} ({columns}) \
+                 SELECT {columns} FROM jsonb_populate_record(NULL::{table}, $1::jsonb) \
+                 RETURNING *",
+                table = self.table,
+                columns = columns,
+            );
+            sqlx::query_as::<_, T>(&sql)
+                .bind(record)
+                .fetch_one(&self.pool)
+                .await?
+        };
+
+        Ok(row)
+    }
+
+    async fn update<V>(&self, id: Uuid, values: &V) -> Result<Option<T>>
+    where
+        V: Serialize + Sync,
+    {
+        let (columns, record) = columns_and_record(values)?;
+        if columns.is_empty() {
+            return self.find_by_id(id).await;
+        }
+
+        let mut assignments = columns
+            .iter()
+            .map(|column| format!("{0} = record.{0}", column))
+            .collect::<Vec<_>>();
+        if let Some(column) = self
+            .touch
+            .filter(|touch| !columns.iter().any(|c| c == touch))
+        {
+            assignments.push(format!("{} = NOW()", column));
+        }
+        let assignments = assignments.join(", ");
+        let sql = format!(
+            "UPDATE {table} SET {assignments} \
+             FROM jsonb_populate_record(NULL::{table}, $1::jsonb) AS record \
+             WHERE {table}.id = $2 \
+             RETURNING {table}.*",
+            table = self.table,
+            assignments = assignments,
+        );
+        let row = sqlx::query_as::<_, T>(&sql)
+            .bind(record)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool> {
+        let sql = format!("DELETE FROM {} WHERE id = $1", self.table);
+        let result = sqlx::query(&sql).bind(id).execute(&self.pool).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// 把字段值序列化为 JSON 对象，返回列名列表和 JSON 文本
+///
+/// # 错误
+///
+/// - `AppError::Internal`: 值不是 JSON 对象，或键不是合法的列名
+fn columns_and_record<V: Serialize>(values: &V) -> Result<(Vec<String>, String)> {
+    let serde_json::Value::Object(map) =
+        serde_json::to_value(values).map_err(|e| AppError::Internal(e.into()))?
+    else {
+        return Err(AppError::Internal(anyhow::anyhow!(
+            "Repository values must serialize to a JSON object"
+        )));
+    };
+
+    // 列名会拼接到 SQL 中，必须是合法的标识符
+    if let Some(column) = map.keys().find(|column| !is_identifier(column)) {
+        return Err(AppError::Internal(anyhow::anyhow!(
+            "Invalid column name: {}",
+            column
+        )));
+    }
+
+    let columns = map.keys().cloned().collect();
+    Ok((columns, serde_json::Value::Object(map).to_string()))
+}
+
+/// 是否为合法的 SQL 标识符（小写字母或下划线开头，只包含小写字母、数字和下划线）
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase() || c == '_')
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::test_support, models::User};
+
+    fn users(pool: &DbPool) -> PgRepository<User> {
+        PgRepository::new(pool.clone(), "users").order_by("created_at DESC")
+    }
+
+    #[test]
+    fn test_columns_and_record() {
+        let (columns, record) =
+            columns_and_record(&serde_json::json!({ "name": "rust", "email_verified": true }))
+                .unwrap();
+        assert_eq!(columns, vec!["email_verified", "name"]);
+        assert_eq!(record, r#"{"email_verified":true,"name":"rust"}"#);
+
+        // 列名不合法或值不是对象时拒绝
+        assert!(columns_and_record(&serde_json::json!({ "name; DROP TABLE users": 1 })).is_err());
+        assert!(columns_and_record(&serde_json::json!({ "Name": 1 })).is_err());
+        assert!(columns_and_record(&vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_is_identifier() {
+        assert!(is_identifier("users"));
+        assert!(is_identifier("_audit_2024"));
+        assert!(!is_identifier(""));
+        assert!(!is_identifier("2fa"));
+        assert!(!is_identifier("users u"));
+        assert!(!is_identifier("public.users"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_find_and_list_users() {
+        let pool = test_support::pool().await;
+        let repo = users(&pool);
+
+        let first: User = repo
+            .insert(&serde_json::json!({
+                "email": test_support::unique_email("repo"),
+                "password_hash": "hash",
+                "name": "first",
+            }))
+            .await
+            .unwrap();
+        let second: User = repo
+            .insert(&serde_json::json!({
+                "email": test_support::unique_email("repo"),
+                "password_hash": "hash",
+                "name": "second",
+            }))
+            .await
+            .unwrap();
+        // 未提供的列使用数据库默认值
+        assert_eq!(first.role, "user");
+        assert!(!first.email_verified);
+
+        let found = repo.find_by_id(first.id).await.unwrap().unwrap();
+        assert_eq!(found.email, first.email);
+        assert!(repo.find_by_id(Uuid::new_v4()).await.unwrap().is_none());
+
+        // 按创建时间倒序，最新插入的排在最前面
+        let page = repo.list(2, 0).await.unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].id, second.id);
+        assert_eq!(page[1].id, first.id);
+        assert!(repo.count().await.unwrap() >= 2);
+        assert!(repo
+            .find_all()
+            .await
+            .unwrap()
+            .iter()
+            .any(|user| user.id == first.id));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_update_and_delete_user() {
+        let pool = test_support::pool().await;
+        let repo = users(&pool);
+
+        let user: User = repo
+            .insert(&serde_json::json!({
+                "email": test_support::unique_email("repo"),
+                "password_hash": "hash",
+                "name": "before",
+            }))
+            .await
+            .unwrap();
+
+        // 只更新提供的列
+        let updated = repo
+            .update(
+                user.id,
+                &serde_json::json!({ "name": "after", "email_verified": true }),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.name, "after");
+        assert!(updated.email_verified);
+        assert_eq!(updated.email, user.email);
+
+        // 没有提供任何列时返回当前记录
+        let unchanged = repo
+            .update(user.id, &serde_json::json!({}))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unchanged.name, "after");
+
+        assert!(repo
+            .update(Uuid::new_v4(), &serde_json::json!({ "name": "nobody" }))
+            .await
+            .unwrap()
+            .is_none());
+
+        assert!(repo.delete(user.id).await.unwrap());
+        assert!(!repo.delete(user.id).await.unwrap());
+        assert!(repo.find_by_id(user.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_update_touches_column_with_database_time() {
+        let pool = test_support::pool().await;
+        let repo = users(&pool).touch("updated_at");
+
+        let user: User = repo
+            .insert(&serde_json::json!({
+                "email": test_support::unique_email("repo"),
+                "password_hash": "hash",
+                "name": "before",
+            }))
+            .await
+            .unwrap();
+
+        let updated = repo
+            .update(user.id, &serde_json::json!({ "name": "after" }))
+            .await
+            .unwrap()
+            .unwrap();
+        let now: chrono::DateTime<chrono::Utc> = sqlx::query_scalar("SELECT NOW()")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(updated.updated_at > user.updated_at);
+        assert!(updated.updated_at <= now);
+    }
+}
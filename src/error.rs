@@ -6,18 +6,43 @@
  */
 
 use axum::{
-    http::StatusCode,
+    extract::rejection::{JsonRejection, PathRejection},
+    http::{header::RETRY_AFTER, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
+use serde::Serialize;
 use serde_json::json;
 use thiserror::Error;
 
+use crate::middleware::current_request_id;
+
 /// 应用程序通用结果类型
 ///
 /// 简化错误处理，统一使用 `AppError` 作为错误类型
 pub type Result<T> = std::result::Result<T, AppError>;
 
+/// 字段校验错误
+///
+/// 描述请求中某个字段未通过校验的原因，通过 `AppError::InvalidFields` 返回给客户端。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    /// 字段名
+    pub field: String,
+    /// 错误原因
+    pub message: String,
+}
+
+impl FieldError {
+    /// 创建字段校验错误
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
 /// 应用程序错误枚举
 ///
 /// 定义了应用程序中可能出现的所有错误类型，
@@ -48,6 +73,12 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// 字段校验错误
+    ///
+    /// 请求中一个或多个字段未通过校验，响应体的 `errors` 字段列出每个字段的错误原因
+    #[error("Validation failed for {} field(s)", .0.len())]
+    InvalidFields(Vec<FieldError>),
+
     /// 身份验证错误
     ///
     /// 用户身份验证失败，如密码错误、Token 无效等
@@ -72,6 +103,25 @@ pub enum AppError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// 请求体过大错误
+    ///
+    /// 请求体超过配置的 `max_request_body_bytes`
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    /// 请求频率超限错误
+    ///
+    /// 客户端在限流时间窗口内的请求次数超出限制，
+    /// `retry_after` 为建议客户端等待的秒数（可选）
+    #[error("Rate limited")]
+    RateLimited { retry_after: Option<u64> },
+
+    /// 请求超时错误
+    ///
+    /// 请求处理时间超过配置的 `request_timeout_seconds`
+    #[error("Request timed out")]
+    Timeout,
+
     /// 内部服务器错误
     ///
     /// 其他未预期的系统错误
@@ -79,11 +129,51 @@ pub enum AppError {
     Internal(#[from] anyhow::Error),
 }
 
+impl From<JsonRejection> for AppError {
+    /// 将 `Json<T>` 提取器的拒绝转换为统一的错误响应
+    ///
+    /// - 请求体超过大小限制 -> `PayloadTooLarge`
+    /// - JSON 格式错误或字段不匹配 -> `Validation`（`JSON解析失败: ...`）
+    /// - 缺少 `Content-Type: application/json` 或读取请求体失败 -> `Validation`
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            JsonRejection::JsonDataError(_) | JsonRejection::JsonSyntaxError(_) => {
+                AppError::Validation(format!("JSON解析失败: {}", rejection.body_text()))
+            }
+            JsonRejection::MissingJsonContentType(_) => {
+                AppError::Validation("请求头缺少 Content-Type: application/json".to_string())
+            }
+            _ if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                AppError::PayloadTooLarge("Request body too large".to_string())
+            }
+            _ => AppError::Validation(format!("读取请求体失败: {}", rejection.body_text())),
+        }
+    }
+}
+
+impl From<PathRejection> for AppError {
+    /// 将 `Path<T>` 提取器的拒绝转换为统一的错误响应
+    ///
+    /// - 路径参数无法解析为目标类型 -> `Validation`（`路径参数无效: ...`）
+    /// - 路由定义与提取器不匹配（服务端编程错误） -> `Internal`
+    fn from(rejection: PathRejection) -> Self {
+        if rejection.status().is_client_error() {
+            AppError::Validation(format!("路径参数无效: {}", rejection.body_text()))
+        } else {
+            AppError::Internal(anyhow::anyhow!(
+                "Path extraction failed: {}",
+                rejection.body_text()
+            ))
+        }
+    }
+}
+
 impl IntoResponse for AppError {
     /// 将应用程序错误转换为 HTTP 响应
     ///
     /// 根据错误类型返回相应的 HTTP 状态码和错误消息。
     /// 敏感的错误信息（如数据库错误）会被隐藏，只返回通用的错误消息。
+    /// 经过请求 ID 中间件的请求会在响应体中附带 `request_id` 字段。
     ///
     /// # 错误映射
     ///
@@ -91,10 +181,14 @@ impl IntoResponse for AppError {
     /// - `Jwt` -> 401 Unauthorized  
     /// - `PasswordHash` -> 500 Internal Server Error
     /// - `Validation` -> 400 Bad Request
+    /// - `InvalidFields` -> 400 Bad Request（附带 `errors` 字段列表）
     /// - `Authentication` -> 401 Unauthorized
     /// - `Authorization` -> 403 Forbidden
     /// - `NotFound` -> 404 Not Found
     /// - `Conflict` -> 409 Conflict
+    /// - `PayloadTooLarge` -> 413 Payload Too Large
+    /// - `RateLimited` -> 429 Too Many Requests（附带 `Retry-After` 头）
+    /// - `Timeout` -> 504 Gateway Timeout
     /// - `Internal` -> 500 Internal Server Error
     fn into_response(self) -> Response {
         let (status, error_message) = match &self {
@@ -116,6 +210,9 @@ impl IntoResponse for AppError {
             // 验证错误：返回具体的验证失败原因
             AppError::Validation(msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
 
+            // 字段校验错误：具体原因在 `errors` 字段中返回
+            AppError::InvalidFields(_) => (StatusCode::BAD_REQUEST, "Validation failed"),
+
             // 身份验证错误：用户名密码错误等
             AppError::Authentication(msg) => (StatusCode::UNAUTHORIZED, msg.as_str()),
 
@@ -128,6 +225,15 @@ impl IntoResponse for AppError {
             // 资源冲突错误：如邮箱已存在
             AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.as_str()),
 
+            // 请求体过大错误
+            AppError::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg.as_str()),
+
+            // 限流错误：请求过于频繁
+            AppError::RateLimited { .. } => (StatusCode::TOO_MANY_REQUESTS, "Too many requests"),
+
+            // 超时错误：处理器未在限定时间内完成
+            AppError::Timeout => (StatusCode::GATEWAY_TIMEOUT, "Request timed out"),
+
             // 内部错误：记录详细错误日志
             AppError::Internal(err) => {
                 tracing::error!("Internal error: {}", err);
@@ -136,10 +242,68 @@ impl IntoResponse for AppError {
         };
 
         // 构造 JSON 错误响应
-        let body = Json(json!({
+        let mut body = json!({
             "error": error_message,
-        }));
+        });
+
+        // 附带请求 ID，便于根据错误响应定位服务端日志
+        if let Some(request_id) = current_request_id() {
+            body["request_id"] = json!(request_id);
+        }
+
+        // 字段校验错误：列出每个字段的错误原因
+        if let AppError::InvalidFields(errors) = &self {
+            body["errors"] = json!(errors);
+        }
+
+        // 限流错误：在响应体和 Retry-After 头中告知客户端重试等待时间
+        if let AppError::RateLimited {
+            retry_after: Some(seconds),
+        } = &self
+        {
+            body["retry_after"] = json!(seconds);
+            return (status, [(RETRY_AFTER, seconds.to_string())], Json(body)).into_response();
+        }
+
+        (status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_response() {
+        let response = AppError::RateLimited {
+            retry_after: Some(30),
+        }
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[RETRY_AFTER], "30");
+
+        let body = json_body(response).await;
+        assert_eq!(body["error"], "Too many requests");
+        assert_eq!(body["retry_after"], 30);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_response_without_retry_after() {
+        let response = AppError::RateLimited { retry_after: None }.into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().get(RETRY_AFTER).is_none());
 
-        (status, body).into_response()
+        let body = json_body(response).await;
+        assert_eq!(body["error"], "Too many requests");
+        assert!(body.get("retry_after").is_none());
     }
 }
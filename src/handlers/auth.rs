@@ -6,50 +6,31 @@
  */
 
 use axum::{
-    extract::Request,
-    extract::State,
-    http::header::{AUTHORIZATION, USER_AGENT},
+    extract::{Request, State},
+    http::header::AUTHORIZATION,
     Json,
 };
 use uuid::Uuid;
 
 use crate::{
     error::{AppError, Result},
-    models::{AuthResponse, CreateUserRequest, LoginRequest},
+    middleware::{AppJson, AppPath, DeviceContext},
+    models::{
+        AuthResponse, CreateUserRequest, ForgotPasswordRequest, LoginRequest, RefreshTokenRequest,
+        ResetPasswordRequest, SendVerificationRequest, User, UserResponse, VerifyEmailRequest,
+    },
     routes::AppState,
-    services::{TokenService, UserService},
-    utils::DeviceInfo,
+    services::{
+        AuditAction, AuditEvent, AuditService, PasswordPolicy, PasswordResetService, SessionPolicy,
+        TokenService, UserService, VerificationService, WebhookEvent,
+    },
+    utils::AuthUser,
 };
 
-/// 从HTTP请求中提取设备信息
-///
-/// # 参数
-///
-/// * `request` - HTTP 请求对象
-///
-/// # 返回值
-///
-/// 返回解析后的设备信息
-fn extract_device_info(request: &Request) -> DeviceInfo {
-    // 从请求头中获取 User-Agent
-    let user_agent = request
-        .headers()
-        .get(USER_AGENT)
-        .and_then(|header| header.to_str().ok())
-        .unwrap_or("Unknown");
-
-    // 从请求头中获取设备类型提示（可选的自定义头部）
-    let device_type_hint = request
-        .headers()
-        .get("X-Device-Type")
-        .and_then(|header| header.to_str().ok());
-
-    DeviceInfo::from_user_agent(user_agent, device_type_hint)
-}
-
 /// 用户注册处理器
 ///
 /// 处理用户注册请求，创建新用户账户并返回 JWT Token。
+/// 启用 `REQUIRE_EMAIL_VERIFICATION` 时不签发令牌，只返回用户信息，用户验证邮箱后再登录。
 ///
 /// # 请求
 ///
@@ -66,10 +47,11 @@ fn extract_device_info(request: &Request) -> DeviceInfo {
 ///
 /// # 响应
 ///
-/// 成功时返回 `AuthResponse`，包含 JWT Token 和用户信息：
+/// 成功时返回 `AuthResponse`，包含 JWT Token、刷新令牌和用户信息：
 /// ```json
 /// {
 ///   "token": "jwt_token_here",
+///   "refresh_token": "refresh_token_here",
 ///   "user": {
 ///     "id": "user_uuid",
 ///     "email": "user@example.com",
@@ -82,52 +64,63 @@ fn extract_device_info(request: &Request) -> DeviceInfo {
 /// # 错误
 ///
 /// - `409 Conflict`: 邮箱已存在
-/// - `400 Bad Request`: 请求数据格式错误
+/// - `400 Bad Request`: 请求数据格式错误，或邮箱、名称、密码未通过校验（`errors` 中列出字段）
+/// - `413 Payload Too Large`: 请求体超过 `MAX_REQUEST_BODY_BYTES`
 /// - `500 Internal Server Error`: 服务器内部错误
 ///
 /// # 参数
 ///
 /// * `app_state` - 应用程序状态，包含数据库连接池和配置
-/// * `request` - 用户注册请求数据
+/// * `device_context` - 从请求头提取的设备信息和客户端 IP
+/// * `create_user_request` - 用户注册请求数据
 pub async fn register(
     State(app_state): State<AppState>,
-    request: Request,
+    DeviceContext(device_info, ip_address): DeviceContext,
+    AppJson(create_user_request): AppJson<CreateUserRequest>,
 ) -> Result<Json<AuthResponse>> {
-    // 提取设备信息
-    let device_info = extract_device_info(&request);
-
-    // 提取IP地址（从连接信息或代理头部）
-    let ip_address = request
-        .headers()
-        .get("X-Forwarded-For")
-        .or_else(|| request.headers().get("X-Real-IP"))
-        .and_then(|header| header.to_str().ok())
-        .map(|s| s.split(',').next().unwrap_or(s).trim().to_string());
-
-    // 提取JSON请求体
-    let (_, body) = request.into_parts();
-    let bytes = axum::body::to_bytes(body, usize::MAX)
-        .await
-        .map_err(|e| AppError::Validation(format!("读取请求体失败: {}", e)))?;
-    let create_user_request: CreateUserRequest = serde_json::from_slice(&bytes)
-        .map_err(|e| AppError::Validation(format!("JSON解析失败: {}", e)))?;
-
     // 调用用户服务创建新用户
-    let user = UserService::create_user(&app_state.pool, create_user_request).await?;
+    let user = UserService::create_user(
+        &app_state.pool,
+        create_user_request,
+        PasswordPolicy::from(&app_state.config),
+    )
+    .await?;
 
-    // 使用 TokenService 生成并存储 token 到 Redis
-    let token = TokenService::create_token(
+    // 通知外部系统有新用户注册（后台投递，不影响注册结果）；
+    // 只有显式开启 WEBHOOK_INCLUDE_PII 时才发送邮箱和姓名
+    let mut webhook_data = serde_json::json!({ "user_id": user.id });
+    if app_state.webhooks.include_pii() {
+        webhook_data["email"] = serde_json::json!(user.email);
+        webhook_data["name"] = serde_json::json!(user.name);
+    }
+    app_state
+        .webhooks
+        .notify(WebhookEvent::UserRegistered, webhook_data);
+
+    // 需要先验证邮箱时不签发令牌
+    if app_state.config.require_email_verification && !user.email_verified {
+        return Ok(Json(AuthResponse {
+            token: None,
+            refresh_token: None,
+            user: user.into(),
+        }));
+    }
+
+    // 使用 TokenService 生成访问令牌和刷新令牌并存储到 Redis
+    let token_pair = TokenService::create_token_pair(
         &app_state.redis,
-        user.id,
-        &app_state.config.jwt_secret,
+        &AuthUser::from(&user),
+        &app_state.jwt_keys,
         device_info,
         ip_address,
+        SessionPolicy::from(&app_state.config),
     )
     .await?;
 
     // 构造响应数据
     let response = AuthResponse {
-        token,
+        token: Some(token_pair.access_token),
+        refresh_token: Some(token_pair.refresh_token),
         user: user.into(), // 转换为 UserResponse，隐藏敏感信息
     };
 
@@ -152,10 +145,11 @@ pub async fn register(
 ///
 /// # 响应
 ///
-/// 成功时返回 `AuthResponse`，包含 JWT Token 和用户信息：
+/// 成功时返回 `AuthResponse`，包含 JWT Token、刷新令牌和用户信息：
 /// ```json
 /// {
 ///   "token": "jwt_token_here",
+///   "refresh_token": "refresh_token_here",
 ///   "user": {
 ///     "id": "user_uuid",
 ///     "email": "user@example.com",
@@ -168,52 +162,125 @@ pub async fn register(
 /// # 错误
 ///
 /// - `401 Unauthorized`: 邮箱或密码错误
+/// - `403 Forbidden`: 启用 `REQUIRE_EMAIL_VERIFICATION` 时邮箱尚未验证
 /// - `400 Bad Request`: 请求数据格式错误
+/// - `413 Payload Too Large`: 请求体超过 `MAX_REQUEST_BODY_BYTES`
 /// - `500 Internal Server Error`: 服务器内部错误
 ///
 /// # 参数
 ///
 /// * `app_state` - 应用程序状态，包含数据库连接池和配置
-/// * `request` - 用户登录请求数据
+/// * `device_context` - 从请求头提取的设备信息和客户端 IP
+/// * `login_request` - 用户登录请求数据
 pub async fn login(
     State(app_state): State<AppState>,
-    request: Request,
+    DeviceContext(device_info, ip_address): DeviceContext,
+    AppJson(login_request): AppJson<LoginRequest>,
 ) -> Result<Json<AuthResponse>> {
-    // 提取设备信息
-    let device_info = extract_device_info(&request);
-
-    // 提取IP地址（从连接信息或代理头部）
-    let ip_address = request
-        .headers()
-        .get("X-Forwarded-For")
-        .or_else(|| request.headers().get("X-Real-IP"))
-        .and_then(|header| header.to_str().ok())
-        .map(|s| s.split(',').next().unwrap_or(s).trim().to_string());
+    // 验证用户凭据
+    let user =
+        UserService::authenticate_user(&app_state.pool, login_request, &app_state.config.argon2)
+            .await?;
 
-    // 提取JSON请求体
-    let (_, body) = request.into_parts();
-    let bytes = axum::body::to_bytes(body, usize::MAX)
-        .await
-        .map_err(|e| AppError::Validation(format!("读取请求体失败: {}", e)))?;
-    let login_request: LoginRequest = serde_json::from_slice(&bytes)
-        .map_err(|e| AppError::Validation(format!("JSON解析失败: {}", e)))?;
+    // 按配置要求用户先验证邮箱
+    ensure_email_verified(&app_state, &user)?;
 
-    // 验证用户凭据
-    let user = UserService::authenticate_user(&app_state.pool, login_request).await?;
+    // 设备信息会移交给令牌，先构造登录审计事件，登录成功后再记录
+    let audit_event = AuditEvent::new(
+        user.id,
+        AuditAction::Login,
+        &device_info,
+        ip_address.clone(),
+    );
 
-    // 使用 TokenService 生成并存储 token 到 Redis（会自动撤销同设备类型的其他登录）
-    let token = TokenService::create_token(
+    // 使用 TokenService 生成令牌对并存储到 Redis（按配置撤销同设备类型的其他登录）
+    let token_pair = TokenService::create_token_pair(
         &app_state.redis,
-        user.id,
-        &app_state.config.jwt_secret,
+        &AuthUser::from(&user),
+        &app_state.jwt_keys,
         device_info,
         ip_address,
+        SessionPolicy::from(&app_state.config),
+    )
+    .await?;
+
+    AuditService::record(
+        &app_state.redis,
+        app_state.config.audit_log_max_events,
+        &audit_event,
+    )
+    .await;
+
+    // 构造响应数据
+    let response = AuthResponse {
+        token: Some(token_pair.access_token),
+        refresh_token: Some(token_pair.refresh_token),
+        user: user.into(), // 转换为 UserResponse，隐藏敏感信息
+    };
+
+    Ok(Json(response))
+}
+
+/// 刷新访问令牌处理器
+///
+/// 使用刷新令牌换取新的访问令牌和刷新令牌。
+/// 旧的刷新令牌会立即作废（轮换），不能重复使用。
+///
+/// # 请求
+///
+/// - **方法**: POST
+/// - **路径**: `/api/auth/refresh`
+/// - **请求体**: JSON 格式的 `RefreshTokenRequest`
+///   ```json
+///   {
+///     "refresh_token": "refresh_token_here"
+///   }
+///   ```
+///
+/// # 响应
+///
+/// 成功时返回 `AuthResponse`，包含新的 JWT Token、刷新令牌和用户信息
+///
+/// # 错误
+///
+/// - `401 Unauthorized`: 刷新令牌无效、已过期或已被使用
+/// - `403 Forbidden`: 启用 `REQUIRE_EMAIL_VERIFICATION` 时邮箱尚未验证
+/// - `400 Bad Request`: 请求数据格式错误
+/// - `500 Internal Server Error`: 服务器内部错误
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含数据库连接池、Redis 管理器和配置
+/// * `request` - 刷新令牌请求数据
+pub async fn refresh(
+    State(app_state): State<AppState>,
+    AppJson(request): AppJson<RefreshTokenRequest>,
+) -> Result<Json<AuthResponse>> {
+    // 校验并轮换刷新令牌
+    let token_info =
+        TokenService::consume_refresh_token(&app_state.redis, &request.refresh_token).await?;
+
+    // 获取最新的用户信息，使新的访问令牌携带当前的邮箱和角色
+    let user = UserService::get_user_by_id(&app_state.pool, token_info.user_id).await?;
+
+    // 启用邮箱验证前签发的令牌不能继续续期
+    ensure_email_verified(&app_state, &user)?;
+
+    // 使用原设备信息签发新的令牌对
+    let token_pair = TokenService::create_token_pair(
+        &app_state.redis,
+        &AuthUser::from(&user),
+        &app_state.jwt_keys,
+        token_info.device_info,
+        token_info.ip_address,
+        SessionPolicy::from(&app_state.config),
     )
     .await?;
 
     // 构造响应数据
     let response = AuthResponse {
-        token,
+        token: Some(token_pair.access_token),
+        refresh_token: Some(token_pair.refresh_token),
         user: user.into(), // 转换为 UserResponse，隐藏敏感信息
     };
 
@@ -229,7 +296,7 @@ pub async fn login(
 /// - **方法**: POST
 /// - **路径**: `/api/auth/logout`
 /// - **请求头**: 必须包含有效的 Authorization header
-///   ```
+///   ```text
 ///   Authorization: Bearer <jwt_token>
 ///   ```
 ///
@@ -250,9 +317,11 @@ pub async fn login(
 /// # 参数
 ///
 /// * `app_state` - 应用程序状态，包含 Redis 管理器和配置
+/// * `device_context` - 从请求头提取的设备信息和客户端 IP（用于审计日志）
 /// * `request` - HTTP 请求对象，用于提取 Authorization header
 pub async fn logout(
     State(app_state): State<AppState>,
+    DeviceContext(device_info, ip_address): DeviceContext,
     request: Request,
 ) -> Result<Json<serde_json::Value>> {
     // 从请求头中提取 Authorization 字段
@@ -268,14 +337,21 @@ pub async fn logout(
     })?;
 
     // 先验证 token 以获取用户 ID
-    let claims =
-        TokenService::verify_token(&app_state.redis, token, &app_state.config.jwt_secret).await?;
+    let claims = TokenService::verify_token(&app_state.redis, token, &app_state.jwt_keys).await?;
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Authentication("Invalid user ID in token".to_string()))?;
 
     // 撤销当前 token
     TokenService::revoke_token(&app_state.redis, token, user_id).await?;
 
+    // 记录审计事件
+    AuditService::record(
+        &app_state.redis,
+        app_state.config.audit_log_max_events,
+        &AuditEvent::new(user_id, AuditAction::Logout, &device_info, ip_address),
+    )
+    .await;
+
     // 返回成功响应
     Ok(Json(serde_json::json!({
         "message": "退出登录成功"
@@ -306,9 +382,11 @@ pub async fn logout(
 /// # 参数
 ///
 /// * `app_state` - 应用程序状态
+/// * `device_context` - 从请求头提取的设备信息和客户端 IP（用于审计日志）
 /// * `request` - HTTP 请求对象
 pub async fn logout_all(
     State(app_state): State<AppState>,
+    DeviceContext(device_info, ip_address): DeviceContext,
     request: Request,
 ) -> Result<Json<serde_json::Value>> {
     // 从请求头中提取 Authorization 字段
@@ -324,8 +402,7 @@ pub async fn logout_all(
     })?;
 
     // 先验证 token 以获取用户 ID
-    let claims =
-        TokenService::verify_token(&app_state.redis, token, &app_state.config.jwt_secret).await?;
+    let claims = TokenService::verify_token(&app_state.redis, token, &app_state.jwt_keys).await?;
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Authentication("Invalid user ID in token".to_string()))?;
 
@@ -335,6 +412,23 @@ pub async fn logout_all(
     // 撤销用户的所有 token
     TokenService::revoke_all_user_tokens(&app_state.redis, user_id).await?;
 
+    // 记录审计事件
+    AuditService::record(
+        &app_state.redis,
+        app_state.config.audit_log_max_events,
+        &AuditEvent::new(user_id, AuditAction::LogoutAll, &device_info, ip_address),
+    )
+    .await;
+
+    app_state.webhooks.notify(
+        WebhookEvent::SessionRevoked,
+        serde_json::json!({
+            "user_id": user_id,
+            "device_type": null,
+            "revoked_count": token_count,
+        }),
+    );
+
     // 返回成功响应
     Ok(Json(serde_json::json!({
         "message": "已撤销所有登录会话",
@@ -360,15 +454,17 @@ pub async fn logout_all(
 ///   "sessions": [
 ///     {
 ///       "device_type": "web",
-///       "device_name": "Chrome on Windows 10",
+///       "device_name": "Chrome 119 on Windows 10",
 ///       "created_at": "2023-01-01T10:00:00Z",
+///       "last_used_at": "2023-01-01T12:30:00Z",
 ///       "ip_address": "192.168.1.100",
 ///       "is_current": true
 ///     },
 ///     {
 ///       "device_type": "mobile",
-///       "device_name": "iOS Device",
+///       "device_name": "iOS 17 Device",
 ///       "created_at": "2023-01-01T09:00:00Z",
+///       "last_used_at": "2023-01-01T09:05:00Z",
 ///       "ip_address": "192.168.1.101",
 ///       "is_current": false
 ///     }
@@ -397,25 +493,31 @@ pub async fn get_sessions(
     })?;
 
     // 先验证 token 以获取用户 ID
-    let claims =
-        TokenService::verify_token(&app_state.redis, token, &app_state.config.jwt_secret).await?;
+    let claims = TokenService::verify_token(&app_state.redis, token, &app_state.jwt_keys).await?;
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Authentication("Invalid user ID in token".to_string()))?;
 
-    // 获取用户所有设备的活跃会话
-    let device_sessions = TokenService::get_user_device_sessions(&app_state.redis, user_id).await?;
+    // 获取用户所有设备的活跃会话，并标记当前请求所使用的会话
+    let device_sessions =
+        TokenService::get_user_device_sessions_with_current(&app_state.redis, user_id, token)
+            .await?;
 
     // 转换为响应格式
     let mut sessions = Vec::new();
-    for (device_type, token_info) in device_sessions {
+    for (device_type, token_info, is_current) in device_sessions {
         let session = serde_json::json!({
             "device_type": device_type.to_string(),
             "device_name": token_info.device_info.display_name(),
             "created_at": chrono::DateTime::from_timestamp(token_info.created_at, 0)
                 .unwrap_or_default()
                 .to_rfc3339(),
+            // 旧会话没有记录最后使用时间时返回 null
+            "last_used_at": (token_info.last_used_at > 0)
+                .then(|| chrono::DateTime::from_timestamp(token_info.last_used_at, 0))
+                .flatten()
+                .map(|time| time.to_rfc3339()),
             "ip_address": token_info.ip_address,
-            "is_current": false // 后面可以通过比较token来确定是否为当前会话
+            "is_current": is_current
         });
         sessions.push(session);
     }
@@ -441,18 +543,21 @@ pub async fn get_sessions(
 /// 成功时返回撤销结果：
 /// ```json
 /// {
-///   "message": "已撤销Web设备的登录会话"
+///   "message": "已撤销Web设备的登录会话",
+///   "revoked_count": 1
 /// }
 /// ```
 ///
 /// # 参数
 ///
 /// * `app_state` - 应用程序状态
-/// * `request` - HTTP 请求对象
+/// * `device_context` - 从请求头提取的设备信息和客户端 IP（用于审计日志）
 /// * `device_type` - 要撤销的设备类型
+/// * `request` - HTTP 请求对象
 pub async fn logout_device(
     State(app_state): State<AppState>,
-    axum::extract::Path(device_type_str): axum::extract::Path<String>,
+    DeviceContext(device_info, ip_address): DeviceContext,
+    AppPath(device_type_str): AppPath<String>,
     request: Request,
 ) -> Result<Json<serde_json::Value>> {
     // 从请求头中提取 Authorization 字段
@@ -468,8 +573,7 @@ pub async fn logout_device(
     })?;
 
     // 先验证 token 以获取用户 ID
-    let claims =
-        TokenService::verify_token(&app_state.redis, token, &app_state.config.jwt_secret).await?;
+    let claims = TokenService::verify_token(&app_state.redis, token, &app_state.jwt_keys).await?;
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Authentication("Invalid user ID in token".to_string()))?;
 
@@ -477,17 +581,350 @@ pub async fn logout_device(
     let device_type = crate::utils::DeviceType::from_str(&device_type_str);
 
     // 撤销指定设备类型的token
-    TokenService::revoke_device_tokens(&app_state.redis, user_id, &device_type).await?;
+    let revoked_count =
+        TokenService::revoke_device_tokens(&app_state.redis, user_id, &device_type).await?;
+
+    // 记录审计事件
+    AuditService::record(
+        &app_state.redis,
+        app_state.config.audit_log_max_events,
+        &AuditEvent::new(
+            user_id,
+            AuditAction::SessionRevoke,
+            &device_info,
+            ip_address,
+        ),
+    )
+    .await;
+
+    app_state.webhooks.notify(
+        WebhookEvent::SessionRevoked,
+        serde_json::json!({
+            "user_id": user_id,
+            "device_type": device_type.to_string(),
+            "revoked_count": revoked_count,
+        }),
+    );
 
     let device_name = match device_type {
         crate::utils::DeviceType::Web => "Web",
         crate::utils::DeviceType::Mobile => "移动",
         crate::utils::DeviceType::Desktop => "桌面",
         crate::utils::DeviceType::Api => "API",
+        crate::utils::DeviceType::Bot => "爬虫",
     };
 
     // 返回成功响应
     Ok(Json(serde_json::json!({
-        "message": format!("已撤销{}设备的登录会话", device_name)
+        "message": format!("已撤销{}设备的登录会话", device_name),
+        "revoked_count": revoked_count
+    })))
+}
+
+/// 发送邮箱验证码处理器
+///
+/// 为未验证邮箱的用户（重新）生成验证码，旧的验证码随之失效。
+/// 无论邮箱是否存在或已验证都返回相同的响应，避免泄露用户信息。
+///
+/// # 请求
+///
+/// - **方法**: POST
+/// - **路径**: `/api/auth/send-verification`
+/// - **请求体**: JSON 格式的 `SendVerificationRequest`
+///   ```json
+///   {
+///     "email": "user@example.com"
+///   }
+///   ```
+///
+/// # 响应
+///
+/// ```json
+/// {
+///   "message": "如果该邮箱已注册且未验证，验证码已发送"
+/// }
+/// ```
+///
+/// # 错误
+///
+/// - `400 Bad Request`: 请求数据格式错误
+/// - `500 Internal Server Error`: 服务器内部错误
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含数据库连接池和 Redis 管理器
+/// * `request` - 发送验证码请求数据
+pub async fn send_verification(
+    State(app_state): State<AppState>,
+    AppJson(request): AppJson<SendVerificationRequest>,
+) -> Result<Json<serde_json::Value>> {
+    VerificationService::send_verification_code(
+        &app_state.pool,
+        &app_state.redis,
+        app_state.email.as_ref(),
+        &request.email,
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "如果该邮箱已注册且未验证，验证码已发送"
+    })))
+}
+
+/// 验证邮箱处理器
+///
+/// 校验邮箱验证码，成功后将用户标记为邮箱已验证。验证码只能使用一次。
+///
+/// # 请求
+///
+/// - **方法**: POST
+/// - **路径**: `/api/auth/verify-email`
+/// - **请求体**: JSON 格式的 `VerifyEmailRequest`
+///   ```json
+///   {
+///     "email": "user@example.com",
+///     "code": "123456"
+///   }
+///   ```
+///
+/// # 响应
+///
+/// 成功时返回更新后的 `UserResponse`（`email_verified` 为 `true`）
+///
+/// # 错误
+///
+/// - `400 Bad Request`: 验证码错误、已过期或请求数据格式错误
+/// - `404 Not Found`: 用户不存在
+/// - `500 Internal Server Error`: 服务器内部错误
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含数据库连接池和 Redis 管理器
+/// * `request` - 验证邮箱请求数据
+pub async fn verify_email(
+    State(app_state): State<AppState>,
+    AppJson(request): AppJson<VerifyEmailRequest>,
+) -> Result<Json<UserResponse>> {
+    let user = VerificationService::verify_email(
+        &app_state.pool,
+        &app_state.redis,
+        &request.email,
+        &request.code,
+    )
+    .await?;
+
+    Ok(Json(user.into()))
+}
+
+/// 忘记密码处理器
+///
+/// 为邮箱对应的用户生成一次性密码重置令牌。为避免泄露邮箱是否已注册，
+/// 无论邮箱是否存在都返回相同的响应。
+///
+/// # 请求
+///
+/// - **方法**: POST
+/// - **路径**: `/api/auth/forgot-password`
+/// - **请求体**: JSON 格式的 `ForgotPasswordRequest`
+///   ```json
+///   {
+///     "email": "user@example.com"
+///   }
+///   ```
+///
+/// # 响应
+///
+/// 始终返回：
+/// ```json
+/// {
+///   "message": "如果该邮箱已注册，密码重置邮件已发送"
+/// }
+/// ```
+///
+/// # 错误
+///
+/// - `400 Bad Request`: 请求数据格式错误
+/// - `429 Too Many Requests`: 该邮箱申请过于频繁（每小时最多 3 次）
+/// - `500 Internal Server Error`: 服务器内部错误
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含数据库连接池和 Redis 管理器
+/// * `request` - 忘记密码请求数据
+pub async fn forgot_password(
+    State(app_state): State<AppState>,
+    AppJson(request): AppJson<ForgotPasswordRequest>,
+) -> Result<Json<serde_json::Value>> {
+    PasswordResetService::request_reset(
+        &app_state.pool,
+        &app_state.redis,
+        app_state.email.as_ref(),
+        &request.email,
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "如果该邮箱已注册，密码重置邮件已发送"
+    })))
+}
+
+/// 重置密码处理器
+///
+/// 校验密码重置令牌并设置新密码。令牌只能使用一次，
+/// 重置成功后撤销该用户的所有登录会话。
+///
+/// # 请求
+///
+/// - **方法**: POST
+/// - **路径**: `/api/auth/reset-password`
+/// - **请求体**: JSON 格式的 `ResetPasswordRequest`
+///   ```json
+///   {
+///     "email": "user@example.com",
+///     "token": "reset_token_here",
+///     "new_password": "N3w-Secure-Passw0rd"
+///   }
+///   ```
+///
+/// # 响应
+///
+/// 成功时返回：
+/// ```json
+/// {
+///   "message": "密码重置成功，请重新登录"
+/// }
+/// ```
+///
+/// # 错误
+///
+/// - `400 Bad Request`: 令牌错误、已过期，新密码强度不足或请求数据格式错误
+/// - `500 Internal Server Error`: 服务器内部错误
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含数据库连接池和 Redis 管理器
+/// * `request` - 重置密码请求数据
+pub async fn reset_password(
+    State(app_state): State<AppState>,
+    AppJson(request): AppJson<ResetPasswordRequest>,
+) -> Result<Json<serde_json::Value>> {
+    PasswordResetService::reset_password(
+        &app_state.pool,
+        &app_state.redis,
+        &request.email,
+        &request.token,
+        &request.new_password,
+        PasswordPolicy::from(&app_state.config),
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "密码重置成功，请重新登录"
     })))
 }
+
+/// 启用 `REQUIRE_EMAIL_VERIFICATION` 时检查用户邮箱是否已验证
+///
+/// # 错误
+///
+/// - `AppError::Authorization`: 邮箱尚未验证
+fn ensure_email_verified(app_state: &AppState, user: &User) -> Result<()> {
+    if app_state.config.require_email_verification && !user.email_verified {
+        return Err(AppError::Authorization("Email not verified".to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        extract::DefaultBodyLimit,
+        http::{header::CONTENT_TYPE, Request, StatusCode},
+        response::Response,
+        routing::post,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    /// 与 `login` 使用相同提取器的处理器，只回显解析结果，不访问数据库和 Redis
+    async fn echo_login(
+        DeviceContext(device_info, ip_address): DeviceContext,
+        AppJson(login_request): AppJson<LoginRequest>,
+    ) -> Result<Json<serde_json::Value>> {
+        Ok(Json(serde_json::json!({
+            "email": login_request.email,
+            "device_type": device_info.device_type.to_string(),
+            "ip_address": ip_address,
+        })))
+    }
+
+    async fn post_login(content_type: Option<&str>, body: impl Into<Body>) -> Response {
+        let app = Router::new()
+            .route("/login", post(echo_login))
+            .layer(DefaultBodyLimit::max(1024));
+
+        let mut request = Request::post("/login")
+            .header("X-Device-Type", "desktop")
+            .header("X-Forwarded-For", "203.0.113.7");
+        if let Some(content_type) = content_type {
+            request = request.header(CONTENT_TYPE, content_type);
+        }
+
+        app.oneshot(request.body(body.into()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_valid_json_body() {
+        let response = post_login(
+            Some("application/json"),
+            r#"{"email":"user@example.com","password":"password123"}"#,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(response).await,
+            serde_json::json!({
+                "email": "user@example.com",
+                "device_type": "desktop",
+                "ip_address": "203.0.113.7",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_body() {
+        let response = post_login(Some("application/json"), r#"{"email":"#).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert!(body["error"].as_str().unwrap().starts_with("JSON解析失败"));
+
+        // 缺少必填字段同样视为 JSON 解析失败
+        let response =
+            post_login(Some("application/json"), r#"{"email":"user@example.com"}"#).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert!(body["error"].as_str().unwrap().starts_with("JSON解析失败"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_content_type_and_oversized_body() {
+        let response = post_login(None, r#"{"email":"a@b.c","password":"x"}"#).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let oversized = serde_json::json!({ "email": "a@b.c", "password": "x".repeat(2048) });
+        let response = post_login(Some("application/json"), oversized.to_string()).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -0,0 +1,375 @@
+/*!
+ * 事件流处理器
+ *
+ * 通过服务器发送事件（SSE）向客户端实时推送当前用户的审计事件，
+ * 适用于只需要服务端单向推送的仪表盘等场景。需要身份验证。
+ */
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+    Extension,
+};
+use chrono::Utc;
+use futures_util::{
+    future,
+    stream::{self, Stream, StreamExt},
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    middleware::bearer_token,
+    redis::RedisManager,
+    routes::AppState,
+    services::{AuditEvent, AuditService, TokenService},
+    utils::{decode_jwt, AuthUser},
+};
+
+/// keep-alive 注释的发送间隔，防止代理和负载均衡器关闭空闲连接
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 检查会话是否已被撤销的间隔
+///
+/// 密码重置等撤销方式不产生审计事件，需要定期检查
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 每个用户允许同时打开的事件流数量
+///
+/// 每个事件流占用一个独立的 Redis 订阅连接
+pub const MAX_EVENT_STREAMS_PER_USER: usize = 3;
+
+/// 审计事件在 SSE 中的事件名
+const AUDIT_EVENT_NAME: &str = "audit";
+
+/// 审计事件流处理器
+///
+/// 订阅当前用户的审计频道，每产生一条新的审计事件就推送一个 `audit` 事件，
+/// 空闲时定期发送 keep-alive 注释保持连接。只推送连接建立之后的事件，
+/// 历史事件请使用 `GET /api/profile/audit`。
+///
+/// 事件流在访问令牌过期或被撤销时结束，客户端需要使用新的令牌重新连接。
+/// 每个用户最多同时打开 `MAX_EVENT_STREAMS_PER_USER` 个事件流。
+///
+/// # 请求
+///
+/// - **方法**: GET
+/// - **路径**: `/api/events`
+/// - **请求头**: `Authorization: Bearer <jwt_token>`
+///
+/// # 响应
+///
+/// `text/event-stream` 格式的事件流：
+/// ```text
+/// event: audit
+/// data: {"timestamp":1700000000,"user_id":"user_uuid","action":"login","ip":"203.0.113.7","device":"Chrome 119 on Windows 10"}
+/// ```
+///
+/// # 错误
+///
+/// - `401 Unauthorized`: JWT Token 无效或已过期
+/// - `429 Too Many Requests`: 该用户打开的事件流数量已达上限
+/// - `500 Internal Server Error`: 订阅 Redis 频道失败
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含 Redis 管理器和事件流并发限制
+/// * `auth_user` - 从 JWT Token 中提取的用户信息（由身份验证中间件注入）
+/// * `headers` - 请求头，用于读取当前的访问令牌
+pub async fn stream_events(
+    State(app_state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = std::result::Result<Event, Infallible>>>> {
+    let permit = app_state
+        .event_streams
+        .try_acquire(auth_user.id)
+        .ok_or(AppError::RateLimited { retry_after: None })?;
+
+    let token = bearer_token(&headers)?
+        .ok_or_else(|| AppError::Authentication("Missing authorization header".to_string()))?
+        .to_string();
+    let claims = decode_jwt(&token, &app_state.jwt_keys)?;
+    let expires_in = Duration::from_secs((claims.exp - Utc::now().timestamp()).max(0) as u64);
+
+    let events = AuditService::subscribe(&app_state.redis, auth_user.id).await?;
+    let events = session_events(
+        app_state.redis.clone(),
+        token,
+        events,
+        expires_in,
+        SESSION_CHECK_INTERVAL,
+    )
+    // 事件流被丢弃时释放并发名额
+    .inspect(move |_| {
+        let _ = &permit;
+    });
+
+    Ok(audit_event_sse(events, KEEP_ALIVE_INTERVAL))
+}
+
+/// 事件流并发限制
+///
+/// 记录每个用户当前打开的事件流数量，超出上限时拒绝新的连接。
+#[derive(Clone)]
+pub struct EventStreamLimiter {
+    max_per_user: usize,
+    open: Arc<Mutex<HashMap<Uuid, usize>>>,
+}
+
+impl EventStreamLimiter {
+    /// 创建每个用户最多同时打开 `max_per_user` 个事件流的限制器
+    pub fn new(max_per_user: usize) -> Self {
+        Self {
+            max_per_user,
+            open: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 为用户占用一个事件流名额
+    ///
+    /// 名额在返回的 `EventStreamPermit` 被丢弃时释放，已达上限时返回 `None`。
+    pub fn try_acquire(&self, user_id: Uuid) -> Option<EventStreamPermit> {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        let count = open.entry(user_id).or_insert(0);
+        if *count >= self.max_per_user {
+            return None;
+        }
+        *count += 1;
+
+        Some(EventStreamPermit {
+            limiter: self.clone(),
+            user_id,
+        })
+    }
+}
+
+/// 事件流名额，被丢弃时归还给 `EventStreamLimiter`
+pub struct EventStreamPermit {
+    limiter: EventStreamLimiter,
+    user_id: Uuid,
+}
+
+impl Drop for EventStreamPermit {
+    fn drop(&mut self) {
+        let mut open = self.limiter.open.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = open.get_mut(&self.user_id) {
+            *count -= 1;
+            if *count == 0 {
+                open.remove(&self.user_id);
+            }
+        }
+    }
+}
+
+/// 会话事件流中的信号
+enum SessionSignal {
+    /// 收到审计事件
+    Event(AuditEvent),
+    /// 定期检查会话
+    Check,
+    /// 订阅已关闭
+    Closed,
+}
+
+/// 只在会话有效期内转发审计事件
+///
+/// 访问令牌过期时结束事件流；每收到一条审计事件以及每隔 `check_interval`
+/// 检查令牌是否已被撤销（退出登录、会话被淘汰、密码重置等），撤销后立即结束。
+/// 无法确认会话状态（如 Redis 不可用）时同样结束。
+fn session_events<S>(
+    redis: RedisManager,
+    token: String,
+    events: S,
+    expires_in: Duration,
+    check_interval: Duration,
+) -> impl Stream<Item = AuditEvent> + Send + 'static
+where
+    S: Stream<Item = AuditEvent> + Send + 'static,
+{
+    let checks = stream::unfold(
+        tokio::time::interval_at(tokio::time::Instant::now() + check_interval, check_interval),
+        |mut interval| async move {
+            interval.tick().await;
+            Some((SessionSignal::Check, interval))
+        },
+    );
+    let events = events
+        .map(SessionSignal::Event)
+        .chain(stream::once(future::ready(SessionSignal::Closed)));
+
+    stream::select(events, checks)
+        .take_while(|signal| future::ready(!matches!(signal, SessionSignal::Closed)))
+        .then(move |signal| {
+            let redis = redis.clone();
+            let token = token.clone();
+            async move {
+                let active = matches!(
+                    TokenService::get_token_info(&redis, &token).await,
+                    Ok(Some(_))
+                );
+                (signal, active)
+            }
+        })
+        .take_while(|(_, active)| future::ready(*active))
+        .filter_map(|(signal, _)| {
+            future::ready(match signal {
+                SessionSignal::Event(event) => Some(event),
+                _ => None,
+            })
+        })
+        .take_until(tokio::time::sleep(expires_in))
+}
+
+/// 将审计事件流转换为 SSE 响应
+fn audit_event_sse<S>(
+    events: S,
+    keep_alive_interval: Duration,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>>
+where
+    S: Stream<Item = AuditEvent> + Send + 'static,
+{
+    let stream = events.filter_map(|event| async move {
+        match Event::default().event(AUDIT_EVENT_NAME).json_data(&event) {
+            Ok(sse_event) => Some(Ok(sse_event)),
+            Err(e) => {
+                tracing::warn!("序列化审计事件失败: {}", e);
+                None
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(keep_alive_interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        services::{AuditAction, SessionPolicy},
+        utils::{DeviceInfo, DeviceType, JwtKeys},
+    };
+    use axum::response::IntoResponse;
+
+    fn login_event(user_id: Uuid) -> AuditEvent {
+        AuditEvent::new(
+            user_id,
+            AuditAction::Login,
+            &DeviceInfo::simple(DeviceType::Web, None),
+            None,
+        )
+    }
+
+    async fn create_session(redis: &RedisManager, user_id: Uuid) -> String {
+        TokenService::create_token(
+            redis,
+            &AuthUser::new(user_id),
+            &JwtKeys::hs256("test-secret"),
+            DeviceInfo::simple(DeviceType::Web, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_event_stream_limiter_caps_streams_per_user() {
+        let limiter = EventStreamLimiter::new(2);
+        let user_id = Uuid::new_v4();
+
+        let first = limiter.try_acquire(user_id).unwrap();
+        let _second = limiter.try_acquire(user_id).unwrap();
+        assert!(limiter.try_acquire(user_id).is_none());
+
+        // 其他用户不受影响
+        assert!(limiter.try_acquire(Uuid::new_v4()).is_some());
+
+        // 关闭一个事件流后可以重新连接
+        drop(first);
+        assert!(limiter.try_acquire(user_id).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_session_events_end_when_token_is_revoked() {
+        let redis = RedisManager::in_memory();
+        let user_id = Uuid::new_v4();
+        let token = create_session(&redis, user_id).await;
+
+        let events = stream::iter(vec![login_event(user_id)]).chain(stream::pending());
+        let mut events = session_events(
+            redis.clone(),
+            token.clone(),
+            events,
+            Duration::from_secs(60),
+            Duration::from_millis(20),
+        )
+        .boxed();
+
+        // 会话有效时正常转发事件
+        assert!(events.next().await.is_some());
+
+        TokenService::revoke_token(&redis, &token, user_id)
+            .await
+            .unwrap();
+        let ended = tokio::time::timeout(Duration::from_secs(2), events.next()).await;
+        assert!(matches!(ended, Ok(None)));
+    }
+
+    #[tokio::test]
+    async fn test_session_events_end_when_token_expires() {
+        let redis = RedisManager::in_memory();
+        let user_id = Uuid::new_v4();
+        let token = create_session(&redis, user_id).await;
+
+        let mut events = session_events(
+            redis,
+            token,
+            stream::pending(),
+            Duration::from_millis(50),
+            Duration::from_secs(60),
+        )
+        .boxed();
+
+        let ended = tokio::time::timeout(Duration::from_secs(2), events.next()).await;
+        assert!(matches!(ended, Ok(None)));
+    }
+
+    #[tokio::test]
+    async fn test_audit_event_sse_emits_event_and_keep_alive() {
+        let event = AuditEvent::new(
+            Uuid::new_v4(),
+            AuditAction::Login,
+            &DeviceInfo::simple(DeviceType::Web, None),
+            None,
+        );
+        // 推送一条事件后保持空闲，等待 keep-alive
+        let events = stream::iter(vec![event]).chain(stream::pending());
+        let response = audit_event_sse(events, Duration::from_millis(50)).into_response();
+        let mut body = response.into_body().into_data_stream();
+
+        let mut chunks = Vec::new();
+        let received = tokio::time::timeout(Duration::from_secs(2), async {
+            while let Some(chunk) = body.next().await {
+                chunks.push(String::from_utf8(chunk.unwrap().to_vec()).unwrap());
+                if chunks.len() >= 2 {
+                    break;
+                }
+            }
+        })
+        .await;
+        assert!(received.is_ok(), "timed out waiting for SSE frames");
+
+        assert!(chunks[0].starts_with("event: audit\n"));
+        assert!(chunks[0].contains("\"action\":\"login\""));
+        assert_eq!(chunks[1], ":\n\n");
+    }
+}
@@ -0,0 +1,133 @@
+/*!
+ * 健康检查处理器
+ *
+ * 检查数据库和 Redis 的连通性，供负载均衡器和容器编排系统探测服务状态。
+ * 该端点不需要身份验证。
+ */
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Serialize;
+
+use crate::{redis::RedisUtils, routes::AppState, utils::CacheHelper};
+
+/// 组件健康状态：正常
+const STATUS_OK: &str = "ok";
+
+/// 组件健康状态：异常
+const STATUS_ERROR: &str = "error";
+
+/// 健康检查响应
+///
+/// 每个字段表示对应组件的状态，取值为 `"ok"` 或 `"error"`。
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct HealthResponse {
+    /// 数据库状态
+    pub db: &'static str,
+    /// Redis 状态
+    pub redis: &'static str,
+}
+
+impl HealthResponse {
+    /// 根据各组件的检查结果构造响应
+    ///
+    /// # 参数
+    ///
+    /// * `db_ok` - 数据库是否可用
+    /// * `redis_ok` - Redis 是否可用
+    ///
+    /// # 返回值
+    ///
+    /// 所有组件都正常时返回 `200 OK`，否则返回 `503 Service Unavailable`
+    pub fn from_checks(db_ok: bool, redis_ok: bool) -> (StatusCode, Self) {
+        let status_of = |ok: bool| if ok { STATUS_OK } else { STATUS_ERROR };
+        let status = if db_ok && redis_ok {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        (
+            status,
+            Self {
+                db: status_of(db_ok),
+                redis: status_of(redis_ok),
+            },
+        )
+    }
+}
+
+/// 健康检查处理器
+///
+/// 对数据库执行 `SELECT 1`，对 Redis 执行 `PING`。
+///
+/// # 请求
+///
+/// - **方法**: GET
+/// - **路径**: `/health`
+///
+/// # 响应
+///
+/// 所有组件正常时返回 `200 OK`：
+/// ```json
+/// {
+///   "db": "ok",
+///   "redis": "ok"
+/// }
+/// ```
+///
+/// 任一组件异常时返回 `503 Service Unavailable`，异常组件标记为 `"error"`：
+/// ```json
+/// {
+///   "db": "ok",
+///   "redis": "error"
+/// }
+/// ```
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含数据库连接池和 Redis 管理器
+pub async fn health_check(State(app_state): State<AppState>) -> (StatusCode, Json<HealthResponse>) {
+    // 数据库检查
+    let db_ok = match sqlx::query("SELECT 1").execute(&app_state.pool).await {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::warn!("健康检查：数据库不可用: {}", e);
+            false
+        }
+    };
+
+    // Redis 检查
+    let cache = CacheHelper::new(RedisUtils::new(app_state.redis.clone()));
+    let redis_ok = cache.health_check().await.unwrap_or(false);
+    if !redis_ok {
+        tracing::warn!("健康检查：Redis 不可用");
+    }
+
+    let (status, response) = HealthResponse::from_checks(db_ok, redis_ok);
+    (status, Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_response_all_ok() {
+        let (status, response) = HealthResponse::from_checks(true, true);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({ "db": "ok", "redis": "ok" })
+        );
+    }
+
+    #[test]
+    fn test_health_response_redis_down() {
+        let (status, response) = HealthResponse::from_checks(true, false);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({ "db": "ok", "redis": "error" })
+        );
+    }
+}
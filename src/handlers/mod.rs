@@ -9,15 +9,25 @@
  * # 子模块
  *
  * - `auth`: 身份验证相关的处理器（注册、登录、退出登录）
+ * - `events`: 事件流处理器（通过 SSE 推送审计事件）
+ * - `health`: 健康检查处理器（数据库和 Redis 连通性）
  * - `user`: 用户管理相关的处理器（用户信息、用户列表）
  */
 
 /// 身份验证处理器
 pub mod auth;
 
+/// 事件流处理器
+pub mod events;
+
+/// 健康检查处理器
+pub mod health;
+
 /// 用户管理处理器
 pub mod user;
 
 // 重新导出所有处理器函数，方便外部使用
 pub use auth::*;
+pub use events::*;
+pub use health::*;
 pub use user::*;
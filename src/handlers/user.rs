@@ -1,14 +1,20 @@
 /*!
  * 用户管理处理器
  *
- * 处理用户信息相关的 HTTP 请求，包括获取个人资料和用户列表。
+ * 处理用户信息相关的 HTTP 请求，包括获取和更新个人资料、修改密码、审计日志和用户列表。
  * 所有处理器都需要身份验证。
  */
 
-use axum::{extract::State, Extension, Json};
-use uuid::Uuid;
+use axum::{extract::State, response::Response, Extension, Json};
 
-use crate::{error::Result, models::UserResponse, routes::AppState, services::UserService};
+use crate::{
+    error::Result,
+    middleware::{AppJson, DeviceContext, IfNoneMatch, Pagination},
+    models::{ChangePasswordRequest, Page, UpdateUserRequest, UserResponse},
+    routes::AppState,
+    services::{AuditAction, AuditEvent, AuditService, PasswordPolicy, TokenService, UserService},
+    utils::AuthUser,
+};
 
 /// 获取用户个人资料处理器
 ///
@@ -20,10 +26,12 @@ use crate::{error::Result, models::UserResponse, routes::AppState, services::Use
 /// - **方法**: GET
 /// - **路径**: `/api/profile`
 /// - **请求头**: `Authorization: Bearer <jwt_token>`
+/// - **可选请求头**: `If-None-Match: <etag>`
 ///
 /// # 响应
 ///
-/// 成功时返回用户信息：
+/// 成功时返回用户信息，并在 `ETag` 响应头中返回弱 ETag；
+/// `If-None-Match` 与当前 ETag 匹配时返回 `304 Not Modified`：
 /// ```json
 /// {
 ///   "id": "user_uuid",
@@ -42,67 +50,256 @@ use crate::{error::Result, models::UserResponse, routes::AppState, services::Use
 /// # 参数
 ///
 /// * `app_state` - 应用程序状态，包含数据库连接池
-/// * `user_id` - 从 JWT Token 中提取的用户 ID（由身份验证中间件注入）
+/// * `auth_user` - 从 JWT Token 中提取的用户信息（由身份验证中间件注入）
+/// * `if_none_match` - 客户端缓存的 ETag
 pub async fn get_profile(
     State(app_state): State<AppState>,
-    Extension(user_id): Extension<Uuid>,
-) -> Result<Json<UserResponse>> {
+    Extension(auth_user): Extension<AuthUser>,
+    if_none_match: IfNoneMatch,
+) -> Result<Response> {
     // 根据用户 ID 查询用户信息
-    let user = UserService::get_user_by_id(&app_state.pool, user_id).await?;
+    let user = UserService::get_user_by_id(&app_state.pool, auth_user.id).await?;
+
+    // 转换为响应格式，未变化时返回 304
+    if_none_match.respond_json(&UserResponse::from(user))
+}
+
+/// 更新用户个人资料处理器
+///
+/// 部分更新当前登录用户的名称和邮箱，只修改请求中提供的字段。
+///
+/// # 请求
+///
+/// - **方法**: PATCH
+/// - **路径**: `/api/profile`
+/// - **请求头**: `Authorization: Bearer <jwt_token>`
+/// - **请求体**: JSON 格式的 `UpdateUserRequest`
+///   ```json
+///   {
+///     "name": "李四",
+///     "email": "new@example.com"
+///   }
+///   ```
+///
+/// # 响应
+///
+/// 成功时返回更新后的 `UserResponse`
+///
+/// # 错误
+///
+/// - `401 Unauthorized`: JWT Token 无效或已过期
+/// - `404 Not Found`: 用户不存在
+/// - `409 Conflict`: 邮箱已被其他用户使用
+/// - `500 Internal Server Error`: 服务器内部错误
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含数据库连接池
+/// * `auth_user` - 从 JWT Token 中提取的用户信息（由身份验证中间件注入）
+/// * `request` - 更新用户资料请求数据
+pub async fn update_profile(
+    State(app_state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    AppJson(request): AppJson<UpdateUserRequest>,
+) -> Result<Json<UserResponse>> {
+    let user = UserService::update_user(&app_state.pool, auth_user.id, request).await?;
 
-    // 转换为响应格式并返回
     Ok(Json(user.into()))
 }
 
-/// 获取所有用户列表处理器
+/// 修改密码处理器
 ///
-/// 返回系统中所有用户的列表。
-/// 需要身份验证，但不进行特殊权限检查。
+/// 验证当前密码后更新为新密码，并撤销该用户的所有登录会话，
+/// 其他设备需要使用新密码重新登录。
+///
+/// # 请求
+///
+/// - **方法**: POST
+/// - **路径**: `/api/profile/password`
+/// - **请求头**: `Authorization: Bearer <jwt_token>`
+/// - **请求体**: JSON 格式的 `ChangePasswordRequest`
+///   ```json
+///   {
+///     "old_password": "password123",
+///     "new_password": "N3w-Secure-Passw0rd"
+///   }
+///   ```
+///
+/// # 响应
+///
+/// 成功时返回：
+/// ```json
+/// {
+///   "message": "密码修改成功，请重新登录"
+/// }
+/// ```
+///
+/// # 错误
+///
+/// - `400 Bad Request`: 新密码强度不足
+/// - `401 Unauthorized`: JWT Token 无效或当前密码错误
+/// - `404 Not Found`: 用户不存在
+/// - `500 Internal Server Error`: 服务器内部错误
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含数据库连接池和 Redis 管理器
+/// * `auth_user` - 从 JWT Token 中提取的用户信息（由身份验证中间件注入）
+/// * `device_context` - 从请求头提取的设备信息和客户端 IP（用于审计日志）
+/// * `request` - 修改密码请求数据
+pub async fn change_password(
+    State(app_state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    DeviceContext(device_info, ip_address): DeviceContext,
+    AppJson(request): AppJson<ChangePasswordRequest>,
+) -> Result<Json<serde_json::Value>> {
+    UserService::change_password(
+        &app_state.pool,
+        auth_user.id,
+        &request.old_password,
+        &request.new_password,
+        PasswordPolicy::from(&app_state.config),
+    )
+    .await?;
+
+    // 密码变更后撤销所有已签发的令牌
+    TokenService::revoke_all_user_tokens(&app_state.redis, auth_user.id).await?;
+
+    // 记录审计事件
+    AuditService::record(
+        &app_state.redis,
+        app_state.config.audit_log_max_events,
+        &AuditEvent::new(
+            auth_user.id,
+            AuditAction::PasswordChange,
+            &device_info,
+            ip_address,
+        ),
+    )
+    .await;
+
+    Ok(Json(serde_json::json!({
+        "message": "密码修改成功，请重新登录"
+    })))
+}
+
+/// 获取安全审计日志处理器
+///
+/// 返回当前登录用户最近的安全相关操作记录（登录、退出登录、撤销会话、修改密码），
+/// 最新的在前，最多保留 `AUDIT_LOG_MAX_EVENTS` 条。
 ///
 /// # 请求
 ///
 /// - **方法**: GET
-/// - **路径**: `/api/users`
+/// - **路径**: `/api/profile/audit`
 /// - **请求头**: `Authorization: Bearer <jwt_token>`
 ///
 /// # 响应
 ///
-/// 成功时返回用户列表：
+/// 成功时返回审计事件列表：
 /// ```json
-/// [
-///   {
-///     "id": "user1_uuid",
-///     "email": "user1@example.com",
-///     "name": "用户1",
-///     "created_at": "2023-01-01T00:00:00Z"
-///   },
-///   {
-///     "id": "user2_uuid",
-///     "email": "user2@example.com",
-///     "name": "用户2",
-///     "created_at": "2023-01-02T00:00:00Z"
-///   }
-/// ]
+/// {
+///   "events": [
+///     {
+///       "timestamp": 1700000000,
+///       "user_id": "user_uuid",
+///       "action": "login",
+///       "ip": "203.0.113.7",
+///       "device": "Chrome 119 on Windows 10"
+///     }
+///   ]
+/// }
+/// ```
+///
+/// # 错误
+///
+/// - `401 Unauthorized`: JWT Token 无效或已过期
+/// - `500 Internal Server Error`: 服务器内部错误
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含 Redis 管理器
+/// * `auth_user` - 从 JWT Token 中提取的用户信息（由身份验证中间件注入）
+pub async fn get_audit_log(
+    State(app_state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> Result<Json<serde_json::Value>> {
+    let events = AuditService::recent_events(&app_state.redis, auth_user.id).await?;
+
+    Ok(Json(serde_json::json!({
+        "events": events
+    })))
+}
+
+/// 获取用户列表处理器
+///
+/// 分页返回系统中的用户列表。
+/// 需要身份验证，且仅 `admin` 角色可以访问（由 `require_role` 中间件检查）。
+///
+/// # 请求
+///
+/// - **方法**: GET
+/// - **路径**: `/api/users?page=1&per_page=20`
+/// - **请求头**: `Authorization: Bearer <jwt_token>`
+/// - **可选请求头**: `If-None-Match: <etag>`
+///
+/// # 查询参数
+///
+/// - `page`: 页码，从 1 开始，默认 1
+/// - `per_page`: 每页条数，默认 20，超过 `MAX_PER_PAGE`（默认 100）时截断
+///
+/// # 响应
+///
+/// 成功时返回分页后的用户列表，并在 `ETag` 响应头中返回弱 ETag；
+/// `If-None-Match` 与当前 ETag 匹配时返回 `304 Not Modified`：
+/// ```json
+/// {
+///   "items": [
+///     {
+///       "id": "user1_uuid",
+///       "email": "user1@example.com",
+///       "name": "用户1",
+///       "created_at": "2023-01-01T00:00:00Z"
+///     }
+///   ],
+///   "total": 1,
+///   "page": 1,
+///   "per_page": 20,
+///   "total_pages": 1
+/// }
 /// ```
 ///
 /// # 错误
 ///
 /// - `401 Unauthorized`: JWT Token 无效或已过期
+/// - `400 Bad Request`: `page` 或 `per_page` 不是正整数
+/// - `403 Forbidden`: 当前用户不是管理员
 /// - `500 Internal Server Error`: 服务器内部错误
 ///
 /// # 参数
 ///
 /// * `app_state` - 应用程序状态，包含数据库连接池
-/// * `_user_id` - 从 JWT Token 中提取的用户 ID（用于验证身份，但不使用）
+/// * `_auth_user` - 从 JWT Token 中提取的用户信息（用于验证身份，但不使用）
+/// * `pagination` - 分页查询参数
+/// * `if_none_match` - 客户端缓存的 ETag
 pub async fn get_all_users(
     State(app_state): State<AppState>,
-    Extension(_user_id): Extension<Uuid>, // 需要身份验证，但不使用具体的用户 ID
-) -> Result<Json<Vec<UserResponse>>> {
-    // 获取所有用户列表
-    let users = UserService::get_all_users(&app_state.pool).await?;
+    Extension(_auth_user): Extension<AuthUser>, // 需要身份验证，但不使用具体的用户信息
+    pagination: Pagination,
+    if_none_match: IfNoneMatch,
+) -> Result<Response> {
+    // 分页获取用户列表
+    let (users, total) =
+        UserService::get_users_paginated(&app_state.pool, pagination.limit(), pagination.offset())
+            .await?;
 
     // 将 User 转换为 UserResponse，隐藏敏感信息如密码哈希
-    let user_responses: Vec<UserResponse> = users.into_iter().map(|user| user.into()).collect();
+    let items: Vec<UserResponse> = users.into_iter().map(|user| user.into()).collect();
 
-    Ok(Json(user_responses))
+    if_none_match.respond_json(&Page::new(
+        items,
+        u64::try_from(total).unwrap_or_default(),
+        pagination.page,
+        pagination.per_page,
+    ))
 }
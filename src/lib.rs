@@ -13,7 +13,9 @@
  * - `config`: 应用配置管理
  * - `db`: 数据库连接和操作
  * - `error`: 统一错误处理
+ * - `logging`: 日志订阅器初始化（JSON / pretty / compact 格式）
  * - `redis`: Redis 缓存和工具
+ * - `metrics`: Prometheus 指标记录和导出
  * - `handlers`: HTTP 请求处理器
  * - `middleware`: 中间件（如身份验证）
  * - `models`: 数据模型定义
@@ -26,6 +28,8 @@
 pub mod config;
 pub mod db;
 pub mod error;
+pub mod logging;
+pub mod metrics;
 pub mod redis;
 
 // Web 相关模块
@@ -43,4 +47,4 @@ pub mod utils;
 // 重新导出常用类型，方便外部使用
 pub use config::Config;
 pub use error::{AppError, Result};
-pub use redis::{RedisManager, RedisUtils};
+pub use redis::{LockGuard, MemoryBackend, RedisBackend, RedisManager, RedisUtils};
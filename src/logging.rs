@@ -0,0 +1,151 @@
+/*!
+ * 日志模块
+ *
+ * 根据配置的 `LogFormat` 初始化 `tracing` 日志订阅器：
+ * - `json`: 每行一个 JSON 对象，包含时间戳、级别、target、事件字段以及 span 字段
+ * - `pretty`: 多行、易读的开发格式
+ * - `compact`: 单行的简洁文本格式
+ *
+ * 日志级别通过 `RUST_LOG` 环境变量配置。
+ */
+
+use tracing::Subscriber;
+use tracing_subscriber::{
+    fmt::MakeWriter, layer::SubscriberExt, registry::LookupSpan, util::SubscriberInitExt,
+    EnvFilter, Layer,
+};
+
+use crate::config::LogFormat;
+
+/// 未设置 `RUST_LOG` 时使用的日志过滤规则
+const DEFAULT_FILTER: &str = "hello_rust=debug,tower_http=debug,axum::rejection=trace";
+
+/// 按日志格式构建输出层
+///
+/// JSON 格式会同时输出当前 span（`span`）和完整 span 链（`spans`）中的字段，
+/// 这样请求 ID、用户 ID 等记录在请求 span 上的字段会出现在每一行日志中。
+///
+/// # 参数
+///
+/// * `format` - 日志格式
+/// * `writer` - 日志输出目标，例如 `std::io::stdout`
+pub fn fmt_layer<S, W>(format: LogFormat, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer);
+    match format {
+        LogFormat::Json => layer
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+        LogFormat::Pretty => layer.pretty().boxed(),
+        LogFormat::Compact => layer.compact().boxed(),
+    }
+}
+
+/// 初始化全局日志订阅器，输出到标准输出
+///
+/// # Panics
+///
+/// 如果已经设置过全局订阅器则会 panic，应只在程序启动时调用一次
+pub fn init(format: LogFormat) {
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| DEFAULT_FILTER.into()))
+        .with(fmt_layer(format, std::io::stdout))
+        .init();
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+    use tracing_subscriber::fmt::MakeWriter;
+
+    /// 把日志写入内存缓冲区的测试输出目标
+    #[derive(Clone, Default)]
+    pub struct BufferWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl BufferWriter {
+        /// 已写入的全部日志
+        pub fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl io::Write for BufferWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for BufferWriter {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::BufferWriter;
+    use super::*;
+
+    #[test]
+    fn test_json_format_produces_parseable_lines() {
+        let writer = BufferWriter::default();
+        let subscriber =
+            tracing_subscriber::registry().with(fmt_layer(LogFormat::Json, writer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request", request_id = "req-1");
+            let _guard = span.enter();
+            tracing::info!(answer = 42, "first");
+            tracing::warn!("second");
+        });
+
+        let output = writer.contents();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        let first = &lines[0];
+        assert!(first["timestamp"].is_string());
+        assert_eq!(first["level"], "INFO");
+        assert_eq!(first["target"], module_path!());
+        assert_eq!(first["fields"]["message"], "first");
+        assert_eq!(first["fields"]["answer"], 42);
+        assert_eq!(first["span"]["name"], "request");
+        assert_eq!(first["span"]["request_id"], "req-1");
+        assert_eq!(first["spans"][0]["request_id"], "req-1");
+
+        assert_eq!(lines[1]["level"], "WARN");
+        assert_eq!(lines[1]["fields"]["message"], "second");
+    }
+
+    #[test]
+    fn test_text_formats_are_not_json() {
+        for format in [LogFormat::Pretty, LogFormat::Compact] {
+            let writer = BufferWriter::default();
+            let subscriber = tracing_subscriber::registry().with(fmt_layer(format, writer.clone()));
+            tracing::subscriber::with_default(subscriber, || tracing::info!("hello"));
+
+            let output = writer.contents();
+            assert!(output.contains("hello"));
+            assert!(serde_json::from_str::<serde_json::Value>(output.trim()).is_err());
+        }
+    }
+}
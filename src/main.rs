@@ -8,12 +8,23 @@
  * - 建立Redis连接
  * - 配置路由和中间件
  * - 启动 HTTP 服务器
+ * - 收到关闭信号后优雅停机
  */
 
-use hello_rust::{config::Config, db::create_pool, redis::RedisManager, routes::create_routes};
+use axum::middleware;
+use hello_rust::{
+    config::Config,
+    db::create_pool_with_config,
+    logging,
+    metrics::prometheus_handle,
+    middleware::{cors_layer, request_id_middleware, trace_layer},
+    redis::RedisManager,
+    routes::create_routes,
+    services::{email_sender_from_config, spawn_token_cleanup},
+    utils::JwtKeys,
+};
+use std::time::Duration;
 use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 /// 应用程序主入口点
 ///
@@ -24,43 +35,106 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 /// 返回 `anyhow::Result<()>`，如果启动过程中出现错误则返回错误信息
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // 初始化追踪日志系统
-    // 使用环境变量配置日志级别，默认为 debug 级别
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-                "hello_rust=debug,tower_http=debug,axum::rejection=trace".into()
-            }),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
-    // 从环境变量加载应用配置
+    // 从环境变量加载应用配置（日志格式由配置决定，因此先于日志系统加载）
     let config = Config::from_env()?;
+
+    // 初始化追踪日志系统
+    logging::init(config.log_format);
     tracing::info!("Starting server with config: {:#?}", config);
 
     // 创建数据库连接池
     // 连接池负责管理数据库连接，提高性能和资源利用率
-    let pool = create_pool(&config.database_url).await?;
+    let pool = create_pool_with_config(&config).await?;
     tracing::info!("Database connection established");
 
     // 创建Redis连接管理器
     let redis_manager = RedisManager::new(&config).await?;
     tracing::info!("Redis connection established");
 
+    // 启动过期 token 定时清理任务
+    let token_cleanup = config.token_cleanup_enabled.then(|| {
+        spawn_token_cleanup(
+            redis_manager.clone(),
+            Duration::from_secs(config.token_cleanup_interval_seconds),
+        )
+    });
+
+    // 根据配置的签名算法加载 JWT 密钥
+    let jwt_keys = JwtKeys::from_config(&config)?;
+    tracing::info!("JWT signing algorithm: {:?}", jwt_keys.algorithm());
+
+    // 根据 SMTP 配置创建邮件发送器，未配置时邮件只写入日志
+    let email_sender = email_sender_from_config(&config)?;
+
+    // 安装 Prometheus 指标记录器，之后记录的指标通过 /metrics 导出
+    prometheus_handle()?;
+
+    // 根据允许的源列表构建 CORS 中间件
+    let cors = cors_layer(
+        config.cors_allowed_origins.as_deref(),
+        config.development_mode,
+    )?;
+
     // 创建应用路由和中间件栈
-    let app = create_routes(pool, redis_manager, config.clone()).layer(
+    let app = create_routes(
+        pool.clone(),
+        redis_manager.clone(),
+        jwt_keys,
+        config.clone(),
+        email_sender,
+    )
+    .layer(
         ServiceBuilder::new()
-            .layer(TraceLayer::new_for_http()) // HTTP 请求追踪中间件
-            .layer(CorsLayer::permissive()), // CORS 跨域支持中间件
+            .layer(middleware::from_fn(request_id_middleware)) // 请求 ID 中间件（需在追踪中间件之前）
+            // HTTP 请求追踪中间件，span 中携带请求 ID 和（身份验证后的）用户 ID，按配置的采样率输出请求日志
+            .layer(trace_layer(config.trace_sample_rate))
+            .layer(cors), // CORS 跨域支持中间件
     );
 
     // 启动 TCP 监听器，绑定到配置的地址和端口
     let listener = tokio::net::TcpListener::bind(&config.server_address()).await?;
     tracing::info!("Server listening on {}", config.server_address());
 
-    // 启动 Axum HTTP 服务器
-    axum::serve(listener, app).await?;
+    // 启动 Axum HTTP 服务器，收到关闭信号后等待处理中的请求完成再退出
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    // 服务器停止后结束后台任务，关闭数据库连接池和 Redis 连接
+    if let Some(task) = token_cleanup {
+        task.abort();
+    }
+    pool.close().await;
+    drop(redis_manager);
+    tracing::info!("Graceful shutdown completed");
 
     Ok(())
 }
+
+/// 等待关闭信号
+///
+/// 在所有平台上监听 Ctrl+C (SIGINT)，在 Unix 平台上额外监听 SIGTERM
+/// （容器停止时发送的信号）。Windows 没有 SIGTERM，只等待 Ctrl+C。
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("Received Ctrl+C, starting graceful shutdown"),
+        _ = terminate => tracing::info!("Received SIGTERM, starting graceful shutdown"),
+    }
+}
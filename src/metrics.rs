@@ -0,0 +1,224 @@
+/*!
+ * Prometheus 指标模块
+ *
+ * 使用 `metrics` + `metrics-exporter-prometheus` 记录并导出应用指标：
+ * - `http_requests_total`: 请求总数（按方法、路由、状态码和状态码类别区分）
+ * - `http_requests_in_flight`: 正在处理的请求数
+ * - `http_request_duration_seconds`: 请求耗时直方图（标签同上，可按路由和 `status_class` 统计延迟）
+ * - `db_pool_connections` / `db_pool_idle_connections`: 数据库连接池大小和空闲连接数
+ * - `redis_up`: Redis 是否可用（1 为可用，0 为不可用）
+ *
+ * 指标通过不需要身份验证的 `GET /metrics` 端点以 Prometheus 文本格式导出。
+ */
+
+use std::{sync::OnceLock, time::Instant};
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+use crate::{error::Result, redis::RedisUtils, routes::AppState, utils::CacheHelper};
+
+/// 请求耗时直方图的分桶（秒）
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// 未匹配任何路由的请求使用的路由标签，避免以原始路径作为标签导致指标数量失控
+const UNMATCHED_PATH: &str = "unmatched";
+
+/// 全局指标记录器的安装结果（安装失败时保存错误信息）
+static PROMETHEUS_HANDLE: OnceLock<std::result::Result<PrometheusHandle, String>> = OnceLock::new();
+
+/// 获取 Prometheus 指标句柄
+///
+/// 首次调用时安装全局指标记录器，之后返回同一个句柄。
+/// 应在启动服务器前调用一次，确保之后记录的指标都能被导出。
+///
+/// # 错误
+///
+/// 其他全局指标记录器已经安装时返回错误，之后的调用返回同样的错误
+pub fn prometheus_handle() -> anyhow::Result<&'static PrometheusHandle> {
+    PROMETHEUS_HANDLE
+        .get_or_init(|| {
+            PrometheusBuilder::new()
+                .set_buckets_for_metric(
+                    Matcher::Full("http_request_duration_seconds".to_string()),
+                    DURATION_BUCKETS,
+                )
+                .and_then(|builder| builder.install_recorder())
+                .map_err(|e| e.to_string())
+        })
+        .as_ref()
+        .map_err(|e| anyhow::anyhow!("Failed to install Prometheus recorder: {}", e))
+}
+
+/// 处理中的请求计数
+///
+/// 创建时增加 `http_requests_in_flight`，被丢弃时减少。
+/// 请求被取消（如客户端断开、超时）或处理器 panic 时同样会减少，计数不会只增不减。
+struct InFlightGuard;
+
+impl InFlightGuard {
+    fn new() -> Self {
+        ::metrics::gauge!("http_requests_in_flight").increment(1.0);
+        Self
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        ::metrics::gauge!("http_requests_in_flight").decrement(1.0);
+    }
+}
+
+/// 指标记录中间件函数
+///
+/// 记录每个请求的总数、处理中的请求数和耗时。路由标签使用匹配到的路由模板
+/// （如 `/api/auth/logout-device/:device_type`），而不是原始请求路径，避免路径参数导致指标数量失控。
+/// 除具体状态码 `status` 外还带有状态码类别 `status_class`（如 `2xx`、`5xx`），
+/// 它由状态码决定，不会增加时间序列数量，便于按类别聚合延迟和错误率。
+///
+/// # 示例
+///
+/// ```rust,ignore
+/// use axum::{middleware, Router};
+/// use hello_rust::metrics::track_metrics;
+///
+/// let app = Router::new()
+///     .route("/health", get(health_check))
+///     .layer(middleware::from_fn(track_metrics));
+/// ```
+pub async fn track_metrics(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_PATH.to_string());
+
+    let start = Instant::now();
+    let in_flight = InFlightGuard::new();
+
+    let response = next.run(request).await;
+
+    drop(in_flight);
+    let status = response.status();
+    let labels = [
+        ("method", method),
+        ("path", path),
+        ("status", status.as_u16().to_string()),
+        ("status_class", status_class(status).to_string()),
+    ];
+    ::metrics::counter!("http_requests_total", &labels[..]).increment(1);
+    ::metrics::histogram!("http_request_duration_seconds", &labels[..])
+        .record(start.elapsed().as_secs_f64());
+
+    response
+}
+
+/// 获取状态码类别标签，如 `200` → `2xx`
+fn status_class(status: StatusCode) -> &'static str {
+    match status.as_u16() {
+        100..=199 => "1xx",
+        200..=299 => "2xx",
+        300..=399 => "3xx",
+        400..=499 => "4xx",
+        _ => "5xx",
+    }
+}
+
+/// 指标导出处理器
+///
+/// 导出前刷新数据库连接池和 Redis 状态指标，然后以 Prometheus 文本格式返回所有指标。
+///
+/// # 请求
+///
+/// - **方法**: GET
+/// - **路径**: `/metrics`
+///
+/// # 参数
+///
+/// * `app_state` - 应用程序状态，包含数据库连接池和 Redis 管理器
+///
+/// # 错误
+///
+/// - `500 Internal Server Error`: Prometheus 指标记录器未能安装
+pub async fn metrics_handler(State(app_state): State<AppState>) -> Result<String> {
+    ::metrics::gauge!("db_pool_connections").set(app_state.pool.size() as f64);
+    ::metrics::gauge!("db_pool_idle_connections").set(app_state.pool.num_idle() as f64);
+
+    let cache = CacheHelper::new(RedisUtils::new(app_state.redis.clone()));
+    let redis_ok = cache.health_check().await.unwrap_or(false);
+    ::metrics::gauge!("redis_up").set(if redis_ok { 1.0 } else { 0.0 });
+
+    Ok(prometheus_handle()?.render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_request_counter_in_scrape_output() {
+        let handle = prometheus_handle().unwrap();
+        let app = Router::new()
+            .route("/metrics-test/:id", get(|| async { "ok" }))
+            .layer(middleware::from_fn(track_metrics));
+
+        let response = app
+            .oneshot(
+                Request::get("/metrics-test/42")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let output = handle.render();
+        assert!(output.contains(
+            r#"http_requests_total{method="GET",path="/metrics-test/:id",status="200",status_class="2xx"} 1"#
+        ));
+        assert!(output.contains("http_request_duration_seconds_bucket"));
+        assert!(output.contains("http_requests_in_flight"));
+    }
+
+    #[test]
+    fn test_in_flight_guard_decrements_on_drop() {
+        // 使用局部记录器，避免与其他测试的请求计数相互影响
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        ::metrics::with_local_recorder(&recorder, || {
+            let guard = InFlightGuard::new();
+            assert!(handle.render().contains("http_requests_in_flight 1"));
+
+            // 请求被取消时 guard 随 future 一起被丢弃
+            drop(guard);
+            assert!(handle.render().contains("http_requests_in_flight 0"));
+        });
+    }
+
+    #[test]
+    fn test_prometheus_handle_is_reused() {
+        let first = prometheus_handle().unwrap();
+        let second = prometheus_handle().unwrap();
+        assert!(std::ptr::eq(first, second));
+    }
+
+    #[test]
+    fn test_status_class() {
+        assert_eq!(status_class(StatusCode::SWITCHING_PROTOCOLS), "1xx");
+        assert_eq!(status_class(StatusCode::NO_CONTENT), "2xx");
+        assert_eq!(status_class(StatusCode::NOT_MODIFIED), "3xx");
+        assert_eq!(status_class(StatusCode::UNAUTHORIZED), "4xx");
+        assert_eq!(status_class(StatusCode::GATEWAY_TIMEOUT), "5xx");
+    }
+}
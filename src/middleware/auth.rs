@@ -2,7 +2,7 @@
  * 身份验证中间件
  *
  * 负责验证 HTTP 请求中的 JWT Token，确保只有经过身份验证的用户
- * 才能访问受保护的资源。验证成功后会将用户 ID 注入到请求扩展中。
+ * 才能访问受保护的资源。验证成功后会将 `AuthUser` 注入到请求扩展中。
  */
 
 use axum::{
@@ -11,26 +11,29 @@ use axum::{
     middleware::Next,
     response::Response,
 };
-use uuid::Uuid;
 
 use crate::{
     error::{AppError, Result},
     routes::AppState,
     services::TokenService,
+    utils::AuthUser,
 };
 
+use super::record_user_id;
+
 /// 身份验证中间件函数
 ///
 /// 验证 HTTP 请求中的 JWT Token，确保用户已经登录。
 /// 该中间件会：
 /// 1. 从 Authorization 请求头中提取 JWT Token
 /// 2. 验证 Token 的有效性和签名
-/// 3. 从 Token 中提取用户 ID
-/// 4. 将用户 ID 注入到请求扩展中，供后续处理器使用
+/// 3. 从 Token 中提取用户 ID、邮箱和角色
+/// 4. 将 `AuthUser` 注入到请求扩展中，供后续处理器使用
+/// 5. 在当前请求的追踪 span 中记录用户 ID
 ///
 /// # 请求头格式
 ///
-/// ```
+/// ```text
 /// Authorization: Bearer <jwt_token>
 /// ```
 ///
@@ -39,8 +42,8 @@ use crate::{
 /// 1. **提取 Authorization 头**: 从请求头中获取 `Authorization` 字段
 /// 2. **验证格式**: 确保头部格式为 `Bearer <token>`
 /// 3. **验证 Token**: 使用配置的密钥验证 JWT Token 的签名和有效期
-/// 4. **提取用户信息**: 从 Token 的 claims 中提取用户 ID
-/// 5. **注入用户 ID**: 将用户 ID 添加到请求扩展中
+/// 4. **提取用户信息**: 从 Token 的 claims 中提取用户 ID、邮箱和角色
+/// 5. **注入用户信息**: 将 `AuthUser` 添加到请求扩展中
 /// 6. **继续处理**: 调用下一个中间件或处理器
 ///
 /// # 错误处理
@@ -62,10 +65,10 @@ use crate::{
 ///
 /// # 示例
 ///
-/// ```rust
+/// ```rust,ignore
 /// // 在路由中应用身份验证中间件
 /// use axum::{middleware, Router};
-/// use crate::middleware::auth_middleware;
+/// use hello_rust::middleware::auth_middleware;
 ///
 /// let protected_routes = Router::new()
 ///     .route("/profile", get(get_profile))
@@ -89,15 +92,16 @@ pub async fn auth_middleware(
     })?;
 
     // 使用 TokenService 验证 token（包括 Redis 存在性检查）
-    let claims =
-        TokenService::verify_token(&app_state.redis, token, &app_state.config.jwt_secret).await?;
+    let claims = TokenService::verify_token(&app_state.redis, token, &app_state.jwt_keys).await?;
+
+    // 从 Token claims 中提取用户信息（旧 Token 中缺少的邮箱和角色为 None）
+    let auth_user = AuthUser::from_claims(&claims)?;
 
-    // 从 Token claims 中提取用户 ID
-    let user_id = Uuid::parse_str(&claims.sub)
-        .map_err(|_| AppError::Authentication("Invalid user ID in token".to_string()))?;
+    // 在追踪 span 中记录用户 ID，便于按用户关联日志
+    record_user_id(auth_user.id);
 
-    // 将用户 ID 注入到请求扩展中，供后续处理器使用
-    request.extensions_mut().insert(user_id);
+    // 将用户信息注入到请求扩展中，供后续处理器使用
+    request.extensions_mut().insert(auth_user);
 
     // 继续处理请求
     Ok(next.run(request).await)
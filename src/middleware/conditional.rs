@@ -0,0 +1,207 @@
+/*!
+ * 条件请求提取器
+ *
+ * 为只读的 GET 接口提供 ETag 支持：根据序列化后的响应体生成弱 ETag，
+ * 请求头 `If-None-Match` 与之匹配时返回 `304 Not Modified` 且不携带响应体，
+ * 客户端可以直接使用本地缓存，节省重复拉取未变化数据的带宽。
+ */
+
+use std::convert::Infallible;
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{
+        header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+        request::Parts,
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::{
+    error::{AppError, Result},
+    utils::CryptoUtils,
+};
+
+/// `If-None-Match` 请求头
+///
+/// 保存客户端提交的 ETag 列表（未提交时为空），通过 `respond_json`
+/// 生成带 ETag 的 JSON 响应或 304 响应。
+///
+/// # 示例
+///
+/// ```rust,ignore
+/// use hello_rust::middleware::IfNoneMatch;
+///
+/// async fn get_profile(if_none_match: IfNoneMatch) -> Result<Response> {
+///     let profile = load_profile().await?;
+///     if_none_match.respond_json(&profile)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IfNoneMatch(pub Vec<String>);
+
+impl IfNoneMatch {
+    /// 从请求头构造
+    ///
+    /// 支持逗号分隔的多个 ETag 以及 `*`。
+    ///
+    /// # 参数
+    ///
+    /// * `headers` - HTTP 请求头
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let tags = headers
+            .get_all(IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        IfNoneMatch(tags)
+    }
+
+    /// 判断 ETag 是否与请求中的任一 ETag 匹配
+    ///
+    /// 按照 GET 请求的弱比较规则，忽略 `W/` 前缀。
+    pub fn matches(&self, etag: &str) -> bool {
+        let etag = strip_weak_prefix(etag);
+        self.0
+            .iter()
+            .any(|tag| tag == "*" || strip_weak_prefix(tag) == etag)
+    }
+
+    /// 生成带 ETag 的 JSON 响应
+    ///
+    /// 响应体未变化（ETag 匹配）时返回不带响应体的 `304 Not Modified`。
+    ///
+    /// # 参数
+    ///
+    /// * `value` - 响应数据
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Internal`: 序列化响应数据失败
+    pub fn respond_json<T: Serialize>(&self, value: &T) -> Result<Response> {
+        let body = serde_json::to_vec(value).map_err(|e| AppError::Internal(e.into()))?;
+        let etag = weak_etag(&body);
+        let etag_header = HeaderValue::from_str(&etag).map_err(|e| AppError::Internal(e.into()))?;
+
+        if self.matches(&etag) {
+            return Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag_header)]).into_response());
+        }
+
+        Ok((
+            [
+                (CONTENT_TYPE, HeaderValue::from_static("application/json")),
+                (ETAG, etag_header),
+            ],
+            body,
+        )
+            .into_response())
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for IfNoneMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// 根据响应体生成弱 ETag
+///
+/// 使用 SHA-256 摘要的前 32 个十六进制字符，格式为 `W/"..."`。
+pub fn weak_etag(body: &[u8]) -> String {
+    format!("W/\"{}\"", &CryptoUtils::sha256_hex(body)[..32])
+}
+
+/// 去掉弱 ETag 的 `W/` 前缀
+fn strip_weak_prefix(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_if_none_match_matches() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            IF_NONE_MATCH,
+            HeaderValue::from_static("\"abc\", W/\"def\""),
+        );
+        let if_none_match = IfNoneMatch::from_headers(&headers);
+
+        assert!(if_none_match.matches("W/\"abc\""));
+        assert!(if_none_match.matches("\"def\""));
+        assert!(!if_none_match.matches("W/\"xyz\""));
+        assert!(!IfNoneMatch::default().matches("W/\"abc\""));
+
+        headers.insert(IF_NONE_MATCH, HeaderValue::from_static("*"));
+        assert!(IfNoneMatch::from_headers(&headers).matches("W/\"xyz\""));
+    }
+
+    #[tokio::test]
+    async fn test_respond_json_returns_not_modified() {
+        let app = Router::new().route(
+            "/profile",
+            get(|if_none_match: IfNoneMatch| async move {
+                if_none_match.respond_json(&serde_json::json!({ "name": "alice" }))
+            }),
+        );
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/profile").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[ETAG].clone();
+        assert!(etag.to_str().unwrap().starts_with("W/\""));
+
+        // 携带返回的 ETag 再次请求，返回 304 且没有响应体
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get("/profile")
+                    .header(IF_NONE_MATCH, etag.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[ETAG], etag);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+
+        // ETag 不匹配时返回完整响应
+        let response = app
+            .oneshot(
+                Request::get("/profile")
+                    .header(IF_NONE_MATCH, "W/\"stale\"")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,251 @@
+/*!
+ * CORS 跨域中间件
+ *
+ * 根据 `CORS_ALLOWED_ORIGINS` 配置构建 `CorsLayer`：
+ * - 配置了具体的源时，只允许这些源跨域访问，并允许携带凭据（Cookie、Authorization）
+ * - 配置为 `*` 时允许任意源，但不允许携带凭据
+ * - 未配置时，开发模式下放行所有跨域请求，生产环境下不返回任何 CORS 响应头（拒绝跨域）
+ */
+
+use std::time::Duration;
+
+use anyhow::Context;
+use axum::http::{
+    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    HeaderName, HeaderValue, Method,
+};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use super::{
+    DEVICE_TYPE_HEADER, IDEMPOTENCY_KEY_HEADER, IDEMPOTENT_REPLAYED_HEADER, REQUEST_ID_HEADER,
+};
+
+/// 允许任意源的通配符
+const WILDCARD_ORIGIN: &str = "*";
+
+/// 预检请求结果的缓存时间
+const PREFLIGHT_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// 构建 CORS 中间件层
+///
+/// # 参数
+///
+/// * `allowed_origins` - 允许的源列表（如 `https://example.com`），`None` 表示未配置
+/// * `development_mode` - 是否为开发模式
+///
+/// # 错误
+///
+/// 源不是合法的 HTTP 头部值，或者 `*` 与具体的源同时配置时返回错误
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_rust::middleware::cors_layer;
+///
+/// let origins = vec!["https://example.com".to_string()];
+/// let layer = cors_layer(Some(&origins), false).unwrap();
+/// ```
+pub fn cors_layer(
+    allowed_origins: Option<&[String]>,
+    development_mode: bool,
+) -> anyhow::Result<CorsLayer> {
+    let origins = match allowed_origins {
+        Some(origins) if !origins.is_empty() => origins,
+        _ if development_mode => {
+            tracing::warn!("CORS_ALLOWED_ORIGINS 未配置，开发模式下允许所有跨域请求");
+            return Ok(CorsLayer::permissive());
+        }
+        // 不返回 Access-Control-Allow-Origin，浏览器会拒绝所有跨域请求
+        _ => return Ok(CorsLayer::new()),
+    };
+
+    let layer = CorsLayer::new()
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([
+            ACCEPT,
+            AUTHORIZATION,
+            CONTENT_TYPE,
+            HeaderName::from_static(DEVICE_TYPE_HEADER),
+            HeaderName::from_static(REQUEST_ID_HEADER),
+            HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+            IF_NONE_MATCH,
+        ])
+        .expose_headers([
+            ETAG,
+            HeaderName::from_static(REQUEST_ID_HEADER),
+            HeaderName::from_static(IDEMPOTENT_REPLAYED_HEADER),
+        ])
+        .max_age(PREFLIGHT_MAX_AGE);
+
+    // 通配符源不能与凭据一起使用（浏览器会拒绝这样的响应）
+    if origins.iter().any(|origin| origin == WILDCARD_ORIGIN) {
+        anyhow::ensure!(
+            origins.len() == 1,
+            "CORS_ALLOWED_ORIGINS 中的 `*` 不能与具体的源同时配置"
+        );
+        return Ok(layer.allow_origin(AllowOrigin::any()));
+    }
+
+    let origins = origins
+        .iter()
+        .map(|origin| {
+            HeaderValue::from_str(origin)
+                .with_context(|| format!("CORS_ALLOWED_ORIGINS 中的源无效: {}", origin))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    Ok(layer
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_credentials(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header::ORIGIN, Request},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    async fn allow_origin_header(layer: CorsLayer, origin: &str) -> Option<String> {
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(layer);
+
+        let response = app
+            .oneshot(
+                Request::get("/")
+                    .header(ORIGIN, origin)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        response
+            .headers()
+            .get("access-control-allow-origin")
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_allowed_origin_is_reflected() {
+        let origins = vec![
+            "https://app.example.com".to_string(),
+            "http://localhost:3001".to_string(),
+        ];
+        let layer = cors_layer(Some(&origins), false).unwrap();
+
+        assert_eq!(
+            allow_origin_header(layer.clone(), "https://app.example.com").await,
+            Some("https://app.example.com".to_string())
+        );
+        assert_eq!(
+            allow_origin_header(layer, "https://evil.example.com").await,
+            None
+        );
+    }
+
+    /// 发送预检请求，返回允许的请求头和暴露的响应头
+    async fn preflight(layer: CorsLayer, request_headers: &str) -> (String, String) {
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(layer);
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::options("/")
+                    .header(ORIGIN, "https://app.example.com")
+                    .header("access-control-request-method", "POST")
+                    .header("access-control-request-headers", request_headers)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let allow_headers = response.headers()["access-control-allow-headers"]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        // 暴露的响应头在实际请求的响应中返回
+        let response = app
+            .oneshot(
+                Request::get("/")
+                    .header(ORIGIN, "https://app.example.com")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let expose_headers = response.headers()["access-control-expose-headers"]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        (allow_headers, expose_headers)
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_headers_are_allowed() {
+        let origins = vec!["https://app.example.com".to_string()];
+        let layer = cors_layer(Some(&origins), false).unwrap();
+
+        let (allow_headers, expose_headers) = preflight(layer, IDEMPOTENCY_KEY_HEADER).await;
+        assert!(
+            allow_headers.contains(IDEMPOTENCY_KEY_HEADER),
+            "{}",
+            allow_headers
+        );
+        assert!(
+            expose_headers.contains(IDEMPOTENT_REPLAYED_HEADER),
+            "{}",
+            expose_headers
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conditional_request_headers_are_allowed() {
+        let origins = vec!["https://app.example.com".to_string()];
+        let layer = cors_layer(Some(&origins), false).unwrap();
+
+        let (allow_headers, expose_headers) = preflight(layer, "if-none-match").await;
+        assert!(allow_headers.contains("if-none-match"), "{}", allow_headers);
+        assert!(expose_headers.contains("etag"), "{}", expose_headers);
+    }
+
+    #[tokio::test]
+    async fn test_unconfigured_origins() {
+        let production = cors_layer(None, false).unwrap();
+        assert_eq!(
+            allow_origin_header(production, "https://app.example.com").await,
+            None
+        );
+
+        let development = cors_layer(None, true).unwrap();
+        assert_eq!(
+            allow_origin_header(development, "https://app.example.com").await,
+            Some("*".to_string())
+        );
+    }
+
+    #[test]
+    fn test_wildcard_cannot_be_mixed_with_origins() {
+        let wildcard = vec!["*".to_string()];
+        assert!(cors_layer(Some(&wildcard), false).is_ok());
+
+        let mixed = vec!["*".to_string(), "https://app.example.com".to_string()];
+        assert!(cors_layer(Some(&mixed), false).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+/*!
+ * 设备上下文提取器
+ *
+ * 从请求头中提取客户端设备信息和 IP 地址，供登录、注册等需要创建会话的处理器使用。
+ * 只读取请求头，可以与 `Json<T>` 等消费请求体的提取器同时使用。
+ */
+
+use std::convert::Infallible;
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::USER_AGENT, request::Parts, HeaderMap},
+};
+
+use crate::utils::DeviceInfo;
+
+/// 设备类型提示请求头
+pub const DEVICE_TYPE_HEADER: &str = "x-device-type";
+
+/// 设备上下文
+///
+/// 包含解析后的设备信息和客户端 IP 地址（IP 无法确定时为 `None`）。
+///
+/// # 提取规则
+///
+/// - 设备类型优先使用 `X-Device-Type` 请求头，否则根据 `User-Agent` 推断
+/// - IP 地址优先使用 `X-Forwarded-For` 中的第一个地址，其次是 `X-Real-IP`
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_rust::middleware::DeviceContext;
+///
+/// async fn login(DeviceContext(device_info, ip_address): DeviceContext) {
+///     tracing::info!("{} 登录，IP: {:?}", device_info.device_type, ip_address);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeviceContext(pub DeviceInfo, pub Option<String>);
+
+impl DeviceContext {
+    /// 从请求头构造设备上下文
+    ///
+    /// # 参数
+    ///
+    /// * `headers` - HTTP 请求头
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        // 从请求头中获取 User-Agent
+        let user_agent = headers
+            .get(USER_AGENT)
+            .and_then(|header| header.to_str().ok())
+            .unwrap_or("Unknown");
+
+        // 从请求头中获取设备类型提示（可选的自定义头部）
+        let device_type_hint = headers
+            .get(DEVICE_TYPE_HEADER)
+            .and_then(|header| header.to_str().ok());
+
+        // 提取IP地址（从代理头部）
+        let ip_address = headers
+            .get("X-Forwarded-For")
+            .or_else(|| headers.get("X-Real-IP"))
+            .and_then(|header| header.to_str().ok())
+            .map(|s| s.split(',').next().unwrap_or(s).trim().to_string());
+
+        DeviceContext(
+            DeviceInfo::from_user_agent(user_agent, device_type_hint),
+            ip_address,
+        )
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for DeviceContext
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::DeviceType;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_device_context_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(DEVICE_TYPE_HEADER, HeaderValue::from_static("mobile"));
+        headers.insert(
+            "X-Forwarded-For",
+            HeaderValue::from_static("203.0.113.7, 10.0.0.1"),
+        );
+        headers.insert("X-Real-IP", HeaderValue::from_static("10.0.0.2"));
+
+        let DeviceContext(device_info, ip_address) = DeviceContext::from_headers(&headers);
+        assert_eq!(device_info.device_type, DeviceType::Mobile);
+        assert_eq!(ip_address.as_deref(), Some("203.0.113.7"));
+    }
+
+    #[test]
+    fn test_device_context_without_headers() {
+        let DeviceContext(device_info, ip_address) = DeviceContext::from_headers(&HeaderMap::new());
+        assert_eq!(device_info.user_agent.as_deref(), Some("Unknown"));
+        assert_eq!(ip_address, None);
+    }
+}
@@ -0,0 +1,184 @@
+/*!
+ * 统一错误格式的请求提取器
+ *
+ * Axum 自带的 `Json<T>` 和 `Path<T>` 提取失败时返回纯文本响应，
+ * 与其他接口的 `{"error": ...}` JSON 错误格式不一致。
+ * `AppJson<T>` 和 `AppPath<T>` 包装了对应的提取器，把拒绝转换为 `AppError`，
+ * 所有处理器都应使用它们代替 `Json<T>` 和 `Path<T>` 提取请求数据。
+ */
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Path, Request},
+    http::request::Parts,
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::error::{AppError, Result};
+
+/// JSON 请求体提取器
+///
+/// 与 `Json<T>` 相同，但 JSON 格式错误、缺少 `Content-Type` 或请求体过大时
+/// 返回统一的 JSON 错误响应（见 `From<JsonRejection> for AppError`）。
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_rust::{middleware::AppJson, models::LoginRequest};
+///
+/// async fn login(AppJson(request): AppJson<LoginRequest>) {
+///     tracing::info!("{} 尝试登录", request.email);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AppJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for AppJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self> {
+        let Json(value) = Json::<T>::from_request(request, state).await?;
+        Ok(Self(value))
+    }
+}
+
+/// 路径参数提取器
+///
+/// 与 `Path<T>` 相同，但参数无法解析为目标类型时返回统一的 JSON 错误响应
+/// （见 `From<PathRejection> for AppError`）。
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_rust::middleware::AppPath;
+///
+/// async fn logout_device(AppPath(device_type): AppPath<String>) {
+///     tracing::info!("撤销 {} 设备的会话", device_type);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AppPath<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for AppPath<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+        let Path(value) = Path::<T>::from_request_parts(parts, state).await?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header::CONTENT_TYPE, StatusCode},
+        response::Response,
+        routing::{get, post},
+        Router,
+    };
+    use serde::Deserialize;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    #[derive(Deserialize)]
+    struct Greeting {
+        name: String,
+    }
+
+    async fn greet(AppJson(greeting): AppJson<Greeting>) -> String {
+        format!("hello {}", greeting.name)
+    }
+
+    async fn show(AppPath(id): AppPath<Uuid>) -> String {
+        id.to_string()
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/greet", post(greet))
+            .route("/items/:id", get(show))
+    }
+
+    async fn send(request: axum::http::Request<Body>) -> Response {
+        app().oneshot(request).await.unwrap()
+    }
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn post_json(body: &'static str) -> axum::http::Request<Body> {
+        axum::http::Request::post("/greet")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_valid_requests() {
+        let response = send(post_json(r#"{"name":"rust"}"#)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let id = Uuid::new_v4();
+        let response = send(
+            axum::http::Request::get(format!("/items/{}", id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_returns_json_error() {
+        let response = send(post_json(r#"{"name":"#)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert!(body["error"].as_str().unwrap().starts_with("JSON解析失败"));
+
+        // 字段类型不匹配
+        let response = send(post_json(r#"{"name":42}"#)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert!(body["error"].as_str().unwrap().starts_with("JSON解析失败"));
+
+        // 缺少 Content-Type
+        let response = send(
+            axum::http::Request::post("/greet")
+                .body(Body::from(r#"{"name":"rust"}"#))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(json_body(response).await["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_bad_path_param_returns_json_error() {
+        let response = send(
+            axum::http::Request::get("/items/not-a-uuid")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert!(body["error"].as_str().unwrap().starts_with("路径参数无效"));
+    }
+}
@@ -0,0 +1,798 @@
+/*!
+ * 幂等键中间件
+ *
+ * 客户端在 POST 请求中携带 `Idempotency-Key` 请求头时，第一次成功的响应会
+ * 保存在 Redis 中，之后使用相同幂等键的重试请求直接返回保存的响应，
+ * 不会重复发送邮件、撤销会话等有副作用的操作。
+ *
+ * 已认证的请求按用户 ID 隔离幂等键，同一幂等键只能用于相同的请求（方法、路径和请求体一致），
+ * 否则返回 `409 Conflict`。未认证的请求无法可靠地区分调用方（`X-Forwarded-For` 可以伪造），
+ * 幂等记录以幂等键、请求指纹和 `Authorization` 请求头的摘要共同定位，只有完全相同的请求
+ * 才会得到保存的响应；在处理器中自行验证 Token 的接口（如退出登录）不会把一个客户端的响应
+ * 重放给携带其他 Token 的客户端。
+ *
+ * 响应体使用 AES-256-GCM 加密后保存，密钥由幂等键、请求方法、路径、`Authorization` 请求头
+ * 和请求体派生，不保存在服务端，只有重试完全相同的请求时才能解密。因此签发令牌的接口
+ * （注册、登录、刷新令牌）也可以使用幂等键，Redis 中不会出现可用的令牌。
+ */
+
+use std::{future::Future, pin::Pin};
+
+use axum::{
+    body::{to_bytes, Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{
+        header::{
+            AUTHORIZATION, CONNECTION, CONTENT_LENGTH, DATE, PROXY_AUTHENTICATE, SET_COOKIE, TE,
+            TRAILER, TRANSFER_ENCODING, UPGRADE,
+        },
+        request::Parts,
+        HeaderMap, HeaderName, HeaderValue, StatusCode,
+    },
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::{AppError, Result},
+    middleware::REQUEST_ID_HEADER,
+    redis::RedisManager,
+    utils::{cache_keys, AuthUser, CryptoUtils},
+};
+
+/// 幂等键请求头
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// 标记响应来自幂等缓存的响应头
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// 幂等记录的保存时间（10 分钟），覆盖客户端的重试窗口即可
+const IDEMPOTENCY_TTL_SECONDS: u64 = 10 * 60;
+
+/// 处理中标记的保存时间
+///
+/// 请求处理完成后标记会被替换或删除；处理器中途被取消（如客户端断开连接）时，
+/// 标记在该时间后过期，之后可以使用相同的幂等键重试。
+const PENDING_TTL_SECONDS: u64 = 60;
+
+/// 幂等键的最大长度
+const MAX_KEY_LENGTH: usize = 255;
+
+/// 保存的响应体的最大字节数，超出或长度未知（如流式响应）时不保存
+const MAX_STORED_RESPONSE_BYTES: usize = 256 * 1024;
+
+/// 不随响应保存的响应头：逐跳头、由服务器重新生成的头和不应重放的 Cookie
+const UNSTORED_HEADERS: &[HeaderName] = &[
+    CONNECTION,
+    TRANSFER_ENCODING,
+    TE,
+    TRAILER,
+    UPGRADE,
+    PROXY_AUTHENTICATE,
+    CONTENT_LENGTH,
+    DATE,
+    SET_COOKIE,
+];
+
+/// 幂等键中间件返回的 Future 类型
+pub type IdempotencyFuture = Pin<Box<dyn Future<Output = Result<Response>> + Send>>;
+
+/// 保存在 Redis 中的幂等记录
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum IdempotencyRecord {
+    /// 第一次请求仍在处理中
+    Pending {
+        /// 请求指纹（方法、路径和请求体的 SHA-256 摘要）
+        request_fingerprint: String,
+    },
+    /// 第一次请求已成功完成
+    Completed(StoredResponse),
+}
+
+impl IdempotencyRecord {
+    fn request_fingerprint(&self) -> &str {
+        match self {
+            IdempotencyRecord::Pending {
+                request_fingerprint,
+            } => request_fingerprint,
+            IdempotencyRecord::Completed(stored) => &stored.request_fingerprint,
+        }
+    }
+}
+
+/// 保存的成功响应
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredResponse {
+    /// 请求指纹（方法、路径和请求体的 SHA-256 摘要）
+    request_fingerprint: String,
+    /// 响应状态码
+    status: u16,
+    /// 可以重放的响应头（如 `Content-Type`、`ETag`、`Location`）
+    headers: Vec<(String, String)>,
+    /// 加密后的响应体（Base64），密钥见 `response_key`
+    body: String,
+}
+
+/// 创建幂等键中间件
+///
+/// 与 `axum::middleware::from_fn_with_state` 配合使用，状态为 Redis 管理器。
+/// 只处理携带 `Idempotency-Key` 的 POST 请求，其他请求直接放行。
+/// 只保存 2xx 响应，失败的请求可以使用相同的幂等键重试。
+/// 响应体超过 `MAX_STORED_RESPONSE_BYTES` 或长度未知时不保存，重试请求会再次执行。
+/// 第一次请求处理期间收到的重复请求不会执行，直接返回 `409 Conflict`。
+///
+/// 需要按用户隔离时，应放在 `auth_middleware` 内层（即作为 `auth_middleware` 之前添加的 layer）。
+///
+/// # 参数
+///
+/// * `max_body_bytes` - 计算请求指纹时读取的最大请求体字节数，应与 `Config::max_request_body_bytes` 一致
+///
+/// # 错误处理
+///
+/// - `400 Bad Request`: 幂等键为空或过长
+/// - `409 Conflict`: 幂等键已用于不同的请求，或相同的请求仍在处理中
+/// - `413 Payload Too Large`: 请求体超过 `max_body_bytes`
+/// - `500 Internal Server Error`: Redis 操作失败
+///
+/// # 示例
+///
+/// ```rust,ignore
+/// use axum::{middleware, routing::post, Router};
+/// use hello_rust::middleware::idempotency_middleware;
+///
+/// let auth_routes = Router::new()
+///     .route("/forgot-password", post(forgot_password))
+///     .route_layer(middleware::from_fn_with_state(
+///         redis.clone(),
+///         idempotency_middleware(config.max_request_body_bytes),
+///     ));
+/// ```
+pub fn idempotency_middleware(
+    max_body_bytes: usize,
+) -> impl Fn(State<RedisManager>, Request, Next) -> IdempotencyFuture + Clone + Send + Sync + 'static
+{
+    move |State(redis): State<RedisManager>, request: Request, next: Next| -> IdempotencyFuture {
+        Box::pin(handle_idempotency(redis, max_body_bytes, request, next))
+    }
+}
+
+/// 幂等键中间件的具体实现
+async fn handle_idempotency(
+    redis: RedisManager,
+    max_body_bytes: usize,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let Some(idempotency_key) = idempotency_key(&request)? else {
+        return Ok(next.run(request).await);
+    };
+
+    // 缓冲请求体以计算请求指纹，之后重新放回请求
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, max_body_bytes)
+        .await
+        .map_err(|_| AppError::PayloadTooLarge("Request body too large".to_string()))?;
+    let request_fingerprint = {
+        let mut data = format!("{} {}\n", parts.method, parts.uri.path()).into_bytes();
+        data.extend_from_slice(&body);
+        CryptoUtils::sha256_hex(&data)
+    };
+
+    let response_key = response_key(&idempotency_key, &parts, &body);
+
+    // 未认证的请求没有可信的调用方标识，把请求指纹和 Authorization 请求头的摘要并入键名，
+    // 不同请求、不同 Token 之间互不影响
+    let scope = match parts.extensions.get::<AuthUser>() {
+        Some(user) => format!("user:{}:{}", user.id, idempotency_key),
+        None => format!(
+            "anonymous:{}",
+            CryptoUtils::sha256_hex(
+                format!(
+                    "{}\n{}\n{}",
+                    idempotency_key,
+                    request_fingerprint,
+                    CryptoUtils::sha256_hex(authorization(&parts))
+                )
+                .as_bytes()
+            )
+        ),
+    };
+    let redis_key = format!("{}{}", cache_keys::IDEMPOTENCY_PREFIX, scope);
+
+    // 写入处理中标记，已有记录时根据记录状态返回
+    let backend = redis.backend();
+    let pending = serde_json::to_string(&IdempotencyRecord::Pending {
+        request_fingerprint: request_fingerprint.clone(),
+    })
+    .map_err(|e| AppError::Internal(e.into()))?;
+    let acquired = backend
+        .set_if_absent(&redis_key, &pending, PENDING_TTL_SECONDS)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis写入幂等记录失败: {}", e)))?;
+    if !acquired {
+        return existing_record(&redis, &redis_key, &request_fingerprint, &response_key).await;
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if !response.status().is_success() {
+        // 失败的请求可以使用相同的幂等键重试
+        if let Err(e) = backend.del(&redis_key).await {
+            tracing::warn!("删除幂等处理中标记失败: {}", e);
+        }
+        return Ok(response);
+    }
+
+    // 响应体过大或长度未知时不缓冲，只删除处理中标记
+    let (parts, body) = response.into_parts();
+    let storable = body
+        .size_hint()
+        .upper()
+        .is_some_and(|upper| upper <= MAX_STORED_RESPONSE_BYTES as u64);
+    if !storable {
+        if let Err(e) = backend.del(&redis_key).await {
+            tracing::warn!("删除幂等处理中标记失败: {}", e);
+        }
+        return Ok(Response::from_parts(parts, body));
+    }
+
+    // 加密保存成功的响应；加密失败时只删除处理中标记
+    let body = to_bytes(body, MAX_STORED_RESPONSE_BYTES)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("读取响应体失败: {}", e)))?;
+    let saved = match CryptoUtils::encrypt_aes_gcm(&body, &response_key) {
+        Ok(ciphertext) => {
+            let record = IdempotencyRecord::Completed(StoredResponse {
+                request_fingerprint,
+                status: parts.status.as_u16(),
+                headers: stored_headers(&parts.headers),
+                body: CryptoUtils::base64_encode(&ciphertext),
+            });
+            let record =
+                serde_json::to_string(&record).map_err(|e| AppError::Internal(e.into()))?;
+            backend
+                .set(&redis_key, &record, Some(IDEMPOTENCY_TTL_SECONDS))
+                .await
+        }
+        Err(_) => backend.del(&redis_key).await.map(|_| ()),
+    };
+    if let Err(e) = saved {
+        tracing::warn!("保存幂等记录失败: {}", e);
+    }
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// 处理已有幂等记录的重复请求
+async fn existing_record(
+    redis: &RedisManager,
+    redis_key: &str,
+    request_fingerprint: &str,
+    response_key: &[u8; 32],
+) -> Result<Response> {
+    let stored = redis
+        .backend()
+        .get(redis_key)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis读取幂等记录失败: {}", e)))?;
+
+    // 记录在两次操作之间过期或被删除（第一次请求失败），按处理中对待，客户端稍后重试即可
+    let Some(stored) = stored else {
+        return Err(in_progress());
+    };
+
+    let record: IdempotencyRecord = serde_json::from_str(&stored)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("幂等记录格式无效: {}", e)))?;
+    if record.request_fingerprint() != request_fingerprint {
+        return Err(different_request());
+    }
+
+    match record {
+        IdempotencyRecord::Pending { .. } => Err(in_progress()),
+        // 请求指纹相同但无法解密：同一用户使用了不同的 Authorization 请求头，视为不同的请求
+        IdempotencyRecord::Completed(stored) => replay(stored, response_key),
+    }
+}
+
+/// 幂等键已用于不同的请求
+fn different_request() -> AppError {
+    AppError::Conflict("Idempotency-Key has already been used for a different request".to_string())
+}
+
+/// 相同的请求仍在处理中
+fn in_progress() -> AppError {
+    AppError::Conflict("A request with this Idempotency-Key is still being processed".to_string())
+}
+
+/// 请求的 `Authorization` 请求头，没有时为空
+fn authorization(parts: &Parts) -> &[u8] {
+    parts
+        .headers
+        .get(AUTHORIZATION)
+        .map(HeaderValue::as_bytes)
+        .unwrap_or_default()
+}
+
+/// 派生加密保存的响应体所用的 AES-256 密钥
+///
+/// 密钥只由请求内容计算，不保存在服务端：Redis 中的数据泄露时，没有原始请求
+/// （请求体中的密码、刷新令牌或 `Authorization` 中的访问令牌）无法解密保存的响应。
+fn response_key(idempotency_key: &str, parts: &Parts, body: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"idempotency-response\n");
+    hasher.update(idempotency_key.as_bytes());
+    hasher.update(format!("\n{} {}\n", parts.method, parts.uri.path()).as_bytes());
+    hasher.update(authorization(parts));
+    hasher.update(b"\n");
+    hasher.update(body);
+    hasher.finalize().into()
+}
+
+/// 读取 POST 请求的幂等键
+fn idempotency_key(request: &Request) -> Result<Option<String>> {
+    if request.method() != axum::http::Method::POST {
+        return Ok(None);
+    }
+
+    let Some(value) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+
+    let key = value.to_str().unwrap_or_default().trim();
+    if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Idempotency-Key must be between 1 and {} visible ASCII characters",
+            MAX_KEY_LENGTH
+        )));
+    }
+
+    Ok(Some(key.to_string()))
+}
+
+/// 选出需要随响应保存的响应头，跳过 `UNSTORED_HEADERS`、请求 ID 和值不是可见 ASCII 的头
+fn stored_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter(|(name, _)| {
+            !UNSTORED_HEADERS.contains(name)
+                && name.as_str() != REQUEST_ID_HEADER
+                && name.as_str() != IDEMPOTENT_REPLAYED_HEADER
+        })
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+/// 解密保存的记录并重建响应
+fn replay(stored: StoredResponse, response_key: &[u8; 32]) -> Result<Response> {
+    let body = CryptoUtils::base64_decode(&stored.body)
+        .ok()
+        .and_then(|ciphertext| CryptoUtils::decrypt_aes_gcm(&ciphertext, response_key).ok())
+        .ok_or_else(different_request)?;
+
+    let mut response = Response::new(Body::from(Bytes::from(body)));
+    *response.status_mut() = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+
+    let headers = response.headers_mut();
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        http::header::{CONTENT_TYPE, ETAG, LOCATION},
+        middleware,
+        response::IntoResponse,
+        routing::post,
+        Router,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    fn app(calls: Arc<AtomicUsize>) -> Router {
+        Router::new()
+            .route(
+                "/send",
+                post(move |body: String| {
+                    let calls = calls.clone();
+                    async move {
+                        let count = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                        if body == "fail" {
+                            return Err(AppError::Validation("invalid".to_string()));
+                        }
+                        Ok(axum::Json(
+                            serde_json::json!({ "count": count, "body": body }),
+                        ))
+                    }
+                }),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                RedisManager::in_memory(),
+                idempotency_middleware(1024),
+            ))
+    }
+
+    fn request(user: Option<Uuid>, key: Option<&str>, body: &str) -> Request {
+        request_with_token(user, key, body, None)
+    }
+
+    fn request_with_token(
+        user: Option<Uuid>,
+        key: Option<&str>,
+        body: &str,
+        token: Option<&str>,
+    ) -> Request {
+        let mut builder = Request::post("/send");
+        if let Some(user) = user {
+            builder = builder.extension(AuthUser::new(user));
+        }
+        if let Some(key) = key {
+            builder = builder.header(IDEMPOTENCY_KEY_HEADER, key);
+        }
+        if let Some(token) = token {
+            builder = builder.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+        builder.body(Body::from(body.to_string())).unwrap()
+    }
+
+    async fn body_string(response: Response) -> String {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_key_replays_response() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+        let user = Some(Uuid::new_v4());
+
+        let first = app
+            .clone()
+            .oneshot(request(user, Some("key-1"), "a"))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        assert!(first.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        let first_body = body_string(first).await;
+
+        let second = app
+            .clone()
+            .oneshot(request(user, Some("key-1"), "a"))
+            .await
+            .unwrap();
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(second.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(second.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(body_string(second).await, first_body);
+
+        // 处理器只执行了一次
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 相同幂等键用于不同的请求体
+        let conflict = app
+            .clone()
+            .oneshot(request(user, Some("key-1"), "b"))
+            .await
+            .unwrap();
+        assert_eq!(conflict.status(), StatusCode::CONFLICT);
+
+        // 不带幂等键、使用新幂等键或由其他用户发起的请求正常执行
+        app.clone().oneshot(request(user, None, "a")).await.unwrap();
+        app.clone()
+            .oneshot(request(user, Some("key-2"), "a"))
+            .await
+            .unwrap();
+        app.oneshot(request(Some(Uuid::new_v4()), Some("key-1"), "a"))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_requests_are_keyed_by_fingerprint() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+
+        // 不同的匿名请求即使使用相同的幂等键也互不影响
+        let first = app
+            .clone()
+            .oneshot(request(None, Some("key-1"), "a"))
+            .await
+            .unwrap();
+        let other = app
+            .clone()
+            .oneshot(request(None, Some("key-1"), "b"))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(other.status(), StatusCode::OK);
+        assert!(other.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+
+        // 完全相同的请求返回保存的响应
+        let replayed = app
+            .oneshot(request(None, Some("key-1"), "a"))
+            .await
+            .unwrap();
+        assert_eq!(replayed.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stored_response_is_encrypted() {
+        let redis = RedisManager::in_memory();
+        let app = Router::new()
+            .route(
+                "/login",
+                post(|| async { axum::Json(serde_json::json!({ "token": "secret-token" })) }),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                redis.clone(),
+                idempotency_middleware(1024),
+            ));
+        let login = || {
+            Request::post("/login")
+                .header(IDEMPOTENCY_KEY_HEADER, "key-1")
+                .body(Body::from(r#"{"password":"p"}"#))
+                .unwrap()
+        };
+
+        app.clone().oneshot(login()).await.unwrap();
+
+        // Redis 中只有密文，令牌不会以明文出现
+        let (_, keys) = redis
+            .backend()
+            .scan(0, &format!("{}*", cache_keys::IDEMPOTENCY_PREFIX), 100)
+            .await
+            .unwrap();
+        assert_eq!(keys.len(), 1);
+        let stored = redis.backend().get(&keys[0]).await.unwrap().unwrap();
+        assert!(!stored.contains("secret-token"), "{}", stored);
+
+        // 重试完全相同的请求可以解密并重放
+        let replayed = app.oneshot(login()).await.unwrap();
+        assert_eq!(replayed.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(body_string(replayed).await, r#"{"token":"secret-token"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_same_user_with_different_token_is_not_replayed() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+        let user = Some(Uuid::new_v4());
+
+        app.clone()
+            .oneshot(request_with_token(
+                user,
+                Some("key-1"),
+                "a",
+                Some("token-a"),
+            ))
+            .await
+            .unwrap();
+        let response = app
+            .oneshot(request_with_token(
+                user,
+                Some("key-1"),
+                "a",
+                Some("token-b"),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_requests_are_keyed_by_authorization() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+
+        // 处理器中自行验证 Token 的接口：不同 Token 的请求不会得到其他客户端保存的响应
+        let first = app
+            .clone()
+            .oneshot(request_with_token(None, Some("key-1"), "", Some("token-a")))
+            .await
+            .unwrap();
+        let other = app
+            .clone()
+            .oneshot(request_with_token(None, Some("key-1"), "", Some("token-b")))
+            .await
+            .unwrap();
+        assert!(first.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        assert!(other.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+
+        let replayed = app
+            .oneshot(request_with_token(None, Some("key-1"), "", Some("token-a")))
+            .await
+            .unwrap();
+        assert_eq!(replayed.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_request_can_be_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(calls.clone());
+        let user = Some(Uuid::new_v4());
+
+        for _ in 0..2 {
+            let response = app
+                .clone()
+                .oneshot(request(user, Some("key-1"), "fail"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicate_is_rejected_while_pending() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let release = Arc::new(Notify::new());
+        let app = {
+            let calls = calls.clone();
+            let release = release.clone();
+            Router::new()
+                .route(
+                    "/send",
+                    post(move || {
+                        let calls = calls.clone();
+                        let release = release.clone();
+                        async move {
+                            calls.fetch_add(1, Ordering::SeqCst);
+                            release.notified().await;
+                            "done"
+                        }
+                    }),
+                )
+                .route_layer(middleware::from_fn_with_state(
+                    RedisManager::in_memory(),
+                    idempotency_middleware(1024),
+                ))
+        };
+        let user = Some(Uuid::new_v4());
+
+        let first = tokio::spawn(app.clone().oneshot(request(user, Some("key-1"), "a")));
+        while calls.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        // 第一次请求处理期间的重复请求不会执行处理器
+        let duplicate = app
+            .clone()
+            .oneshot(request(user, Some("key-1"), "a"))
+            .await
+            .unwrap();
+        assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+
+        release.notify_one();
+        assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::OK);
+
+        let replayed = app
+            .oneshot(request(user, Some("key-1"), "a"))
+            .await
+            .unwrap();
+        assert_eq!(replayed.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    fn app_with_response(response: fn() -> Response, calls: Arc<AtomicUsize>) -> Router {
+        Router::new()
+            .route(
+                "/send",
+                post(move || {
+                    let calls = calls.clone();
+                    async move {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        response()
+                    }
+                }),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                RedisManager::in_memory(),
+                idempotency_middleware(1024),
+            ))
+    }
+
+    #[tokio::test]
+    async fn test_replay_restores_response_headers() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app_with_response(
+            || {
+                (
+                    StatusCode::CREATED,
+                    [
+                        (ETAG, "\"v1\""),
+                        (LOCATION, "/api/items/1"),
+                        (SET_COOKIE, "session=secret"),
+                    ],
+                    "created",
+                )
+                    .into_response()
+            },
+            calls.clone(),
+        );
+        let user = Some(Uuid::new_v4());
+
+        app.clone()
+            .oneshot(request(user, Some("key-1"), "a"))
+            .await
+            .unwrap();
+        let replayed = app
+            .oneshot(request(user, Some("key-1"), "a"))
+            .await
+            .unwrap();
+
+        assert_eq!(replayed.status(), StatusCode::CREATED);
+        assert_eq!(replayed.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(replayed.headers()[ETAG], "\"v1\"");
+        assert_eq!(replayed.headers()[LOCATION], "/api/items/1");
+        assert_eq!(
+            replayed.headers()[CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        assert!(replayed.headers().get(SET_COOKIE).is_none());
+        assert_eq!(body_string(replayed).await, "created");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_large_response_is_not_stored() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app_with_response(
+            || "x".repeat(MAX_STORED_RESPONSE_BYTES + 1).into_response(),
+            calls.clone(),
+        );
+        let user = Some(Uuid::new_v4());
+
+        for _ in 0..2 {
+            let response = app
+                .clone()
+                .oneshot(request(user, Some("key-1"), "a"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+            assert_eq!(
+                body_string(response).await.len(),
+                MAX_STORED_RESPONSE_BYTES + 1
+            );
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_body_larger_than_limit_is_rejected() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let response = app(calls.clone())
+            .oneshot(request(
+                Some(Uuid::new_v4()),
+                Some("key-1"),
+                &"x".repeat(2048),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -7,10 +7,65 @@
  * # 子模块
  *
  * - `auth`: 身份验证中间件，验证 JWT Token 并提取用户信息
+ * - `role`: 角色授权中间件，限制只有特定角色才能访问的路由
+ * - `optional_auth`: 可选身份验证提取器，支持匿名访问的端点
+ * - `request_id`: 请求 ID 中间件，为每个请求分配用于日志关联的唯一 ID
+ * - `device_context`: 设备上下文提取器，从请求头提取设备信息和客户端 IP
+ * - `cors`: CORS 跨域中间件，根据配置的源列表构建
+ * - `timeout`: 请求超时中间件，处理时间过长时取消请求并返回 504
+ * - `conditional`: 条件请求提取器，为只读接口生成 ETag 并处理 `If-None-Match`
+ * - `idempotency`: 幂等键中间件，重试的 POST 请求直接返回第一次的响应
+ * - `pagination`: 分页参数提取器，统一解析列表接口的 `page` 和 `per_page`
+ * - `extract`: `AppJson`、`AppPath` 提取器，解析失败时返回统一的 JSON 错误响应
+ * - `trace_sampling`: 请求追踪采样，按比例输出请求追踪日志以降低日志量
  */
 
 /// 身份验证中间件
 pub mod auth;
 
+/// 角色授权中间件
+pub mod role;
+
+/// 可选身份验证提取器
+pub mod optional_auth;
+
+/// 请求 ID 中间件
+pub mod request_id;
+
+/// 设备上下文提取器
+pub mod device_context;
+
+/// CORS 跨域中间件
+pub mod cors;
+
+/// 请求超时中间件
+pub mod timeout;
+
+/// 条件请求提取器
+pub mod conditional;
+
+/// 幂等键中间件
+pub mod idempotency;
+
+/// 分页参数提取器
+pub mod pagination;
+
+/// 统一错误格式的请求提取器
+pub mod extract;
+
+/// 请求追踪采样
+pub mod trace_sampling;
+
 // 重新导出所有中间件函数，方便外部使用
 pub use auth::*;
+pub use conditional::*;
+pub use cors::*;
+pub use device_context::*;
+pub use extract::*;
+pub use idempotency::*;
+pub use optional_auth::*;
+pub use pagination::*;
+pub use request_id::*;
+pub use role::*;
+pub use timeout::*;
+pub use trace_sampling::*;
@@ -0,0 +1,170 @@
+/*!
+ * 可选身份验证提取器
+ *
+ * 为同时支持登录用户和匿名用户的端点提供身份提取功能。
+ * 与 `auth_middleware` 不同，缺少 Token 或 Token 无效时不会拒绝请求，
+ * 而是交由处理器根据是否登录决定行为。
+ */
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts, HeaderMap},
+};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    routes::AppState,
+    services::TokenService,
+};
+
+/// 可选的当前用户
+///
+/// 包含已登录用户的 ID；匿名请求或 Token 无效时为 `None`。
+///
+/// # 提取规则
+///
+/// - 缺少 `Authorization` 头 -> `OptionalUser(None)`
+/// - `Authorization` 头格式不正确 -> `401 Unauthorized`
+/// - Token 无效、已过期或已被撤销 -> `OptionalUser(None)`
+/// - Token 有效 -> `OptionalUser(Some(user_id))`
+///
+/// # 示例
+///
+/// ```rust
+/// use axum::Json;
+/// use hello_rust::middleware::OptionalUser;
+///
+/// async fn greeting(OptionalUser(user_id): OptionalUser) -> Json<serde_json::Value> {
+///     match user_id {
+///         Some(id) => Json(serde_json::json!({ "message": format!("欢迎回来, {}", id) })),
+///         None => Json(serde_json::json!({ "message": "欢迎访问" })),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionalUser(pub Option<Uuid>);
+
+#[async_trait]
+impl FromRequestParts<AppState> for OptionalUser {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self> {
+        // 没有携带 Token 的请求视为匿名用户
+        let Some(token) = bearer_token(&parts.headers)? else {
+            return Ok(OptionalUser(None));
+        };
+
+        // Token 无效时同样视为匿名用户，而不是拒绝请求
+        let user_id = match TokenService::verify_token(&state.redis, token, &state.jwt_keys).await {
+            Ok(claims) => Uuid::parse_str(&claims.sub).ok(),
+            Err(_) => None,
+        };
+
+        Ok(OptionalUser(user_id))
+    }
+}
+
+/// 从请求头中提取 Bearer Token
+///
+/// # 返回值
+///
+/// - `Ok(None)`: 请求未携带 `Authorization` 头
+/// - `Ok(Some(token))`: 成功提取 Token
+/// - `Err(AppError::Authentication)`: `Authorization` 头存在但格式不正确
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Result<Option<&str>> {
+    let Some(header) = headers.get(AUTHORIZATION) else {
+        return Ok(None);
+    };
+
+    header
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(Some)
+        .ok_or_else(|| AppError::Authentication("Invalid authorization header format".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::Config,
+        redis::test_support,
+        services::SessionPolicy,
+        utils::{AuthUser, DeviceInfo, DeviceType, JwtKeys},
+    };
+    use axum::http::{HeaderValue, Request};
+
+    #[test]
+    fn test_bearer_token_parsing() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers).unwrap(), None);
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer abc"));
+        assert_eq!(bearer_token(&headers).unwrap(), Some("abc"));
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Basic abc"));
+        assert!(bearer_token(&headers).is_err());
+    }
+
+    async fn test_state() -> AppState {
+        let config = Config::from_env().unwrap();
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy(&config.database_url)
+            .unwrap();
+
+        AppState {
+            pool,
+            redis: test_support::redis_manager().await,
+            jwt_keys: JwtKeys::from_config(&config).unwrap(),
+            config,
+            email: std::sync::Arc::new(crate::services::LogSender),
+            webhooks: crate::services::WebhookService::disabled(),
+            event_streams: crate::handlers::EventStreamLimiter::new(
+                crate::handlers::MAX_EVENT_STREAMS_PER_USER,
+            ),
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_optional_user_extraction() {
+        let state = test_state().await;
+
+        // 未携带 Token：匿名用户
+        let (mut parts, _) = Request::new(()).into_parts();
+        let user = OptionalUser::from_request_parts(&mut parts, &state)
+            .await
+            .unwrap();
+        assert_eq!(user, OptionalUser(None));
+
+        // 携带有效 Token：提取用户 ID
+        let user_id = Uuid::new_v4();
+        let token = TokenService::create_token(
+            &state.redis,
+            &AuthUser::new(user_id),
+            &state.jwt_keys,
+            DeviceInfo::simple(DeviceType::Api, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        let (mut parts, _) = Request::builder()
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(())
+            .unwrap()
+            .into_parts();
+        let user = OptionalUser::from_request_parts(&mut parts, &state)
+            .await
+            .unwrap();
+        assert_eq!(user, OptionalUser(Some(user_id)));
+
+        TokenService::revoke_all_user_tokens(&state.redis, user_id)
+            .await
+            .unwrap();
+    }
+}
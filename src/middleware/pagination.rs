@@ -0,0 +1,153 @@
+/*!
+ * 分页参数提取器
+ *
+ * 从查询字符串中解析 `page` 和 `per_page`，供所有列表接口统一使用。
+ * 参数缺省时使用默认值，`per_page` 超过配置的上限时截断，
+ * 非正整数等无效值返回 `400 Bad Request`。
+ */
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use url::form_urlencoded;
+
+use crate::{
+    error::{AppError, Result},
+    models::{DEFAULT_PAGE, DEFAULT_PER_PAGE},
+    routes::AppState,
+};
+
+/// 分页参数
+///
+/// 每页条数上限取自 `Config::max_per_page`。
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_rust::middleware::Pagination;
+///
+/// async fn list_users(pagination: Pagination) {
+///     tracing::info!("LIMIT {} OFFSET {}", pagination.limit(), pagination.offset());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    /// 页码（从 1 开始）
+    pub page: u32,
+    /// 每页条数
+    pub per_page: u32,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            page: DEFAULT_PAGE,
+            per_page: DEFAULT_PER_PAGE,
+        }
+    }
+}
+
+impl Pagination {
+    /// 从查询字符串解析分页参数
+    ///
+    /// # 参数
+    ///
+    /// * `query` - 请求的查询字符串（不含 `?`）
+    /// * `max_per_page` - 每页条数上限
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Validation`: `page` 或 `per_page` 不是正整数
+    pub fn from_query(query: Option<&str>, max_per_page: u32) -> Result<Self> {
+        let mut pagination = Self::default();
+
+        for (name, value) in form_urlencoded::parse(query.unwrap_or_default().as_bytes()) {
+            match name.as_ref() {
+                "page" => pagination.page = parse_positive("page", &value)?,
+                "per_page" => pagination.per_page = parse_positive("per_page", &value)?,
+                _ => {}
+            }
+        }
+
+        pagination.per_page = pagination.per_page.min(max_per_page.max(1));
+        Ok(pagination)
+    }
+
+    /// 对应 SQL `LIMIT` 的值
+    pub fn limit(&self) -> i64 {
+        i64::from(self.per_page)
+    }
+
+    /// 对应 SQL `OFFSET` 的值
+    pub fn offset(&self) -> i64 {
+        i64::from(self.page - 1) * self.limit()
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Pagination {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self> {
+        Self::from_query(parts.uri.query(), state.config.max_per_page)
+    }
+}
+
+/// 解析正整数参数
+fn parse_positive(name: &str, value: &str) -> Result<u32> {
+    match value.trim().parse::<u32>() {
+        Ok(number) if number >= 1 => Ok(number),
+        _ => Err(AppError::Validation(format!(
+            "{} must be a positive integer",
+            name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MAX_PER_PAGE;
+
+    #[test]
+    fn test_defaults() {
+        let pagination = Pagination::from_query(None, MAX_PER_PAGE).unwrap();
+        assert_eq!(pagination, Pagination::default());
+        assert_eq!(pagination.offset(), 0);
+
+        // 未知参数被忽略
+        let pagination = Pagination::from_query(Some("sort=name"), MAX_PER_PAGE).unwrap();
+        assert_eq!(pagination, Pagination::default());
+    }
+
+    #[test]
+    fn test_offset_and_clamping() {
+        let pagination = Pagination::from_query(Some("page=3&per_page=25"), MAX_PER_PAGE).unwrap();
+        assert_eq!(pagination.limit(), 25);
+        assert_eq!(pagination.offset(), 50);
+
+        // 超过上限时截断
+        let pagination = Pagination::from_query(Some("page=2&per_page=1000"), 50).unwrap();
+        assert_eq!(pagination.per_page, 50);
+        assert_eq!(pagination.offset(), 50);
+    }
+
+    #[test]
+    fn test_invalid_values_rejected() {
+        for query in [
+            "page=0",
+            "per_page=0",
+            "page=-1",
+            "per_page=abc",
+            "page=",
+            "page=99999999999",
+        ] {
+            assert!(
+                matches!(
+                    Pagination::from_query(Some(query), MAX_PER_PAGE),
+                    Err(AppError::Validation(_))
+                ),
+                "{} should be rejected",
+                query
+            );
+        }
+    }
+}
@@ -0,0 +1,240 @@
+/*!
+ * 请求 ID 中间件
+ *
+ * 为每个请求分配唯一的请求 ID，便于在日志和错误响应之间关联同一请求。
+ * 请求 ID 会写入请求扩展、响应头 `X-Request-Id` 以及错误响应体的 `request_id` 字段。
+ * 同时提供请求追踪 span 的构造函数，span 中携带请求 ID 和已认证的用户 ID。
+ */
+
+use axum::{extract::Request, http::HeaderValue, middleware::Next, response::Response};
+use uuid::Uuid;
+
+/// 请求 ID 请求头/响应头名称
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    /// 当前请求的 ID，在请求处理期间有效
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// 请求 ID
+///
+/// 由请求 ID 中间件注入到请求扩展中，处理器可以通过 `Extension<RequestId>` 读取。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// 获取当前请求的 ID
+///
+/// 只在请求 ID 中间件内部执行的代码中返回 `Some`，
+/// 用于在 `AppError` 转换为响应时附带请求 ID。
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// 请求 ID 中间件函数
+///
+/// 该中间件会：
+/// 1. 读取请求头中的 `X-Request-Id`，如果是合法的 UUID 则沿用，否则生成新的 UUID
+/// 2. 将 `RequestId` 注入到请求扩展中
+/// 3. 在请求处理期间记录当前请求 ID，供错误响应使用
+/// 4. 在响应头中返回 `X-Request-Id`
+///
+/// 应作为最外层中间件使用，使追踪日志和所有错误响应都能获取到请求 ID。
+///
+/// # 示例
+///
+/// ```rust,ignore
+/// use axum::{middleware, Router};
+/// use hello_rust::middleware::request_id_middleware;
+///
+/// let app = Router::new()
+///     .route("/health", get(health_check))
+///     .layer(middleware::from_fn(request_id_middleware));
+/// ```
+pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value).ok())
+        .unwrap_or_else(Uuid::new_v4)
+        .to_string();
+
+    request
+        .extensions_mut()
+        .insert(RequestId(request_id.clone()));
+
+    let mut response = CURRENT_REQUEST_ID
+        .scope(request_id.clone(), next.run(request))
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
+
+/// 创建请求的追踪 span
+///
+/// 与 `TraceLayer::make_span_with` 配合使用，记录请求方法、路径和请求 ID，
+/// 并预留 `user_id` 字段，由身份验证中间件在验证通过后通过 `record_user_id` 填写。
+/// 请求 ID 中间件需要位于追踪中间件外层，否则 `request_id` 为空。
+///
+/// # 示例
+///
+/// ```rust,ignore
+/// use tower_http::trace::TraceLayer;
+/// use hello_rust::middleware::request_span;
+///
+/// let trace_layer = TraceLayer::new_for_http().make_span_with(request_span);
+/// ```
+pub fn request_span<B>(request: &axum::http::Request<B>) -> tracing::Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.as_str())
+        .unwrap_or_default();
+
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = %request_id,
+        user_id = tracing::field::Empty,
+    )
+}
+
+/// 在当前请求的追踪 span 中记录已认证的用户 ID
+///
+/// 当前 span 不是 `request_span` 创建的 span 时不产生任何效果。
+pub fn record_user_id(user_id: Uuid) {
+    tracing::Span::current().record("user_id", tracing::field::display(user_id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{AppError, Result};
+    use axum::{body::Body, http::Request, middleware, routing::get, Router};
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Subscriber,
+    };
+    use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+    /// 记录所有 span 字段的测试用 Layer
+    #[derive(Clone, Default)]
+    struct FieldRecorder(Arc<Mutex<Vec<(String, String)>>>);
+
+    impl Visit for FieldRecorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for FieldRecorder {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            attrs.record(&mut self.clone());
+        }
+
+        fn on_record(&self, _id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    async fn failing_handler() -> Result<()> {
+        Err(AppError::NotFound("User not found".to_string()))
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/fail", get(failing_handler))
+            .layer(middleware::from_fn(request_id_middleware))
+    }
+
+    async fn body_request_id(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        body["request_id"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_request_id_in_header_and_error_body() {
+        let response = app()
+            .oneshot(Request::get("/fail").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let header = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(Uuid::parse_str(&header).is_ok());
+        assert_eq!(body_request_id(response).await, header);
+    }
+
+    #[tokio::test]
+    async fn test_incoming_request_id_is_reused() {
+        let incoming = Uuid::new_v4().to_string();
+        let response = app()
+            .oneshot(
+                Request::get("/fail")
+                    .header(REQUEST_ID_HEADER, &incoming)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], incoming.as_str());
+        assert_eq!(body_request_id(response).await, incoming);
+    }
+
+    #[test]
+    fn test_request_span_records_fields() {
+        let recorder = FieldRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        let user_id = Uuid::new_v4();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let mut request = Request::get("/api/profile?page=1")
+                .body(Body::empty())
+                .unwrap();
+            request
+                .extensions_mut()
+                .insert(RequestId("req-1".to_string()));
+
+            let span = request_span(&request);
+            let _guard = span.enter();
+            record_user_id(user_id);
+        });
+
+        let fields = recorder.0.lock().unwrap();
+        for (name, value) in [
+            ("method", "GET".to_string()),
+            ("path", "/api/profile".to_string()),
+            ("request_id", "req-1".to_string()),
+            ("user_id", user_id.to_string()),
+        ] {
+            assert!(
+                fields.contains(&(name.to_string(), value.clone())),
+                "missing {} = {} in {:?}",
+                name,
+                value,
+                fields
+            );
+        }
+    }
+}
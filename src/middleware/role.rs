@@ -0,0 +1,150 @@
+/*!
+ * 角色授权中间件
+ *
+ * 在身份验证中间件之后运行，根据用户角色限制对特定路由的访问。
+ * 权限不足时返回 `403 Forbidden`。
+ */
+
+use std::{future::Future, pin::Pin};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    db::DbPool,
+    error::{AppError, Result},
+    routes::AppState,
+    services::UserService,
+    utils::AuthUser,
+};
+
+/// 角色授权中间件返回的 Future 类型
+pub type RoleFuture = Pin<Box<dyn Future<Output = Result<Response>> + Send>>;
+
+/// 创建角色授权中间件
+///
+/// 返回可用于 `axum::middleware::from_fn_with_state` 的中间件函数。
+/// 该中间件会：
+/// 1. 从请求扩展中读取身份验证中间件注入的用户身份
+/// 2. 使用 Token 中的 `role` 声明；旧 Token 中没有该声明时从数据库加载用户角色
+/// 3. 角色不匹配时返回 `AppError::Authorization`
+///
+/// 必须在 `auth_middleware` 之后执行（即 `auth_middleware` 作为外层 layer）。
+/// 角色变更在用户重新获取 Token（登录或刷新）后生效。
+///
+/// # 参数
+///
+/// * `role` - 访问路由所需的角色
+///
+/// # 错误处理
+///
+/// - `401 Unauthorized`: 请求扩展中没有用户 ID（未经过身份验证中间件）
+/// - `403 Forbidden`: 用户角色与要求不符
+///
+/// # 示例
+///
+/// ```rust,ignore
+/// use axum::{middleware, routing::get, Router};
+/// use hello_rust::middleware::{auth_middleware, require_role};
+///
+/// let admin_routes = Router::new()
+///     .route("/users", get(get_all_users))
+///     .route_layer(middleware::from_fn_with_state(app_state.clone(), require_role("admin")));
+///
+/// let protected_routes = Router::new()
+///     .merge(admin_routes)
+///     .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+/// ```
+pub fn require_role(
+    role: &str,
+) -> impl Fn(State<AppState>, Request, Next) -> RoleFuture + Clone + Send + Sync + 'static {
+    let role = role.to_string();
+
+    move |State(app_state): State<AppState>, request: Request, next: Next| -> RoleFuture {
+        Box::pin(role_middleware(app_state, role.clone(), request, next))
+    }
+}
+
+/// 角色授权中间件的具体实现
+async fn role_middleware(
+    app_state: AppState,
+    role: String,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    // 读取身份验证中间件注入的用户身份
+    let user = request
+        .extensions()
+        .get::<AuthUser>()
+        .ok_or_else(|| AppError::Authentication("Missing authenticated user".to_string()))?;
+
+    let user_role = user_role(&app_state.pool, user).await?;
+    ensure_role(&user_role, &role)?;
+
+    Ok(next.run(request).await)
+}
+
+/// 获取用户角色
+///
+/// 优先使用 Token 中的 `role` 声明，旧 Token 中没有该声明时才查询数据库。
+async fn user_role(pool: &DbPool, user: &AuthUser) -> Result<String> {
+    match &user.role {
+        Some(role) => Ok(role.clone()),
+        None => Ok(UserService::get_user_by_id(pool, user.id).await?.role),
+    }
+}
+
+/// 检查用户角色是否满足要求
+///
+/// # 参数
+///
+/// * `user_role` - 用户当前角色
+/// * `required_role` - 所需角色
+///
+/// # 错误
+///
+/// - `AppError::Authorization`: 角色不匹配
+pub fn ensure_role(user_role: &str, required_role: &str) -> Result<()> {
+    if user_role != required_role {
+        return Err(AppError::Authorization(format!(
+            "Requires {} role",
+            required_role
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ROLE_ADMIN, ROLE_USER};
+    use axum::{http::StatusCode, response::IntoResponse};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_user_role_uses_token_claim() {
+        // 连接池不会真正连接数据库，Token 中带有角色时不应查询数据库
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let user = AuthUser {
+            role: Some(ROLE_ADMIN.to_string()),
+            ..AuthUser::new(Uuid::new_v4())
+        };
+
+        assert_eq!(user_role(&pool, &user).await.unwrap(), ROLE_ADMIN);
+    }
+
+    #[test]
+    fn test_ensure_role() {
+        assert!(ensure_role(ROLE_ADMIN, ROLE_ADMIN).is_ok());
+
+        let err = ensure_role(ROLE_USER, ROLE_ADMIN).unwrap_err();
+        assert!(matches!(err, AppError::Authorization(_)));
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+    }
+}
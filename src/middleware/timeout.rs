@@ -0,0 +1,118 @@
+/*!
+ * 请求超时中间件
+ *
+ * 限制单个请求的处理时间，超时后丢弃处理器的 Future 并返回 `504 Gateway Timeout`。
+ * Future 被丢弃时，其中尚未完成的数据库查询和 Redis 命令会一并取消，
+ * 连接随之归还连接池，避免挂起的查询长期占用连接。
+ */
+
+use std::{future::Future, pin::Pin, time::Duration};
+
+use axum::{extract::Request, middleware::Next, response::Response};
+
+use crate::error::{AppError, Result};
+
+/// 请求超时中间件返回的 Future 类型
+pub type TimeoutFuture = Pin<Box<dyn Future<Output = Result<Response>> + Send>>;
+
+/// 创建请求超时中间件
+///
+/// 返回可用于 `axum::middleware::from_fn` 的中间件函数。
+/// 超时只限制处理器生成响应的时间，SSE 等流式响应在返回响应头之后不受影响。
+///
+/// # 参数
+///
+/// * `timeout` - 单个请求允许的最长处理时间
+///
+/// # 错误处理
+///
+/// - `504 Gateway Timeout`: 处理器未在 `timeout` 内返回响应
+///
+/// # 示例
+///
+/// ```rust,ignore
+/// use std::time::Duration;
+/// use axum::{middleware, routing::get, Router};
+/// use hello_rust::middleware::request_timeout;
+///
+/// let app = Router::new()
+///     .route("/profile", get(get_profile))
+///     .layer(middleware::from_fn(request_timeout(Duration::from_secs(30))));
+/// ```
+pub fn request_timeout(
+    timeout: Duration,
+) -> impl Fn(Request, Next) -> TimeoutFuture + Clone + Send + Sync + 'static {
+    move |request: Request, next: Next| -> TimeoutFuture {
+        Box::pin(timeout_middleware(timeout, request, next))
+    }
+}
+
+/// 请求超时中间件的具体实现
+async fn timeout_middleware(timeout: Duration, request: Request, next: Next) -> Result<Response> {
+    let method = request.method().clone();
+    let uri = request.uri().clone();
+
+    // 超时后 `next.run` 返回的 Future 被丢弃，处理器中的查询随之取消
+    tokio::time::timeout(timeout, next.run(request))
+        .await
+        .map_err(|_| {
+            tracing::warn!("请求处理超时 ({:?}): {} {}", timeout, method, uri);
+            AppError::Timeout
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+    use tower::ServiceExt;
+
+    /// 被丢弃时记录标记，用于确认处理器的 Future 已被取消
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_times_out() {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(move || {
+                    let flag = flag.clone();
+                    async move {
+                        let _guard = DropFlag(flag);
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        "done"
+                    }
+                }),
+            )
+            .route("/fast", get(|| async { "done" }))
+            .layer(middleware::from_fn(request_timeout(Duration::from_millis(
+                50,
+            ))));
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/slow").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert!(cancelled.load(Ordering::SeqCst));
+
+        let response = app
+            .oneshot(Request::get("/fast").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,220 @@
+/*!
+ * 请求追踪采样
+ *
+ * 高流量部署中为每个请求输出开始/结束处理的追踪日志开销较大。
+ * `trace_layer` 构建的 `TraceLayer` 只为一部分请求输出这些日志，比例由 `Config::trace_sample_rate` 控制；
+ * 5xx 响应不受采样影响，总是记录，且只在响应回调中记录一次（不再使用 `TraceLayer` 默认的失败回调）。
+ *
+ * 采样决定由请求 ID 的哈希值计算，同一请求在所有回调中得到相同的结果，
+ * 沿用上游 `X-Request-Id` 的请求在上下游服务之间的采样结果也一致。
+ * 未被采样的请求仍然会创建请求 span，处理器中输出的日志照常携带请求 ID 和用户 ID。
+ */
+
+use std::time::Duration;
+
+use axum::http::{Request, Response};
+use sha2::{Digest, Sha256};
+use tower_http::{
+    classify::{ServerErrorsAsFailures, SharedClassifier},
+    trace::{DefaultOnBodyChunk, DefaultOnEos, MakeSpan, OnRequest, OnResponse, TraceLayer},
+};
+use tracing::Span;
+
+use crate::middleware::{current_request_id, request_span, RequestId};
+
+/// 按采样率输出请求追踪日志的 `TraceLayer`
+///
+/// 失败回调为 `()`：5xx 响应已由 `TraceSampler::on_response` 记录，避免同一错误输出两条日志。
+pub type SampledTraceLayer = TraceLayer<
+    SharedClassifier<ServerErrorsAsFailures>,
+    TraceSampler,
+    TraceSampler,
+    TraceSampler,
+    DefaultOnBodyChunk,
+    DefaultOnEos,
+    (),
+>;
+
+/// 构建带采样的请求追踪中间件
+///
+/// 请求 ID 中间件需要位于该中间件外层，否则无法得到一致的采样决定，所有请求都会被追踪。
+///
+/// # 参数
+///
+/// * `sample_rate` - 采样率，0.0 表示只记录 5xx 响应，1.0 表示记录所有请求
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_rust::middleware::trace_layer;
+///
+/// let trace_layer = trace_layer(0.1);
+/// ```
+pub fn trace_layer(sample_rate: f64) -> SampledTraceLayer {
+    let sampler = TraceSampler::new(sample_rate);
+    TraceLayer::new_for_http()
+        .make_span_with(sampler)
+        .on_request(sampler)
+        .on_response(sampler)
+        .on_failure(())
+}
+
+/// 请求追踪采样器
+///
+/// 作为 `TraceLayer` 的 span 构造器和请求/响应回调使用。
+#[derive(Debug, Clone, Copy)]
+pub struct TraceSampler {
+    sample_rate: f64,
+}
+
+impl TraceSampler {
+    /// 创建采样器，采样率会被截断到 [0.0, 1.0]
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate: sample_rate.clamp(0.0, 1.0),
+        }
+    }
+
+    /// 判断请求是否被采样
+    ///
+    /// 相同的请求 ID 总是得到相同的结果。没有请求 ID 时无法保证一致，
+    /// 除非采样率为 0，否则视为被采样。
+    pub fn is_sampled(&self, request_id: Option<&str>) -> bool {
+        if self.sample_rate >= 1.0 {
+            return true;
+        }
+        if self.sample_rate <= 0.0 {
+            return false;
+        }
+
+        match request_id {
+            Some(request_id) => {
+                let digest = Sha256::digest(request_id.as_bytes());
+                let mut bucket = [0u8; 8];
+                bucket.copy_from_slice(&digest[..8]);
+                (u64::from_be_bytes(bucket) as f64) < self.sample_rate * u64::MAX as f64
+            }
+            None => true,
+        }
+    }
+}
+
+impl<B> MakeSpan<B> for TraceSampler {
+    fn make_span(&mut self, request: &Request<B>) -> Span {
+        request_span(request)
+    }
+}
+
+impl<B> OnRequest<B> for TraceSampler {
+    fn on_request(&mut self, request: &Request<B>, _span: &Span) {
+        let request_id = request.extensions().get::<RequestId>();
+        if self.is_sampled(request_id.map(|id| id.0.as_str())) {
+            tracing::debug!("started processing request");
+        }
+    }
+}
+
+impl<B> OnResponse<B> for TraceSampler {
+    fn on_response(self, response: &Response<B>, latency: Duration, _span: &Span) {
+        let status = response.status();
+        if status.is_server_error() {
+            tracing::error!(
+                status = status.as_u16(),
+                latency_ms = latency.as_millis(),
+                "finished processing request"
+            );
+        } else if self.is_sampled(current_request_id().as_deref()) {
+            tracing::debug!(
+                status = status.as_u16(),
+                latency_ms = latency.as_millis(),
+                "finished processing request"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{logging::test_support::BufferWriter, middleware::request_id_middleware};
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+    use uuid::Uuid;
+
+    /// 分别请求正常和出错的接口，返回期间输出的日志
+    async fn traced_output(sample_rate: f64) -> String {
+        let writer = BufferWriter::default();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(writer.clone())
+                .with_ansi(false),
+        );
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route("/fail", get(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+            .layer(trace_layer(sample_rate))
+            .layer(middleware::from_fn(request_id_middleware));
+
+        for path in ["/ok", "/fail"] {
+            app.clone()
+                .oneshot(Request::get(path).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+        }
+
+        writer.contents()
+    }
+
+    #[tokio::test]
+    async fn test_zero_sample_rate_only_traces_errors() {
+        let output = traced_output(0.0).await;
+        assert!(!output.contains("path=/ok"), "{}", output);
+        assert!(output.contains("path=/fail"), "{}", output);
+        assert!(output.contains("status=500"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_logged_once() {
+        let output = traced_output(1.0).await;
+        assert_eq!(output.matches("ERROR").count(), 1, "{}", output);
+        assert!(!output.contains("response failed"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_full_sample_rate_traces_all_requests() {
+        let output = traced_output(1.0).await;
+        assert!(output.contains("path=/ok"), "{}", output);
+        assert!(output.contains("status=200"), "{}", output);
+        assert!(output.contains("path=/fail"), "{}", output);
+    }
+
+    #[test]
+    fn test_sampling_is_consistent_per_request_id() {
+        let sampler = TraceSampler::new(0.5);
+        let ids: Vec<String> = (0..1000).map(|_| Uuid::new_v4().to_string()).collect();
+
+        for id in &ids {
+            assert_eq!(
+                sampler.is_sampled(Some(id.as_str())),
+                sampler.is_sampled(Some(id.as_str()))
+            );
+        }
+
+        let sampled = ids
+            .iter()
+            .filter(|id| sampler.is_sampled(Some(id.as_str())))
+            .count();
+        assert!(
+            (350..=650).contains(&sampled),
+            "sampled {} of 1000",
+            sampled
+        );
+
+        // 超出范围的采样率被截断
+        assert!(TraceSampler::new(2.0).is_sampled(Some("req-1")));
+        assert!(!TraceSampler::new(-1.0).is_sampled(None));
+    }
+}
@@ -9,10 +9,15 @@
  * # 子模块
  *
  * - `user`: 用户相关的数据模型，包括用户实体、请求和响应结构
+ * - `pagination`: 分页查询参数和分页响应结构
  */
 
 /// 用户数据模型
 pub mod user;
 
+/// 分页数据模型
+pub mod pagination;
+
 // 重新导出所有模型，方便外部使用
+pub use pagination::*;
 pub use user::*;
@@ -0,0 +1,182 @@
+/*!
+ * 分页数据模型
+ *
+ * 定义列表接口通用的分页查询参数和分页响应结构。
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// 默认页码（从 1 开始）
+pub const DEFAULT_PAGE: u32 = 1;
+
+/// 默认每页条数
+pub const DEFAULT_PER_PAGE: u32 = 20;
+
+/// 每页条数上限，防止一次性加载过多数据
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// 分页查询参数
+///
+/// 从查询字符串中解析 `page` 和 `per_page`，缺省时使用默认值，
+/// `per_page` 超过上限时会被截断为 `MAX_PER_PAGE`。
+///
+/// 处理器中优先使用 `middleware::Pagination` 提取器，它会拒绝无效值并使用配置的上限。
+///
+/// # 示例
+///
+/// ```text
+/// GET /api/users?page=2&per_page=50
+/// ```
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub struct PaginationQuery {
+    /// 页码（从 1 开始）
+    pub page: Option<u32>,
+
+    /// 每页条数
+    pub per_page: Option<u32>,
+}
+
+impl PaginationQuery {
+    /// 获取规范化后的页码（最小为 1）
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(DEFAULT_PAGE).max(1)
+    }
+
+    /// 获取规范化后的每页条数（范围 1..=MAX_PER_PAGE）
+    pub fn per_page(&self) -> u32 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+
+    /// 对应 SQL `LIMIT` 的值
+    pub fn limit(&self) -> i64 {
+        i64::from(self.per_page())
+    }
+
+    /// 对应 SQL `OFFSET` 的值
+    pub fn offset(&self) -> i64 {
+        i64::from(self.page() - 1) * self.limit()
+    }
+}
+
+/// 分页响应
+///
+/// 列表接口统一使用的分页响应包装，通过 [`Page::new`] 创建以自动计算总页数。
+///
+/// # 示例 JSON
+///
+/// ```json
+/// {
+///   "items": [],
+///   "total": 42,
+///   "page": 1,
+///   "per_page": 20,
+///   "total_pages": 3
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    /// 当前页数据
+    pub items: Vec<T>,
+
+    /// 总记录数
+    pub total: u64,
+
+    /// 当前页码
+    pub page: u32,
+
+    /// 每页条数
+    pub per_page: u32,
+
+    /// 总页数（向上取整，没有记录时为 0）
+    pub total_pages: u32,
+}
+
+impl<T> Page<T> {
+    /// 创建分页响应并计算总页数
+    ///
+    /// # 参数
+    ///
+    /// * `items` - 当前页数据
+    /// * `total` - 总记录数
+    /// * `page` - 当前页码
+    /// * `per_page` - 每页条数，为 0 时总页数为 0
+    pub fn new(items: Vec<T>, total: u64, page: u32, per_page: u32) -> Self {
+        let total_pages = if per_page == 0 {
+            0
+        } else {
+            u32::try_from(total.div_ceil(u64::from(per_page))).unwrap_or(u32::MAX)
+        };
+
+        Self {
+            items,
+            total,
+            page,
+            per_page,
+            total_pages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(page: Option<u32>, per_page: Option<u32>) -> PaginationQuery {
+        PaginationQuery { page, per_page }
+    }
+
+    #[test]
+    fn test_defaults() {
+        let q = PaginationQuery::default();
+        assert_eq!(q.page(), DEFAULT_PAGE);
+        assert_eq!(q.per_page(), DEFAULT_PER_PAGE);
+        assert_eq!(q.offset(), 0);
+    }
+
+    #[test]
+    fn test_offset_math() {
+        assert_eq!(query(Some(1), Some(10)).offset(), 0);
+        assert_eq!(query(Some(2), Some(10)).offset(), 10);
+        assert_eq!(query(Some(5), Some(25)).offset(), 100);
+        // 页码 0 视为第一页
+        assert_eq!(query(Some(0), Some(10)).offset(), 0);
+    }
+
+    #[test]
+    fn test_page_total_pages_rounding() {
+        assert_eq!(Page::<()>::new(vec![], 25, 1, 10).total_pages, 3);
+        assert_eq!(Page::<()>::new(vec![], 30, 1, 10).total_pages, 3);
+        assert_eq!(Page::<()>::new(vec![], 31, 1, 10).total_pages, 4);
+        assert_eq!(Page::<()>::new(vec![], 1, 1, 20).total_pages, 1);
+        assert_eq!(Page::<()>::new(vec![], 0, 1, 20).total_pages, 0);
+        assert_eq!(Page::<()>::new(vec![], 10, 1, 0).total_pages, 0);
+    }
+
+    #[test]
+    fn test_page_serialization() {
+        let page = Page::new(vec![1, 2], 12, 2, 10);
+        assert_eq!(
+            serde_json::to_value(&page).unwrap(),
+            serde_json::json!({
+                "items": [1, 2],
+                "total": 12,
+                "page": 2,
+                "per_page": 10,
+                "total_pages": 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_per_page_cap() {
+        assert_eq!(query(None, Some(1000)).per_page(), MAX_PER_PAGE);
+        assert_eq!(query(None, Some(1000)).limit(), i64::from(MAX_PER_PAGE));
+        assert_eq!(query(None, Some(0)).per_page(), 1);
+        assert_eq!(
+            query(Some(3), Some(500)).offset(),
+            2 * i64::from(MAX_PER_PAGE)
+        );
+    }
+}
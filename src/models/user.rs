@@ -10,6 +10,14 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::utils::AuthUser;
+
+/// 管理员角色
+pub const ROLE_ADMIN: &str = "admin";
+
+/// 普通用户角色（新注册用户的默认角色）
+pub const ROLE_USER: &str = "user";
+
 /// 用户数据库实体
 ///
 /// 对应数据库中的 `users` 表，包含用户的完整信息。
@@ -21,6 +29,8 @@ use uuid::Uuid;
 /// - `email`: 用户邮箱地址，用于登录和联系
 /// - `password_hash`: 经过 Argon2 哈希处理的密码
 /// - `name`: 用户显示名称
+/// - `role`: 用户角色，用于权限控制（默认 `user`）
+/// - `email_verified`: 邮箱是否已验证
 /// - `created_at`: 账户创建时间
 /// - `updated_at`: 最后更新时间
 ///
@@ -42,6 +52,12 @@ pub struct User {
     /// 用户显示名称
     pub name: String,
 
+    /// 用户角色（如 `user`、`admin`）
+    pub role: String,
+
+    /// 邮箱是否已验证
+    pub email_verified: bool,
+
     /// 账户创建时间
     pub created_at: DateTime<Utc>,
 
@@ -102,6 +118,137 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+/// 刷新令牌请求
+///
+/// 用于使用刷新令牌换取新的访问令牌。
+///
+/// # 示例 JSON
+///
+/// ```json
+/// {
+///   "refresh_token": "q3Y2x0b2tlbi1leGFtcGxl..."
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct RefreshTokenRequest {
+    /// 登录或上次刷新时获得的刷新令牌
+    pub refresh_token: String,
+}
+
+/// 修改密码请求
+///
+/// 用于已登录用户修改自己的密码。
+///
+/// # 示例 JSON
+///
+/// ```json
+/// {
+///   "old_password": "securePassword123",
+///   "new_password": "N3w-Secure-Passw0rd"
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    /// 当前密码
+    pub old_password: String,
+
+    /// 新密码（需满足最低强度要求）
+    pub new_password: String,
+}
+
+/// 更新用户资料请求
+///
+/// 用于已登录用户修改自己的资料，所有字段都是可选的，
+/// 只更新请求中提供的字段。
+///
+/// # 示例 JSON
+///
+/// ```json
+/// {
+///   "name": "李四"
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateUserRequest {
+    /// 新的显示名称
+    pub name: Option<String>,
+
+    /// 新的邮箱地址（不能与其他用户重复）
+    pub email: Option<String>,
+}
+
+/// 发送邮箱验证码请求
+///
+/// # 示例 JSON
+///
+/// ```json
+/// {
+///   "email": "user@example.com"
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct SendVerificationRequest {
+    /// 需要验证的邮箱地址
+    pub email: String,
+}
+
+/// 验证邮箱请求
+///
+/// # 示例 JSON
+///
+/// ```json
+/// {
+///   "email": "user@example.com",
+///   "code": "123456"
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailRequest {
+    /// 需要验证的邮箱地址
+    pub email: String,
+
+    /// 邮件中收到的验证码
+    pub code: String,
+}
+
+/// 忘记密码请求
+///
+/// # 示例 JSON
+///
+/// ```json
+/// {
+///   "email": "user@example.com"
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordRequest {
+    /// 需要重置密码的邮箱地址
+    pub email: String,
+}
+
+/// 重置密码请求
+///
+/// # 示例 JSON
+///
+/// ```json
+/// {
+///   "email": "user@example.com",
+///   "token": "reset_token_here",
+///   "new_password": "N3w-Secure-Passw0rd"
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordRequest {
+    /// 需要重置密码的邮箱地址
+    pub email: String,
+
+    /// 邮件中收到的重置令牌
+    pub token: String,
+
+    /// 新密码（需满足最低强度要求）
+    pub new_password: String,
+}
+
 /// 用户信息响应
 ///
 /// 返回给客户端的用户信息，不包含敏感数据如密码哈希。
@@ -114,6 +261,7 @@ pub struct LoginRequest {
 ///   "id": "123e4567-e89b-12d3-a456-426614174000",
 ///   "email": "user@example.com",
 ///   "name": "张三",
+///   "email_verified": false,
 ///   "created_at": "2023-01-01T00:00:00Z"
 /// }
 /// ```
@@ -128,20 +276,25 @@ pub struct UserResponse {
     /// 用户名称
     pub name: String,
 
+    /// 邮箱是否已验证
+    pub email_verified: bool,
+
     /// 创建时间
     pub created_at: DateTime<Utc>,
 }
 
 /// 身份验证响应
 ///
-/// 用于注册和登录成功后返回给客户端的数据。
-/// 包含 JWT Token 和用户基本信息。
+/// 用于注册、登录和刷新令牌成功后返回给客户端的数据。
+/// 包含 JWT Token、刷新令牌和用户基本信息。
+/// 启用 `REQUIRE_EMAIL_VERIFICATION` 时，注册响应只包含用户信息，验证邮箱后再登录获取令牌。
 ///
 /// # 示例 JSON
 ///
 /// ```json
 /// {
 ///   "token": "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...",
+///   "refresh_token": "q3Y2x0b2tlbi1leGFtcGxl...",
 ///   "user": {
 ///     "id": "123e4567-e89b-12d3-a456-426614174000",
 ///     "email": "user@example.com",
@@ -152,8 +305,13 @@ pub struct UserResponse {
 /// ```
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
-    /// JWT 访问令牌
-    pub token: String,
+    /// JWT 访问令牌（需要先验证邮箱时，注册响应中没有令牌）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    /// 刷新令牌（用于在访问令牌过期后换取新令牌）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
 
     /// 用户信息
     pub user: UserResponse,
@@ -166,7 +324,7 @@ pub struct AuthResponse {
 ///
 /// # 示例
 ///
-/// ```rust
+/// ```rust,ignore
 /// let user: User = get_user_from_db().await?;
 /// let response: UserResponse = user.into();
 /// ```
@@ -176,8 +334,22 @@ impl From<User> for UserResponse {
             id: user.id,
             email: user.email,
             name: user.name,
+            email_verified: user.email_verified,
             created_at: user.created_at,
             // 注意：不包含 password_hash 和 updated_at
         }
     }
 }
+
+/// 从 User 实体转换为 AuthUser
+///
+/// 用于签发 JWT 时携带用户的邮箱和角色。
+impl From<&User> for AuthUser {
+    fn from(user: &User) -> Self {
+        AuthUser {
+            id: user.id,
+            email: Some(user.email.clone()),
+            role: Some(user.role.clone()),
+        }
+    }
+}
@@ -6,16 +6,31 @@
 
 use crate::config::Config;
 use crate::error::AppError;
+use crate::utils::retry::{
+    is_transient_redis_error, with_backoff, STARTUP_ATTEMPTS, STARTUP_BASE_DELAY,
+};
+use futures_util::stream::{BoxStream, StreamExt};
 use redis::{aio::ConnectionManager, Client, RedisResult};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+pub mod backend;
+
+pub use backend::{MemoryBackend, RedisBackend};
 
 /// Redis 管理器
 ///
 /// 封装 Redis 连接管理器，提供连接池和基础配置
 #[derive(Clone)]
 pub struct RedisManager {
-    /// Redis 连接管理器
-    connection_manager: ConnectionManager,
+    /// Redis 客户端，用于创建订阅等需要独占的连接（内存后端没有客户端）
+    client: Option<Client>,
+    /// Redis 连接管理器（内存后端没有连接）
+    connection_manager: Option<ConnectionManager>,
+    /// 存储后端（默认为 Redis 连接）
+    backend: Arc<dyn RedisBackend>,
     /// 默认过期时间（秒）
     default_expiry: Option<u64>,
 }
@@ -49,8 +64,15 @@ impl RedisManager {
             AppError::Internal(anyhow::anyhow!("Failed to create Redis client: {}", e))
         })?;
 
-        // 创建连接管理器
-        let connection_manager = client.get_connection_manager().await.map_err(|e| {
+        // 创建连接管理器，Redis 暂时不可用时按指数退避重试
+        let connection_manager = with_backoff(
+            STARTUP_ATTEMPTS,
+            STARTUP_BASE_DELAY,
+            is_transient_redis_error,
+            || client.get_connection_manager(),
+        )
+        .await
+        .map_err(|e| {
             AppError::Internal(anyhow::anyhow!(
                 "Failed to create Redis connection manager: {}",
                 e
@@ -58,14 +80,55 @@ impl RedisManager {
         })?;
 
         Ok(RedisManager {
-            connection_manager,
+            client: Some(client),
+            backend: Arc::new(connection_manager.clone()),
+            connection_manager: Some(connection_manager),
             default_expiry: config.redis_default_expiry,
         })
     }
 
+    /// 创建使用内存后端的 Redis 管理器
+    ///
+    /// 用于在没有 Redis 实例时测试 `TokenService` 等基于 `RedisBackend` 的功能。
+    /// 内存后端没有 Redis 连接，`connection()` 以及依赖连接的 `RedisUtils` 方法返回 `AppError::Internal`。
+    pub fn in_memory() -> Self {
+        RedisManager {
+            client: None,
+            connection_manager: None,
+            backend: Arc::new(MemoryBackend::new()),
+            default_expiry: None,
+        }
+    }
+
     /// 获取连接管理器的引用
-    pub fn connection(&self) -> &ConnectionManager {
-        &self.connection_manager
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Internal`: 使用内存后端（`RedisManager::in_memory`），没有 Redis 连接
+    pub fn connection(&self) -> Result<&ConnectionManager, AppError> {
+        self.connection_manager.as_ref().ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!(
+                "in-memory RedisManager has no Redis connection"
+            ))
+        })
+    }
+
+    /// 获取 Redis 客户端的引用
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Internal`: 使用内存后端（`RedisManager::in_memory`），没有 Redis 客户端
+    pub fn client(&self) -> Result<&Client, AppError> {
+        self.client.as_ref().ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!(
+                "in-memory RedisManager has no Redis client"
+            ))
+        })
+    }
+
+    /// 获取存储后端
+    pub fn backend(&self) -> &dyn RedisBackend {
+        self.backend.as_ref()
     }
 
     /// 获取默认过期时间
@@ -87,6 +150,21 @@ impl RedisUtils {
         Self { manager }
     }
 
+    /// 发送 PING 命令检查 Redis 连接
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<bool, AppError>`，true 表示 Redis 返回了 PONG
+    pub async fn ping(&self) -> Result<bool, AppError> {
+        let mut conn = self.manager.connection()?.clone();
+        let reply: String = redis::cmd("PING")
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis ping failed: {}", e)))?;
+
+        Ok(reply == "PONG")
+    }
+
     /// 设置字符串值
     ///
     /// # 参数
@@ -110,7 +188,7 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
+        let mut conn = self.manager.connection()?.clone();
 
         // 确定过期时间
         let exp = expiry.or(self.manager.default_expiry());
@@ -121,8 +199,7 @@ impl RedisUtils {
                 .await
                 .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis set_ex failed: {}", e)))?;
         } else {
-            let _: () = conn
-                .set(key, value)
+            let _: () = AsyncCommands::set(&mut conn, key, value)
                 .await
                 .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis set failed: {}", e)))?;
         }
@@ -145,8 +222,8 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
-        let result: RedisResult<String> = conn.get(key).await;
+        let mut conn = self.manager.connection()?.clone();
+        let result: RedisResult<String> = AsyncCommands::get(&mut conn, key).await;
 
         match result {
             Ok(value) => Ok(Some(value)),
@@ -209,6 +286,101 @@ impl RedisUtils {
         }
     }
 
+    /// 批量设置 JSON 对象
+    ///
+    /// 使用一次 `MSET` 写入所有键，需要过期时间时在同一个事务管道中为每个键设置 `EXPIRE`。
+    ///
+    /// # 参数
+    ///
+    /// * `items` - 键和可序列化值的列表
+    /// * `expiry` - 过期时间（秒），None 表示使用默认过期时间
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<(), AppError>`，任一值序列化失败时不写入任何键
+    pub async fn mset_json<T>(
+        &self,
+        items: &[(String, &T)],
+        expiry: Option<u64>,
+    ) -> Result<(), AppError>
+    where
+        T: Serialize,
+    {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+
+        pipe.cmd("MSET");
+        for (key, value) in items {
+            let json_value = serde_json::to_string(value).map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("JSON serialization failed: {}", e))
+            })?;
+            pipe.arg(key).arg(json_value);
+        }
+        pipe.ignore();
+
+        if let Some(seconds) = expiry.or(self.manager.default_expiry()) {
+            for (key, _) in items {
+                pipe.cmd("EXPIRE").arg(key).arg(seconds).ignore();
+            }
+        }
+
+        let mut conn = self.manager.connection()?.clone();
+        let _: () = pipe
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis mset failed: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 批量获取 JSON 对象
+    ///
+    /// 使用一次 `MGET` 读取所有键，结果顺序与 `keys` 一致。
+    /// 缓存中的数据可能是旧版本结构写入的，无法反序列化的值按缓存未命中处理（返回 None
+    /// 并记录警告日志），不会导致整批读取失败。
+    ///
+    /// # 参数
+    ///
+    /// * `keys` - 键名列表
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Vec<Option<T>>, AppError>`，键不存在或值无法反序列化时对应位置为 None
+    pub async fn mget_json<T>(&self, keys: &[String]) -> Result<Vec<Option<T>>, AppError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        // MGET 至少需要一个键
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.manager.connection()?.clone();
+        let values: Vec<Option<String>> = redis::cmd("MGET")
+            .arg(keys)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis mget failed: {}", e)))?;
+
+        Ok(keys
+            .iter()
+            .zip(values)
+            .map(|(key, value)| {
+                value.and_then(|json_str| match serde_json::from_str(&json_str) {
+                    Ok(value) => Some(value),
+                    Err(e) => {
+                        tracing::warn!("缓存值反序列化失败，按未命中处理 (key: {}): {}", key, e);
+                        None
+                    }
+                })
+            })
+            .collect())
+    }
+
     /// 删除键
     ///
     /// # 参数
@@ -224,9 +396,8 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
-        let deleted: u32 = conn
-            .del(key)
+        let mut conn = self.manager.connection()?.clone();
+        let deleted: u32 = AsyncCommands::del(&mut conn, key)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis del failed: {}", e)))?;
 
@@ -248,9 +419,8 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
-        let exists: bool = conn
-            .exists(key)
+        let mut conn = self.manager.connection()?.clone();
+        let exists: bool = AsyncCommands::exists(&mut conn, key)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis exists failed: {}", e)))?;
 
@@ -273,9 +443,8 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
-        let result: bool = conn
-            .expire(key, seconds as i64)
+        let mut conn = self.manager.connection()?.clone();
+        let result: bool = AsyncCommands::expire(&mut conn, key, seconds as i64)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis expire failed: {}", e)))?;
 
@@ -299,9 +468,8 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
-        let ttl: i64 = conn
-            .ttl(key)
+        let mut conn = self.manager.connection()?.clone();
+        let ttl: i64 = AsyncCommands::ttl(&mut conn, key)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis ttl failed: {}", e)))?;
 
@@ -329,7 +497,7 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
+        let mut conn = self.manager.connection()?.clone();
 
         let result = if let Some(inc) = increment {
             conn.incr(key, inc).await
@@ -356,7 +524,7 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
+        let mut conn = self.manager.connection()?.clone();
 
         let result = if let Some(dec) = decrement {
             conn.incr(key, -dec).await
@@ -384,7 +552,7 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
+        let mut conn = self.manager.connection()?.clone();
         let length: u32 = conn
             .lpush(key, value)
             .await
@@ -410,7 +578,7 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
+        let mut conn = self.manager.connection()?.clone();
         let length: u32 = conn
             .rpush(key, value)
             .await
@@ -434,7 +602,7 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
+        let mut conn = self.manager.connection()?.clone();
         let result: RedisResult<String> = conn.lpop(key, None).await;
 
         match result {
@@ -462,7 +630,7 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
+        let mut conn = self.manager.connection()?.clone();
         let result: RedisResult<String> = conn.rpop(key, None).await;
 
         match result {
@@ -490,7 +658,7 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
+        let mut conn = self.manager.connection()?.clone();
         let length: u32 = conn
             .llen(key)
             .await
@@ -516,9 +684,8 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
-        let added: u32 = conn
-            .sadd(key, member)
+        let mut conn = self.manager.connection()?.clone();
+        let added: u32 = AsyncCommands::sadd(&mut conn, key, member)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis sadd failed: {}", e)))?;
 
@@ -542,9 +709,8 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
-        let removed: u32 = conn
-            .srem(key, member)
+        let mut conn = self.manager.connection()?.clone();
+        let removed: u32 = AsyncCommands::srem(&mut conn, key, member)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis srem failed: {}", e)))?;
 
@@ -568,7 +734,7 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
+        let mut conn = self.manager.connection()?.clone();
         let is_member: bool = conn
             .sismember(key, member)
             .await
@@ -592,12 +758,697 @@ impl RedisUtils {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.manager.connection().clone();
-        let members: Vec<String> = conn
-            .smembers(key)
+        let mut conn = self.manager.connection()?.clone();
+        let members: Vec<String> = AsyncCommands::smembers(&mut conn, key)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis smembers failed: {}", e)))?;
 
         Ok(members)
     }
+
+    /// 设置哈希字段
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 哈希键名
+    /// * `field` - 字段名
+    /// * `value` - 字段值
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<bool, AppError>` - true 表示新建字段，false 表示覆盖已有字段
+    pub async fn hash_set<K, F, V>(&self, key: K, field: F, value: V) -> Result<bool, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+        F: redis::ToRedisArgs + Send + Sync,
+        V: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let created: u32 = conn
+            .hset(key, field, value)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis hset failed: {}", e)))?;
+
+        Ok(created > 0)
+    }
+
+    /// 获取哈希字段值
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 哈希键名
+    /// * `field` - 字段名
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Option<String>, AppError>` - 字段不存在时返回 None
+    pub async fn hash_get<K, F>(&self, key: K, field: F) -> Result<Option<String>, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+        F: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let value: Option<String> = conn
+            .hget(key, field)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis hget failed: {}", e)))?;
+
+        Ok(value)
+    }
+
+    /// 获取哈希的所有字段和值
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 哈希键名
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<HashMap<String, String>, AppError>` - 键不存在时返回空表
+    pub async fn hash_get_all<K>(&self, key: K) -> Result<HashMap<String, String>, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let fields: HashMap<String, String> = conn
+            .hgetall(key)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis hgetall failed: {}", e)))?;
+
+        Ok(fields)
+    }
+
+    /// 删除哈希字段
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 哈希键名
+    /// * `field` - 字段名
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<bool, AppError>` - true 表示字段存在并被删除
+    pub async fn hash_delete<K, F>(&self, key: K, field: F) -> Result<bool, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+        F: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let deleted: u32 = conn
+            .hdel(key, field)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis hdel failed: {}", e)))?;
+
+        Ok(deleted > 0)
+    }
+
+    /// 检查哈希字段是否存在
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 哈希键名
+    /// * `field` - 字段名
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<bool, AppError>`
+    pub async fn hash_exists<K, F>(&self, key: K, field: F) -> Result<bool, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+        F: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let exists: bool = conn
+            .hexists(key, field)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis hexists failed: {}", e)))?;
+
+        Ok(exists)
+    }
+
+    /// 有序集合添加成员
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 有序集合键名
+    /// * `member` - 成员
+    /// * `score` - 分数
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<bool, AppError>` - true 表示新增成员，false 表示更新已有成员的分数
+    pub async fn zadd<K, M>(&self, key: K, member: M, score: f64) -> Result<bool, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+        M: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let added: u32 = conn
+            .zadd(key, member, score)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis zadd failed: {}", e)))?;
+
+        Ok(added > 0)
+    }
+
+    /// 按分数升序获取有序集合成员及分数
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 有序集合键名
+    /// * `start` - 起始索引（支持负数，-1 表示最后一个）
+    /// * `stop` - 结束索引（包含）
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Vec<(String, f64)>, AppError>`
+    pub async fn zrange_with_scores<K>(
+        &self,
+        key: K,
+        start: isize,
+        stop: isize,
+    ) -> Result<Vec<(String, f64)>, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let members: Vec<(String, f64)> = conn
+            .zrange_withscores(key, start, stop)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis zrange failed: {}", e)))?;
+
+        Ok(members)
+    }
+
+    /// 按分数降序获取有序集合成员
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 有序集合键名
+    /// * `start` - 起始索引（支持负数）
+    /// * `stop` - 结束索引（包含）
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Vec<String>, AppError>`
+    pub async fn zrevrange<K>(
+        &self,
+        key: K,
+        start: isize,
+        stop: isize,
+    ) -> Result<Vec<String>, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let members: Vec<String> = conn
+            .zrevrange(key, start, stop)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis zrevrange failed: {}", e)))?;
+
+        Ok(members)
+    }
+
+    /// 获取有序集合成员的分数
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 有序集合键名
+    /// * `member` - 成员
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Option<f64>, AppError>` - 成员不存在时返回 None
+    pub async fn zscore<K, M>(&self, key: K, member: M) -> Result<Option<f64>, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+        M: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let score: Option<f64> = conn
+            .zscore(key, member)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis zscore failed: {}", e)))?;
+
+        Ok(score)
+    }
+
+    /// 有序集合移除成员
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 有序集合键名
+    /// * `member` - 要移除的成员
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<bool, AppError>` - true 表示成员存在并被移除
+    pub async fn zrem<K, M>(&self, key: K, member: M) -> Result<bool, AppError>
+    where
+        K: redis::ToRedisArgs + Send + Sync,
+        M: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        let removed: u32 = conn
+            .zrem(key, member)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis zrem failed: {}", e)))?;
+
+        Ok(removed > 0)
+    }
+
+    /// 发布消息到频道
+    ///
+    /// # 参数
+    ///
+    /// * `channel` - 频道名
+    /// * `message` - 消息内容
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<u32, AppError>` - 收到消息的订阅者数量
+    pub async fn publish<C, M>(&self, channel: C, message: M) -> Result<u32, AppError>
+    where
+        C: redis::ToRedisArgs + Send + Sync,
+        M: redis::ToRedisArgs + Send + Sync,
+    {
+        use redis::AsyncCommands;
+
+        let mut conn = self.manager.connection()?.clone();
+        conn.publish(channel, message)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis publish failed: {}", e)))
+    }
+
+    /// 订阅频道
+    ///
+    /// 订阅模式下的连接只能接收消息，不能与 `ConnectionManager` 的多路复用连接共享，
+    /// 因此每次订阅都会新建一个专用连接。返回的消息流被丢弃时连接随之关闭。
+    /// 无法解码为 UTF-8 字符串的消息会被跳过。
+    ///
+    /// # 参数
+    ///
+    /// * `channel` - 频道名
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<BoxStream<'static, String>, AppError>` - 订阅成功后收到的消息流
+    pub async fn subscribe(&self, channel: &str) -> Result<BoxStream<'static, String>, AppError> {
+        let connection = self
+            .manager
+            .client()?
+            .get_async_connection()
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Redis pubsub connection failed: {}", e))
+            })?;
+
+        let mut pubsub = connection.into_pubsub();
+        pubsub
+            .subscribe(channel)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis subscribe failed: {}", e)))?;
+
+        let messages = pubsub.into_on_message().filter_map(|msg| async move {
+            match msg.get_payload::<String>() {
+                Ok(payload) => Some(payload),
+                Err(e) => {
+                    tracing::warn!(
+                        "忽略无法解码的订阅消息 (channel: {}): {}",
+                        msg.get_channel_name(),
+                        e
+                    );
+                    None
+                }
+            }
+        });
+
+        Ok(messages.boxed())
+    }
+
+    /// 尝试获取分布式锁
+    ///
+    /// 使用 `SET key token NX EX ttl` 获取锁，锁的值为随机 token，
+    /// 释放时只有 token 匹配才会删除，避免误删其他实例持有的锁。
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 锁的键名
+    /// * `ttl_seconds` - 锁的过期时间（秒），防止持有者崩溃后锁无法释放
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Option<LockGuard>, AppError>` - 锁已被占用时返回 None
+    pub async fn acquire_lock(
+        &self,
+        key: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<LockGuard>, AppError> {
+        let mut conn = self.manager.connection()?.clone();
+        let token = uuid::Uuid::new_v4().to_string();
+
+        let result: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(&token)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl_seconds)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis lock acquire failed: {}", e)))?;
+
+        Ok(result.map(|_| LockGuard {
+            manager: self.manager.clone(),
+            key: key.to_string(),
+            token,
+            released: false,
+        }))
+    }
+
+    /// 在持有分布式锁的情况下执行任务
+    ///
+    /// 获取锁成功时执行 `task` 并在完成后释放锁；锁已被占用时不执行任务。
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 锁的键名
+    /// * `ttl_seconds` - 锁的过期时间（秒）
+    /// * `task` - 需要互斥执行的异步任务
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Option<T>, AppError>` - 未获取到锁时返回 None
+    pub async fn try_with_lock<F, Fut, T>(
+        &self,
+        key: &str,
+        ttl_seconds: u64,
+        task: F,
+    ) -> Result<Option<T>, AppError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let Some(guard) = self.acquire_lock(key, ttl_seconds).await? else {
+            return Ok(None);
+        };
+
+        let output = task().await;
+        guard.release().await?;
+
+        Ok(Some(output))
+    }
+}
+
+/// 释放锁的 Lua 脚本：仅当锁的值与 token 一致时才删除
+const RELEASE_LOCK_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+else
+    return 0
+end
+"#;
+
+/// 分布式锁守卫
+///
+/// 由 `RedisUtils::acquire_lock` 返回。推荐调用 `release` 显式释放锁；
+/// 未显式释放时会在 `Drop` 中尽力异步释放（需要处于 Tokio 运行时中），
+/// 否则等待锁自然过期。
+pub struct LockGuard {
+    manager: RedisManager,
+    key: String,
+    token: String,
+    released: bool,
+}
+
+impl LockGuard {
+    /// 获取锁的键名
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// 释放锁
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<bool, AppError>` - false 表示锁已过期或已被其他实例持有
+    pub async fn release(mut self) -> Result<bool, AppError> {
+        self.released = true;
+        release_lock(&self.manager, &self.key, &self.token).await
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let manager = self.manager.clone();
+            let key = std::mem::take(&mut self.key);
+            let token = std::mem::take(&mut self.token);
+            handle.spawn(async move {
+                if let Err(e) = release_lock(&manager, &key, &token).await {
+                    tracing::warn!("Failed to release lock {}: {}", key, e);
+                }
+            });
+        }
+    }
+}
+
+/// 原子地比较 token 并删除锁
+async fn release_lock(manager: &RedisManager, key: &str, token: &str) -> Result<bool, AppError> {
+    let mut conn = manager.connection()?.clone();
+    let deleted: u32 = redis::Script::new(RELEASE_LOCK_SCRIPT)
+        .key(key)
+        .arg(token)
+        .invoke_async(&mut conn)
+        .await
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis lock release failed: {}", e)))?;
+
+    Ok(deleted > 0)
+}
+
+/// 测试辅助工具
+///
+/// 提供连接测试 Redis 实例的辅助函数。依赖 Redis 的测试需要
+/// 通过 `REDIS_URL` 指定可用的 Redis，并使用 `cargo test -- --ignored` 运行。
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::RedisManager;
+    use crate::config::Config;
+
+    /// 连接测试用 Redis
+    pub async fn redis_manager() -> RedisManager {
+        let config = Config::from_env().expect("Failed to load config");
+        RedisManager::new(&config)
+            .await
+            .expect("Failed to connect to Redis")
+    }
+
+    /// 生成测试专用的唯一键名，避免测试之间相互干扰
+    pub fn unique_key(prefix: &str) -> String {
+        format!("test:{}:{}", prefix, uuid::Uuid::new_v4())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn redis_utils() -> RedisUtils {
+        RedisUtils::new(test_support::redis_manager().await)
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_hash_operations() {
+        let redis = redis_utils().await;
+        let key = test_support::unique_key("hash");
+
+        assert!(redis.hash_set(&key, "name", "张三").await.unwrap());
+        assert!(redis.hash_set(&key, "visits", 1).await.unwrap());
+        // 覆盖已有字段返回 false
+        assert!(!redis.hash_set(&key, "visits", 2).await.unwrap());
+
+        assert_eq!(
+            redis.hash_get(&key, "name").await.unwrap(),
+            Some("张三".to_string())
+        );
+        assert_eq!(redis.hash_get(&key, "missing").await.unwrap(), None);
+        assert!(redis.hash_exists(&key, "visits").await.unwrap());
+        assert!(!redis.hash_exists(&key, "missing").await.unwrap());
+
+        let all = redis.hash_get_all(&key).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all.get("visits"), Some(&"2".to_string()));
+
+        assert!(redis.hash_delete(&key, "name").await.unwrap());
+        assert!(!redis.hash_delete(&key, "name").await.unwrap());
+        assert_eq!(redis.hash_get(&key, "name").await.unwrap(), None);
+
+        redis.delete(&key).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_sorted_set_operations() {
+        let redis = redis_utils().await;
+        let key = test_support::unique_key("zset");
+
+        assert!(redis.zadd(&key, "alice", 30.0).await.unwrap());
+        assert!(redis.zadd(&key, "bob", 10.0).await.unwrap());
+        assert!(redis.zadd(&key, "carol", 20.0).await.unwrap());
+
+        assert_eq!(
+            redis.zrevrange(&key, 0, -1).await.unwrap(),
+            vec!["alice", "carol", "bob"]
+        );
+        assert_eq!(
+            redis.zrange_with_scores(&key, 0, -1).await.unwrap(),
+            vec![
+                ("bob".to_string(), 10.0),
+                ("carol".to_string(), 20.0),
+                ("alice".to_string(), 30.0)
+            ]
+        );
+
+        assert_eq!(redis.zscore(&key, "carol").await.unwrap(), Some(20.0));
+        assert_eq!(redis.zscore(&key, "dave").await.unwrap(), None);
+
+        assert!(redis.zrem(&key, "carol").await.unwrap());
+        assert!(!redis.zrem(&key, "carol").await.unwrap());
+        assert_eq!(
+            redis.zrevrange(&key, 0, -1).await.unwrap(),
+            vec!["alice", "bob"]
+        );
+
+        redis.delete(&key).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_mset_mget_json() {
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        struct Item {
+            id: u32,
+            name: String,
+        }
+
+        let redis = redis_utils().await;
+        let items: Vec<Item> = (1..=3)
+            .map(|id| Item {
+                id,
+                name: format!("item-{}", id),
+            })
+            .collect();
+        let keys: Vec<String> = (0..3).map(|_| test_support::unique_key("mjson")).collect();
+
+        let entries: Vec<(String, &Item)> = keys.iter().cloned().zip(&items).collect();
+        redis.mset_json(&entries, Some(60)).await.unwrap();
+        assert!(redis.ttl(&keys[0]).await.unwrap().is_some());
+
+        // 结果顺序与键一致，不存在和无法反序列化的键返回 None
+        let missing = test_support::unique_key("mjson");
+        let invalid = test_support::unique_key("mjson");
+        redis
+            .set_string(&invalid, "not json", Some(60))
+            .await
+            .unwrap();
+        let lookup = vec![
+            keys[2].clone(),
+            missing,
+            keys[0].clone(),
+            invalid.clone(),
+            keys[1].clone(),
+        ];
+        let values: Vec<Option<Item>> = redis.mget_json(&lookup).await.unwrap();
+        assert_eq!(
+            values,
+            vec![
+                Some(items[2].clone()),
+                None,
+                Some(items[0].clone()),
+                None,
+                Some(items[1].clone()),
+            ]
+        );
+
+        assert!(redis.mget_json::<Item>(&[]).await.unwrap().is_empty());
+
+        for key in keys.iter().chain([&invalid]) {
+            redis.delete(key).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_distributed_lock() {
+        let redis = redis_utils().await;
+        let key = test_support::unique_key("lock");
+
+        let guard = redis.acquire_lock(&key, 30).await.unwrap();
+        assert!(guard.is_some());
+
+        // 锁被持有时无法再次获取
+        assert!(redis.acquire_lock(&key, 30).await.unwrap().is_none());
+        assert_eq!(
+            redis.try_with_lock(&key, 30, || async { 1 }).await.unwrap(),
+            None
+        );
+
+        assert!(guard.unwrap().release().await.unwrap());
+
+        // 释放后可以重新获取
+        assert_eq!(
+            redis.try_with_lock(&key, 30, || async { 1 }).await.unwrap(),
+            Some(1)
+        );
+        assert!(!redis.exists(&key).await.unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_publish_subscribe() {
+        let redis = redis_utils().await;
+        let channel = test_support::unique_key("pubsub");
+
+        let mut messages = redis.subscribe(&channel).await.unwrap();
+        assert_eq!(redis.publish(&channel, "hello").await.unwrap(), 1);
+
+        let received = tokio::time::timeout(std::time::Duration::from_secs(5), messages.next())
+            .await
+            .expect("timed out waiting for message");
+        assert_eq!(received.as_deref(), Some("hello"));
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_manager_without_connection_returns_error() {
+        let redis = RedisUtils::new(RedisManager::in_memory());
+
+        assert!(redis.manager.connection().is_err());
+        assert!(redis.manager.client().is_err());
+        assert!(redis.acquire_lock("lock:in-memory", 30).await.is_err());
+        assert!(redis.subscribe("pubsub:in-memory").await.is_err());
+    }
 }
@@ -0,0 +1,525 @@
+/*!
+ * Redis 存储后端抽象
+ *
+ * `RedisBackend` 抽象了 `TokenService` 使用的 Redis 操作，默认由 `ConnectionManager`
+ * 实现；`MemoryBackend` 是基于 `HashMap` 的内存实现，用于在没有 Redis 实例时测试认证逻辑。
+ */
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use ::redis::{aio::ConnectionManager, AsyncCommands, ErrorKind, RedisResult};
+use axum::async_trait;
+
+/// Redis 存储后端
+///
+/// 方法与同名的 Redis 命令语义一致，错误类型沿用 `RedisError`，
+/// 调用方可以像直接使用连接时一样为错误附加上下文。
+#[async_trait]
+pub trait RedisBackend: Send + Sync {
+    /// 获取字符串值（`GET`）
+    async fn get(&self, key: &str) -> RedisResult<Option<String>>;
+
+    /// 设置字符串值，`expiry_seconds` 为 `None` 时不过期（`SET` / `SETEX`）
+    async fn set(&self, key: &str, value: &str, expiry_seconds: Option<u64>) -> RedisResult<()>;
+
+    /// 仅在键不存在时设置字符串值（`SET ... NX EX`）
+    ///
+    /// 返回键是否被设置
+    async fn set_if_absent(&self, key: &str, value: &str, expiry_seconds: u64)
+        -> RedisResult<bool>;
+
+    /// 覆盖已存在的键并保留原有的过期时间（`SET ... XX KEEPTTL`）
+    ///
+    /// 返回键是否存在并被覆盖
+    async fn set_existing_keep_ttl(&self, key: &str, value: &str) -> RedisResult<bool>;
+
+    /// 原子地读取并删除键（`GETDEL`）
+    async fn get_del(&self, key: &str) -> RedisResult<Option<String>>;
+
+    /// 删除键（`DEL`），返回键是否存在
+    async fn del(&self, key: &str) -> RedisResult<bool>;
+
+    /// 检查键是否存在（`EXISTS`）
+    async fn exists(&self, key: &str) -> RedisResult<bool>;
+
+    /// 设置键的过期时间（`EXPIRE`），返回键是否存在
+    async fn expire(&self, key: &str, seconds: u64) -> RedisResult<bool>;
+
+    /// 获取键的剩余生存时间（`TTL`），键不存在或没有过期时间时返回 `None`
+    async fn ttl(&self, key: &str) -> RedisResult<Option<u64>>;
+
+    /// 向集合添加成员（`SADD`），返回成员是否为新添加的
+    async fn sadd(&self, key: &str, member: &str) -> RedisResult<bool>;
+
+    /// 从集合移除成员（`SREM`），返回成员是否存在
+    async fn srem(&self, key: &str, member: &str) -> RedisResult<bool>;
+
+    /// 获取集合所有成员（`SMEMBERS`）
+    async fn smembers(&self, key: &str) -> RedisResult<Vec<String>>;
+
+    /// 获取集合成员数量（`SCARD`）
+    async fn scard(&self, key: &str) -> RedisResult<u32>;
+
+    /// 按模式分批遍历键（`SCAN cursor MATCH pattern COUNT count`）
+    ///
+    /// 返回下一次遍历的游标和本批的键，游标为 0 表示遍历完成
+    async fn scan(
+        &self,
+        cursor: u64,
+        pattern: &str,
+        count: usize,
+    ) -> RedisResult<(u64, Vec<String>)>;
+}
+
+// 方法名与 `AsyncCommands` 重名，需要使用完整路径调用，避免递归调用自身
+#[async_trait]
+impl RedisBackend for ConnectionManager {
+    async fn get(&self, key: &str) -> RedisResult<Option<String>> {
+        AsyncCommands::get(&mut self.clone(), key).await
+    }
+
+    async fn set(&self, key: &str, value: &str, expiry_seconds: Option<u64>) -> RedisResult<()> {
+        let mut conn = self.clone();
+        match expiry_seconds {
+            Some(seconds) => conn.set_ex(key, value, seconds).await,
+            None => AsyncCommands::set(&mut conn, key, value).await,
+        }
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: &str,
+        expiry_seconds: u64,
+    ) -> RedisResult<bool> {
+        let reply: Option<String> = ::redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("NX")
+            .arg("EX")
+            .arg(expiry_seconds)
+            .query_async(&mut self.clone())
+            .await?;
+
+        Ok(reply.is_some())
+    }
+
+    async fn set_existing_keep_ttl(&self, key: &str, value: &str) -> RedisResult<bool> {
+        let reply: Option<String> = ::redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("XX")
+            .arg("KEEPTTL")
+            .query_async(&mut self.clone())
+            .await?;
+
+        Ok(reply.is_some())
+    }
+
+    async fn get_del(&self, key: &str) -> RedisResult<Option<String>> {
+        ::redis::cmd("GETDEL")
+            .arg(key)
+            .query_async(&mut self.clone())
+            .await
+    }
+
+    async fn del(&self, key: &str) -> RedisResult<bool> {
+        let deleted: u32 = AsyncCommands::del(&mut self.clone(), key).await?;
+        Ok(deleted > 0)
+    }
+
+    async fn exists(&self, key: &str) -> RedisResult<bool> {
+        AsyncCommands::exists(&mut self.clone(), key).await
+    }
+
+    async fn expire(&self, key: &str, seconds: u64) -> RedisResult<bool> {
+        AsyncCommands::expire(&mut self.clone(), key, seconds as i64).await
+    }
+
+    async fn ttl(&self, key: &str) -> RedisResult<Option<u64>> {
+        let ttl: i64 = AsyncCommands::ttl(&mut self.clone(), key).await?;
+        Ok((ttl > 0).then_some(ttl as u64))
+    }
+
+    async fn sadd(&self, key: &str, member: &str) -> RedisResult<bool> {
+        let added: u32 = AsyncCommands::sadd(&mut self.clone(), key, member).await?;
+        Ok(added > 0)
+    }
+
+    async fn srem(&self, key: &str, member: &str) -> RedisResult<bool> {
+        let removed: u32 = AsyncCommands::srem(&mut self.clone(), key, member).await?;
+        Ok(removed > 0)
+    }
+
+    async fn smembers(&self, key: &str) -> RedisResult<Vec<String>> {
+        AsyncCommands::smembers(&mut self.clone(), key).await
+    }
+
+    async fn scard(&self, key: &str) -> RedisResult<u32> {
+        AsyncCommands::scard(&mut self.clone(), key).await
+    }
+
+    async fn scan(
+        &self,
+        cursor: u64,
+        pattern: &str,
+        count: usize,
+    ) -> RedisResult<(u64, Vec<String>)> {
+        ::redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(pattern)
+            .arg("COUNT")
+            .arg(count)
+            .query_async(&mut self.clone())
+            .await
+    }
+}
+
+/// 内存中存储的值
+#[derive(Debug, Clone)]
+enum Value {
+    String(String),
+    Set(HashSet<String>),
+}
+
+/// 内存中存储的条目
+#[derive(Debug, Clone)]
+struct Entry {
+    value: Value,
+    expires_at: Option<Instant>,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// 基于 `HashMap` 的内存后端
+///
+/// 支持键过期（访问时惰性删除），对类型不匹配的键返回 `WRONGTYPE` 错误。
+/// 数据只保存在当前进程中，仅适用于测试和本地开发。
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_rust::RedisManager;
+///
+/// let redis = RedisManager::in_memory();
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl MemoryBackend {
+    /// 创建空的内存后端
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 在锁内访问未过期的数据，已过期的键会先被删除
+    fn with_entries<T>(&self, f: impl FnOnce(&mut HashMap<String, Entry>) -> T) -> T {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = Instant::now();
+        entries.retain(|_, entry| !entry.is_expired(now));
+        f(&mut entries)
+    }
+}
+
+/// 类型不匹配错误，与 Redis 的 `WRONGTYPE` 错误对应
+fn wrong_type<T>() -> RedisResult<T> {
+    Err((
+        ErrorKind::TypeError,
+        "WRONGTYPE Operation against a key holding the wrong kind of value",
+    )
+        .into())
+}
+
+/// 读取字符串值，键不存在时返回 `None`
+fn string_value(entry: Option<&Entry>) -> RedisResult<Option<String>> {
+    match entry.map(|entry| &entry.value) {
+        None => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(Value::Set(_)) => wrong_type(),
+    }
+}
+
+/// 读取集合，键不存在时返回 `None`
+fn set_value(entry: Option<&Entry>) -> RedisResult<Option<&HashSet<String>>> {
+    match entry.map(|entry| &entry.value) {
+        None => Ok(None),
+        Some(Value::Set(members)) => Ok(Some(members)),
+        Some(Value::String(_)) => wrong_type(),
+    }
+}
+
+/// Redis 风格的通配符匹配（支持 `*` 和 `?`）
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // 最近一个 `*` 在模式中的位置，以及它当时匹配到的文本位置
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // 让上一个 `*` 多匹配一个字符后重试
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[async_trait]
+impl RedisBackend for MemoryBackend {
+    async fn get(&self, key: &str) -> RedisResult<Option<String>> {
+        self.with_entries(|entries| string_value(entries.get(key)))
+    }
+
+    async fn set(&self, key: &str, value: &str, expiry_seconds: Option<u64>) -> RedisResult<()> {
+        self.with_entries(|entries| {
+            entries.insert(
+                key.to_string(),
+                Entry {
+                    value: Value::String(value.to_string()),
+                    expires_at: expiry_seconds
+                        .map(|seconds| Instant::now() + Duration::from_secs(seconds)),
+                },
+            );
+            Ok(())
+        })
+    }
+
+    async fn set_if_absent(
+        &self,
+        key: &str,
+        value: &str,
+        expiry_seconds: u64,
+    ) -> RedisResult<bool> {
+        self.with_entries(|entries| {
+            if entries.contains_key(key) {
+                return Ok(false);
+            }
+            entries.insert(
+                key.to_string(),
+                Entry {
+                    value: Value::String(value.to_string()),
+                    expires_at: Some(Instant::now() + Duration::from_secs(expiry_seconds)),
+                },
+            );
+            Ok(true)
+        })
+    }
+
+    async fn set_existing_keep_ttl(&self, key: &str, value: &str) -> RedisResult<bool> {
+        self.with_entries(|entries| match entries.get_mut(key) {
+            Some(entry) => {
+                entry.value = Value::String(value.to_string());
+                Ok(true)
+            }
+            None => Ok(false),
+        })
+    }
+
+    async fn get_del(&self, key: &str) -> RedisResult<Option<String>> {
+        self.with_entries(|entries| {
+            let value = string_value(entries.get(key))?;
+            entries.remove(key);
+            Ok(value)
+        })
+    }
+
+    async fn del(&self, key: &str) -> RedisResult<bool> {
+        self.with_entries(|entries| Ok(entries.remove(key).is_some()))
+    }
+
+    async fn exists(&self, key: &str) -> RedisResult<bool> {
+        self.with_entries(|entries| Ok(entries.contains_key(key)))
+    }
+
+    async fn expire(&self, key: &str, seconds: u64) -> RedisResult<bool> {
+        self.with_entries(|entries| match entries.get_mut(key) {
+            Some(entry) => {
+                entry.expires_at = Some(Instant::now() + Duration::from_secs(seconds));
+                Ok(true)
+            }
+            None => Ok(false),
+        })
+    }
+
+    async fn ttl(&self, key: &str) -> RedisResult<Option<u64>> {
+        self.with_entries(|entries| {
+            Ok(entries
+                .get(key)
+                .and_then(|entry| entry.expires_at)
+                .map(|expires_at| {
+                    // 与 Redis 一致，剩余不足一秒时向上取整
+                    let remaining = expires_at.saturating_duration_since(Instant::now());
+                    remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+                }))
+        })
+    }
+
+    async fn sadd(&self, key: &str, member: &str) -> RedisResult<bool> {
+        self.with_entries(|entries| {
+            let entry = entries.entry(key.to_string()).or_insert_with(|| Entry {
+                value: Value::Set(HashSet::new()),
+                expires_at: None,
+            });
+            match &mut entry.value {
+                Value::Set(members) => Ok(members.insert(member.to_string())),
+                Value::String(_) => wrong_type(),
+            }
+        })
+    }
+
+    async fn srem(&self, key: &str, member: &str) -> RedisResult<bool> {
+        self.with_entries(|entries| {
+            let Some(entry) = entries.get_mut(key) else {
+                return Ok(false);
+            };
+            let Value::Set(members) = &mut entry.value else {
+                return wrong_type();
+            };
+
+            let removed = members.remove(member);
+            // 与 Redis 一致，集合为空时删除键
+            if members.is_empty() {
+                entries.remove(key);
+            }
+            Ok(removed)
+        })
+    }
+
+    async fn smembers(&self, key: &str) -> RedisResult<Vec<String>> {
+        self.with_entries(|entries| {
+            Ok(set_value(entries.get(key))?
+                .map(|members| members.iter().cloned().collect())
+                .unwrap_or_default())
+        })
+    }
+
+    async fn scard(&self, key: &str) -> RedisResult<u32> {
+        self.with_entries(|entries| {
+            Ok(set_value(entries.get(key))?.map_or(0, |members| members.len() as u32))
+        })
+    }
+
+    /// 内存后端一次返回所有匹配的键，游标始终为 0
+    async fn scan(
+        &self,
+        _cursor: u64,
+        pattern: &str,
+        _count: usize,
+    ) -> RedisResult<(u64, Vec<String>)> {
+        self.with_entries(|entries| {
+            Ok((
+                0,
+                entries
+                    .keys()
+                    .filter(|key| glob_match(pattern, key))
+                    .cloned()
+                    .collect(),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("auth:token:*", "auth:token:abc"));
+        assert!(glob_match("auth:token:*", "auth:token:"));
+        assert!(!glob_match("auth:token:*", "auth:refresh:abc"));
+        assert!(glob_match("a*b*c", "aXXbYYc"));
+        assert!(glob_match("h?llo", "hello"));
+        assert!(!glob_match("h?llo", "heello"));
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_strings_and_expiry() {
+        let backend = MemoryBackend::new();
+
+        backend.set("key", "value", None).await.unwrap();
+        assert_eq!(backend.get("key").await.unwrap().as_deref(), Some("value"));
+        assert_eq!(backend.ttl("key").await.unwrap(), None);
+
+        // NX 只设置不存在的键
+        assert!(!backend.set_if_absent("key", "other", 60).await.unwrap());
+        assert!(backend.set_if_absent("nx", "value", 60).await.unwrap());
+        assert_eq!(backend.ttl("nx").await.unwrap(), Some(60));
+
+        // XX 只覆盖已存在的键，并保留过期时间
+        assert!(!backend.set_existing_keep_ttl("missing", "v").await.unwrap());
+        assert!(!backend.exists("missing").await.unwrap());
+        assert!(backend.expire("key", 60).await.unwrap());
+        assert!(backend.set_existing_keep_ttl("key", "new").await.unwrap());
+        assert_eq!(backend.ttl("key").await.unwrap(), Some(60));
+
+        assert_eq!(
+            backend.get_del("key").await.unwrap().as_deref(),
+            Some("new")
+        );
+        assert_eq!(backend.get("key").await.unwrap(), None);
+
+        // 过期时间为 0 的键立即失效
+        backend.set("short", "value", Some(60)).await.unwrap();
+        assert!(backend.expire("short", 0).await.unwrap());
+        assert!(!backend.exists("short").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_sets_and_scan() {
+        let backend = MemoryBackend::new();
+
+        assert!(backend.sadd("set", "a").await.unwrap());
+        assert!(!backend.sadd("set", "a").await.unwrap());
+        assert!(backend.sadd("set", "b").await.unwrap());
+        assert_eq!(backend.scard("set").await.unwrap(), 2);
+
+        let mut members = backend.smembers("set").await.unwrap();
+        members.sort();
+        assert_eq!(members, vec!["a", "b"]);
+
+        // 对字符串键执行集合操作返回类型错误
+        backend.set("string", "value", None).await.unwrap();
+        assert!(backend.sadd("string", "a").await.is_err());
+
+        let (cursor, mut keys) = backend.scan(0, "s*", 10).await.unwrap();
+        keys.sort();
+        assert_eq!(cursor, 0);
+        assert_eq!(keys, vec!["set", "string"]);
+
+        assert!(backend.srem("set", "a").await.unwrap());
+        assert!(backend.srem("set", "b").await.unwrap());
+        assert!(!backend.exists("set").await.unwrap());
+    }
+}
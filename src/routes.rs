@@ -5,35 +5,53 @@
  * 包含公开路由和需要身份验证的受保护路由。
  */
 
+use std::{sync::Arc, time::Duration};
+
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{get, post},
     Router,
 };
+use tower_http::compression::CompressionLayer;
 
 use crate::{
     config::Config,
     db::DbPool,
     handlers::{
-        get_all_users, get_profile, get_sessions, login, logout, logout_all, logout_device,
-        register,
+        change_password, forgot_password, get_all_users, get_audit_log, get_profile, get_sessions,
+        health_check, login, logout, logout_all, logout_device, refresh, register, reset_password,
+        send_verification, stream_events, update_profile, verify_email, EventStreamLimiter,
+        MAX_EVENT_STREAMS_PER_USER,
     },
-    middleware::auth_middleware,
+    metrics::{metrics_handler, track_metrics},
+    middleware::{auth_middleware, idempotency_middleware, request_timeout, require_role},
+    models::ROLE_ADMIN,
     redis::RedisManager,
+    services::{EmailSender, WebhookService},
+    utils::JwtKeys,
 };
 
 /// 应用程序状态
 ///
 /// 包含在整个应用程序生命周期中需要共享的数据，
-/// 如数据库连接池、Redis管理器和配置信息。
+/// 如数据库连接池、Redis管理器、JWT 密钥、配置信息、邮件发送器和 Webhook 通知服务。
 #[derive(Clone)]
 pub struct AppState {
     /// 数据库连接池
     pub pool: DbPool,
     /// Redis管理器
     pub redis: RedisManager,
+    /// JWT 签名和验证密钥
+    pub jwt_keys: JwtKeys,
     /// 应用配置
     pub config: Config,
+    /// 邮件发送器（验证码、密码重置令牌）
+    pub email: Arc<dyn EmailSender>,
+    /// Webhook 通知服务（注册、撤销会话等事件）
+    pub webhooks: WebhookService,
+    /// 审计事件流（SSE）的并发限制
+    pub event_streams: EventStreamLimiter,
 }
 
 /// 创建应用程序路由
@@ -42,59 +60,499 @@ pub struct AppState {
 /// - 公开的身份验证路由 (`/api/auth`)
 /// - 需要身份验证的受保护路由 (`/api`)
 /// - 健康检查路由 (`/health`)
+/// - Prometheus 指标路由 (`/metrics`)
 ///
 /// # 参数
 ///
 /// * `pool` - 数据库连接池
 /// * `redis_manager` - Redis管理器
+/// * `jwt_keys` - JWT 签名和验证密钥（通过 `JwtKeys::from_config` 创建）
 /// * `config` - 应用配置
+/// * `email` - 邮件发送器（通过 `email_sender_from_config` 创建）
 ///
 /// # 返回值
 ///
 /// 返回配置好的 Axum Router
-pub fn create_routes(pool: DbPool, redis_manager: RedisManager, config: Config) -> Router {
-    // 创建应用状态，包含共享的数据库连接池、Redis管理器和配置
+pub fn create_routes(
+    pool: DbPool,
+    redis_manager: RedisManager,
+    jwt_keys: JwtKeys,
+    config: Config,
+    email: Arc<dyn EmailSender>,
+) -> Router {
+    // 创建应用状态，包含共享的数据库连接池、Redis管理器、JWT 密钥、配置、邮件发送器和 Webhook 通知服务
     let app_state = AppState {
         pool,
         redis: redis_manager,
+        jwt_keys,
+        webhooks: WebhookService::from_config(&config),
         config: config.clone(),
+        email,
+        event_streams: EventStreamLimiter::new(MAX_EVENT_STREAMS_PER_USER),
     };
 
     // 公开的身份验证路由
     // 这些路由不需要用户登录即可访问
     let auth_routes = Router::new()
-        .route("/register", post(register)) // 用户注册
-        .route("/login", post(login)) // 用户登录
         .route("/logout", post(logout)) // 退出登录（需要token）
         .route("/logout-all", post(logout_all)) // 退出所有设备（需要token）
         .route("/sessions", get(get_sessions)) // 获取活跃会话列表（需要token）
-        .route("/logout-device/:device_type", post(logout_device)); // 撤销特定设备登录（需要token）
+        .route("/logout-device/:device_type", post(logout_device)) // 撤销特定设备登录（需要token）
+        .route("/send-verification", post(send_verification)) // 发送邮箱验证码
+        .route("/verify-email", post(verify_email)) // 验证邮箱
+        .route("/forgot-password", post(forgot_password)) // 申请密码重置令牌
+        .route("/reset-password", post(reset_password)) // 使用重置令牌设置新密码
+        .route("/register", post(register)) // 用户注册
+        .route("/login", post(login)) // 用户登录
+        .route("/refresh", post(refresh)) // 使用刷新令牌换取新令牌
+        .route_layer(middleware::from_fn_with_state(
+            app_state.redis.clone(),
+            idempotency_middleware(config.max_request_body_bytes),
+        )); // 携带 Idempotency-Key 的重试请求返回第一次的响应（响应体加密保存）
+
+    // 管理员路由
+    // 在身份验证之后额外检查用户角色，只有 admin 角色可以访问
+    let admin_routes = Router::new()
+        .route("/users", get(get_all_users)) // 获取所有用户列表
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            require_role(ROLE_ADMIN),
+        ));
 
     // 受保护的路由
     // 这些路由需要有效的 JWT Token 才能访问
     let protected_routes = Router::new()
-        .route("/profile", get(get_profile)) // 获取用户个人信息
-        .route("/users", get(get_all_users)) // 获取所有用户列表
+        .route("/profile", get(get_profile).patch(update_profile)) // 获取/更新用户个人信息
+        .route("/profile/password", post(change_password)) // 修改密码
+        .route("/profile/audit", get(get_audit_log)) // 获取安全审计日志
+        .route("/events", get(stream_events)) // 通过 SSE 实时推送审计事件
+        .merge(admin_routes)
+        .route_layer(middleware::from_fn_with_state(
+            app_state.redis.clone(),
+            idempotency_middleware(config.max_request_body_bytes),
+        )) // 幂等键按已认证用户隔离，需在身份验证中间件内层
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
             auth_middleware,
         )); // 应用身份验证中间件
 
     // 组合所有路由
-    Router::new()
+    let router = Router::new()
         .nest("/api/auth", auth_routes) // 挂载身份验证路由到 /api/auth
         .nest("/api", protected_routes) // 挂载受保护路由到 /api
-        .route("/health", get(health_check)) // 健康检查端点
-        .with_state(app_state) // 设置应用状态
+        .route("/health", get(health_check)) // 健康检查端点（检查数据库和 Redis，无需认证）
+        .route("/metrics", get(metrics_handler)) // Prometheus 指标端点（无需认证）
+        .layer(DefaultBodyLimit::max(config.max_request_body_bytes)) // 限制请求体大小，超出时返回 413
+        .layer(middleware::from_fn(request_timeout(Duration::from_secs(
+            config.request_timeout_seconds,
+        )))) // 处理超时时取消请求并返回 504
+        .layer(middleware::from_fn(track_metrics)); // 记录所有路由的请求指标
+
+    // 根据配置压缩响应体
+    let router = if config.enable_compression {
+        router.layer(compression_layer())
+    } else {
+        router
+    };
+
+    router.with_state(app_state) // 设置应用状态
 }
 
-/// 健康检查处理器
-///
-/// 提供一个简单的健康检查端点，用于监控服务是否正常运行。
+/// 创建响应压缩中间件
 ///
-/// # 返回值
-///
-/// 返回字符串 "OK" 表示服务正常
-async fn health_check() -> &'static str {
-    "OK"
+/// 根据请求的 `Accept-Encoding` 协商 gzip 或 br 编码。默认策略会跳过
+/// 小于 32 字节的响应、图片、gRPC 以及 `text/event-stream`，
+/// 因此 SSE 事件流不会被缓冲压缩。
+fn compression_layer() -> CompressionLayer {
+    CompressionLayer::new().gzip(true).br(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_support as db_support, redis::test_support as redis_support, services::LogSender,
+    };
+    use axum::{
+        body::Body,
+        http::{header::CONTENT_TYPE, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_compression_layer() {
+        use axum::{
+            http::header::{ACCEPT_ENCODING, CONTENT_ENCODING},
+            response::sse::{Event, Sse},
+        };
+        use futures_util::stream;
+        use std::convert::Infallible;
+
+        let app = Router::new()
+            .route(
+                "/large",
+                get(|| async {
+                    let items: Vec<String> = (0..1000).map(|i| format!("user-{}", i)).collect();
+                    axum::Json(serde_json::json!({ "items": items }))
+                }),
+            )
+            .route(
+                "/events",
+                get(|| async {
+                    let event = Event::default().data("x".repeat(1024));
+                    Sse::new(stream::iter([Ok::<_, Infallible>(event)]))
+                }),
+            )
+            .layer(compression_layer());
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::get("/large")
+                    .header(ACCEPT_ENCODING, "gzip")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+
+        // 未声明支持压缩时返回原始响应
+        let response = app
+            .clone()
+            .oneshot(Request::get("/large").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+
+        // SSE 事件流不压缩
+        let response = app
+            .oneshot(
+                Request::get("/events")
+                    .header(ACCEPT_ENCODING, "gzip")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+    }
+
+    /// 不连接数据库和 Redis 的应用，只能用于在访问存储之前就返回的请求
+    fn offline_app() -> Router {
+        use crate::redis::RedisManager;
+        use sqlx::postgres::PgPoolOptions;
+
+        let config = Config::from_env().unwrap();
+        let jwt_keys = JwtKeys::from_config(&config).unwrap();
+        let pool = PgPoolOptions::new()
+            .connect_lazy(&config.database_url)
+            .unwrap();
+        create_routes(
+            pool,
+            RedisManager::in_memory(),
+            jwt_keys,
+            config,
+            Arc::new(LogSender),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_metrics_use_matched_route_for_nested_routes() {
+        use crate::metrics::prometheus_handle;
+
+        let handle = prometheus_handle().unwrap();
+        // 未携带 token 的请求在访问数据库和 Redis 之前就会被拒绝
+        let app = offline_app();
+
+        let response = app
+            .oneshot(
+                Request::post("/api/auth/logout-device/mobile")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let output = handle.render();
+        assert!(output.contains(
+            r#"http_request_duration_seconds_count{method="POST",path="/api/auth/logout-device/:device_type",status="401",status_class="4xx"}"#
+        ));
+        assert!(!output.contains("/api/auth/logout-device/mobile"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running PostgreSQL and Redis instances"]
+    async fn test_oversized_body_is_rejected() {
+        let mut config = Config::from_env().unwrap();
+        config.max_request_body_bytes = 1024;
+        let jwt_keys = JwtKeys::from_config(&config).unwrap();
+        let app = create_routes(
+            db_support::pool().await,
+            redis_support::redis_manager().await,
+            jwt_keys,
+            config,
+            Arc::new(LogSender),
+        );
+
+        let body = serde_json::json!({
+            "email": db_support::unique_email("oversized"),
+            "password": "x".repeat(4096),
+            "name": "oversized",
+        });
+        let response = app
+            .oneshot(
+                Request::post("/api/auth/register")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    async fn json_body(response: axum::response::Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_extractor_rejections_are_json() {
+        // 请求体格式错误时在访问存储之前返回统一的 JSON 错误
+        let response = offline_app()
+            .oneshot(
+                Request::post("/api/auth/forgot-password")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(Body::from(r#"{"email":"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = json_body(response).await;
+        assert!(body["error"].as_str().unwrap().starts_with("JSON解析失败"));
+
+        let response = offline_app()
+            .oneshot(
+                Request::post("/api/auth/refresh")
+                    .body(Body::from(r#"{"refresh_token":"x"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(json_body(response).await["error"].is_string());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running PostgreSQL and Redis instances"]
+    async fn test_login_is_recorded_in_audit_log() {
+        let config = Config::from_env().unwrap();
+        let jwt_keys = JwtKeys::from_config(&config).unwrap();
+        let app = create_routes(
+            db_support::pool().await,
+            redis_support::redis_manager().await,
+            jwt_keys,
+            config,
+            Arc::new(LogSender),
+        );
+
+        let email = db_support::unique_email("audit");
+        let password = "N3w-Secure-Passw0rd";
+        let response = app
+            .clone()
+            .oneshot(
+                Request::post("/api/auth/register")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "email": email, "password": password, "name": "audit" })
+                            .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // 分别从 Web 和移动设备登录
+        let mut token = String::new();
+        for device_type in ["web", "mobile"] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::post("/api/auth/login")
+                        .header(CONTENT_TYPE, "application/json")
+                        .header("X-Device-Type", device_type)
+                        .header("X-Forwarded-For", "203.0.113.7")
+                        .body(Body::from(
+                            serde_json::json!({ "email": email, "password": password }).to_string(),
+                        ))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            token = json_body(response).await["token"]
+                .as_str()
+                .unwrap()
+                .to_string();
+        }
+
+        let response = app
+            .oneshot(
+                Request::get("/api/profile/audit")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // 最新的登录排在最前面
+        let body = json_body(response).await;
+        let events = body["events"].as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| event["action"] == "login"));
+        assert!(events.iter().all(|event| event["ip"] == "203.0.113.7"));
+        let device_name =
+            |hint| crate::utils::DeviceInfo::from_user_agent("Unknown", Some(hint)).display_name();
+        assert_eq!(events[0]["device"], device_name("mobile"));
+        assert_eq!(events[1]["device"], device_name("web"));
+        assert!(events[0]["timestamp"].as_i64() >= events[1]["timestamp"].as_i64());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_register_with_idempotency_key_runs_once() {
+        use crate::{middleware::IDEMPOTENT_REPLAYED_HEADER, redis::RedisManager};
+
+        let config = Config::from_env().unwrap();
+        let jwt_keys = JwtKeys::from_config(&config).unwrap();
+        let app = create_routes(
+            db_support::pool().await,
+            RedisManager::in_memory(),
+            jwt_keys,
+            config,
+            Arc::new(LogSender),
+        );
+
+        let body = serde_json::json!({
+            "email": db_support::unique_email("idempotent"),
+            "password": "N3w-Secure-Passw0rd",
+            "name": "idempotent",
+        })
+        .to_string();
+        let register = |key: &str| {
+            Request::post("/api/auth/register")
+                .header(CONTENT_TYPE, "application/json")
+                .header("Idempotency-Key", key)
+                .body(Body::from(body.clone()))
+                .unwrap()
+        };
+
+        let first = app.clone().oneshot(register("register-1")).await.unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        assert!(first.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        let first = json_body(first).await;
+
+        // 重试返回第一次的响应，不会再次执行注册
+        let second = app.clone().oneshot(register("register-1")).await.unwrap();
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(second.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(json_body(second).await, first);
+
+        // 使用新的幂等键会真正执行注册，邮箱已被第一次请求注册
+        let third = app.oneshot(register("register-2")).await.unwrap();
+        assert_eq!(third.status(), StatusCode::CONFLICT);
+    }
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_unverified_users_get_no_tokens_when_verification_required() {
+        use crate::{
+            redis::RedisManager,
+            services::{SessionPolicy, TokenService},
+            utils::{AuthUser, DeviceInfo, DeviceType},
+        };
+
+        let mut config = Config::from_env().unwrap();
+        config.require_email_verification = true;
+        let jwt_keys = JwtKeys::from_config(&config).unwrap();
+        let redis = RedisManager::in_memory();
+        let app = create_routes(
+            db_support::pool().await,
+            redis.clone(),
+            jwt_keys.clone(),
+            config,
+            Arc::new(LogSender),
+        );
+
+        let email = db_support::unique_email("unverified");
+        let password = "N3w-Secure-Passw0rd";
+        let post_json = |path: &str, body: serde_json::Value| {
+            Request::post(path)
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        // 注册成功但不签发令牌
+        let response = app
+            .clone()
+            .oneshot(post_json(
+                "/api/auth/register",
+                serde_json::json!({ "email": email, "password": password, "name": "unverified" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert!(body.get("token").is_none(), "{}", body);
+        assert!(body.get("refresh_token").is_none(), "{}", body);
+        assert_eq!(body["user"]["email_verified"], false);
+
+        // 没有令牌无法访问 API，登录同样被拒绝
+        let response = app
+            .clone()
+            .oneshot(Request::get("/api/profile").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = app
+            .clone()
+            .oneshot(post_json(
+                "/api/auth/login",
+                serde_json::json!({ "email": email, "password": password }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // 启用验证前签发的刷新令牌不能继续续期
+        let user_id = uuid::Uuid::parse_str(body["user"]["id"].as_str().unwrap()).unwrap();
+        let token_pair = TokenService::create_token_pair(
+            &redis,
+            &AuthUser::new(user_id),
+            &jwt_keys,
+            DeviceInfo::simple(DeviceType::Api, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+        let response = app
+            .oneshot(post_json(
+                "/api/auth/refresh",
+                serde_json::json!({ "refresh_token": token_pair.refresh_token }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
 }
@@ -0,0 +1,210 @@
+/*!
+ * 审计日志服务
+ *
+ * 记录登录、退出登录、修改密码、撤销会话等安全相关操作。
+ * 每个用户的审计事件存储在独立的 Redis 列表中，新事件插入列表头部，
+ * 超出配置的最大条数时丢弃最早的事件。新事件同时发布到用户的审计频道，
+ * 供事件流等实时功能订阅。
+ */
+
+use chrono::Utc;
+use futures_util::stream::{BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    redis::{RedisManager, RedisUtils},
+    utils::{cache_keys, CacheHelper, DeviceInfo},
+};
+
+/// 审计操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// 登录
+    Login,
+    /// 退出当前会话
+    Logout,
+    /// 退出所有会话
+    LogoutAll,
+    /// 撤销指定设备类型的会话
+    SessionRevoke,
+    /// 修改密码
+    PasswordChange,
+}
+
+/// 审计事件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// 事件发生时间（Unix 时间戳，秒）
+    pub timestamp: i64,
+    /// 用户 ID
+    pub user_id: Uuid,
+    /// 操作类型
+    pub action: AuditAction,
+    /// 客户端 IP 地址
+    pub ip: Option<String>,
+    /// 设备名称
+    pub device: String,
+}
+
+impl AuditEvent {
+    /// 以当前时间创建审计事件
+    pub fn new(
+        user_id: Uuid,
+        action: AuditAction,
+        device_info: &DeviceInfo,
+        ip: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now().timestamp(),
+            user_id,
+            action,
+            ip,
+            device: device_info.display_name(),
+        }
+    }
+}
+
+/// 审计日志服务
+///
+/// 采用静态方法设计，与 `TokenService`、`VerificationService` 保持一致。
+pub struct AuditService;
+
+impl AuditService {
+    /// 记录审计事件
+    ///
+    /// 事件写入用户的审计列表后发布到用户的审计频道。
+    /// 审计日志不应影响业务操作本身，写入失败时只记录警告日志。
+    ///
+    /// # 参数
+    ///
+    /// * `redis` - Redis 管理器
+    /// * `max_events` - 每个用户保留的最大事件数
+    /// * `event` - 审计事件
+    pub async fn record(redis: &RedisManager, max_events: u32, event: &AuditEvent) {
+        let cache = CacheHelper::new(RedisUtils::new(redis.clone()));
+        let key = Self::audit_key(event.user_id);
+
+        if let Err(e) = cache.add_to_list(&key, event, Some(max_events)).await {
+            tracing::warn!(
+                "记录审计事件失败 (user_id: {}, action: {:?}): {}",
+                event.user_id,
+                event.action,
+                e
+            );
+        }
+
+        // 推送给正在订阅的事件流
+        let published = match serde_json::to_string(event) {
+            Ok(payload) => RedisUtils::new(redis.clone())
+                .publish(Self::channel(event.user_id), payload)
+                .await
+                .map(|_| ()),
+            Err(e) => Err(AppError::Internal(e.into())),
+        };
+        if let Err(e) = published {
+            tracing::warn!(
+                "发布审计事件失败 (user_id: {}, action: {:?}): {}",
+                event.user_id,
+                event.action,
+                e
+            );
+        }
+    }
+
+    /// 获取用户最近的审计事件
+    ///
+    /// # 参数
+    ///
+    /// * `redis` - Redis 管理器
+    /// * `user_id` - 用户 ID
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Vec<AuditEvent>>`，按时间倒序排列（最新的在前）
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Internal`: Redis 操作或反序列化失败
+    pub async fn recent_events(redis: &RedisManager, user_id: Uuid) -> Result<Vec<AuditEvent>> {
+        let cache = CacheHelper::new(RedisUtils::new(redis.clone()));
+        cache.get_list_items(&Self::audit_key(user_id), 0, -1).await
+    }
+
+    /// 订阅用户新产生的审计事件
+    ///
+    /// 只能收到订阅之后记录的事件，历史事件请使用 `recent_events`。
+    /// 每个订阅占用一个独立的 Redis 连接，返回的事件流被丢弃时释放。
+    ///
+    /// # 参数
+    ///
+    /// * `redis` - Redis 管理器
+    /// * `user_id` - 用户 ID
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Internal`: 创建订阅连接或订阅频道失败
+    pub async fn subscribe(
+        redis: &RedisManager,
+        user_id: Uuid,
+    ) -> Result<BoxStream<'static, AuditEvent>> {
+        let messages = RedisUtils::new(redis.clone())
+            .subscribe(&Self::channel(user_id))
+            .await?;
+
+        let events = messages.filter_map(|message| async move {
+            match serde_json::from_str::<AuditEvent>(&message) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    tracing::warn!("忽略无法解析的审计事件: {}", e);
+                    None
+                }
+            }
+        });
+
+        Ok(events.boxed())
+    }
+
+    /// 用户审计日志列表的键
+    fn audit_key(user_id: Uuid) -> String {
+        format!("{}{}", cache_keys::AUDIT_PREFIX, user_id)
+    }
+
+    /// 用户审计事件的发布频道
+    fn channel(user_id: Uuid) -> String {
+        format!("{}{}", cache_keys::AUDIT_CHANNEL_PREFIX, user_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support;
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_audit_log_is_capped_and_newest_first() {
+        let redis = test_support::redis_manager().await;
+        let user_id = Uuid::new_v4();
+        let device_info = DeviceInfo::from_user_agent("test-agent", Some("web"));
+
+        for action in [
+            AuditAction::Login,
+            AuditAction::PasswordChange,
+            AuditAction::Logout,
+        ] {
+            let event = AuditEvent::new(user_id, action, &device_info, None);
+            AuditService::record(&redis, 2, &event).await;
+        }
+
+        let events = AuditService::recent_events(&redis, user_id).await.unwrap();
+        let actions: Vec<AuditAction> = events.iter().map(|event| event.action).collect();
+        assert_eq!(
+            actions,
+            vec![AuditAction::Logout, AuditAction::PasswordChange]
+        );
+        assert!(events.iter().all(|event| event.user_id == user_id));
+    }
+}
@@ -0,0 +1,203 @@
+/*!
+ * 邮件发送服务
+ *
+ * `EmailSender` 抽象了邮件发送，邮箱验证码和密码重置令牌通过它投递给用户：
+ * - `SmtpSender`: 通过 SMTP 发送邮件（需要启用 `smtp` feature 并配置 `SMTP_HOST`）
+ * - `LogSender`: 只把邮件内容写入日志，仅在开发模式（`DEVELOPMENT_MODE=true`）下使用
+ */
+
+use std::sync::Arc;
+
+use axum::async_trait;
+
+use crate::{config::Config, error::Result};
+
+/// 邮件发送器
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    /// 发送纯文本邮件
+    ///
+    /// # 参数
+    ///
+    /// * `to` - 收件人邮箱
+    /// * `subject` - 邮件主题
+    /// * `body` - 邮件正文（纯文本）
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()>;
+}
+
+/// 把邮件内容写入日志的发送器
+///
+/// 不会真正发送邮件，邮件正文（包括验证码、重置令牌）以 info 级别写入日志，
+/// 只应在开发环境中使用。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogSender;
+
+#[async_trait]
+impl EmailSender for LogSender {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()> {
+        tracing::info!(to, subject, "邮件未实际发送（LogSender）:\n{}", body);
+        Ok(())
+    }
+}
+
+/// 通过 SMTP 发送邮件的发送器
+#[cfg(feature = "smtp")]
+pub struct SmtpSender {
+    transport: lettre::AsyncSmtpTransport<lettre::Tokio1Executor>,
+    from: lettre::message::Mailbox,
+}
+
+#[cfg(feature = "smtp")]
+impl SmtpSender {
+    /// 根据 SMTP 配置创建发送器
+    ///
+    /// 使用 STARTTLS 连接 SMTP 服务器，配置了用户名和密码时进行身份验证。
+    /// 只校验配置，不会立即连接服务器。
+    pub fn new(config: &crate::config::SmtpConfig) -> anyhow::Result<Self> {
+        use lettre::transport::smtp::authentication::Credentials;
+
+        let mut builder =
+            lettre::AsyncSmtpTransport::<lettre::Tokio1Executor>::starttls_relay(&config.host)?
+                .port(config.port);
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: config
+                .from
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid SMTP_FROM {}: {}", config.from, e))?,
+        })
+    }
+}
+
+#[cfg(feature = "smtp")]
+#[async_trait]
+impl EmailSender for SmtpSender {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()> {
+        use lettre::AsyncTransport;
+
+        let message = lettre::Message::builder()
+            .from(self.from.clone())
+            .to(to
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid recipient {}: {}", to, e))?)
+            .subject(subject)
+            .header(lettre::message::header::ContentType::TEXT_PLAIN)
+            .body(body.to_string())
+            .map_err(|e| anyhow::anyhow!("Failed to build email: {}", e))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send email to {}: {}", to, e))?;
+
+        Ok(())
+    }
+}
+
+/// 根据配置创建邮件发送器
+///
+/// 配置了 `SMTP_HOST` 且启用了 `smtp` feature 时使用 `SmtpSender`；
+/// 否则只有开发模式下才会退回 `LogSender`，生产环境返回错误，
+/// 避免验证码和密码重置令牌被写入日志。
+///
+/// # 错误
+///
+/// 非开发模式下没有可用的 SMTP 配置，或 SMTP 配置无效时返回错误
+pub fn email_sender_from_config(config: &Config) -> anyhow::Result<Arc<dyn EmailSender>> {
+    let reason = match &config.smtp {
+        #[cfg(feature = "smtp")]
+        Some(smtp) => return Ok(Arc::new(SmtpSender::new(smtp)?)),
+        #[cfg(not(feature = "smtp"))]
+        Some(_) => "SMTP_HOST is set but the `smtp` feature is disabled",
+        None => "SMTP_HOST is not set",
+    };
+
+    if !config.development_mode {
+        anyhow::bail!(
+            "{}: emails cannot be delivered outside development mode \
+             (build with `--features smtp` and set SMTP_HOST, or set DEVELOPMENT_MODE=true to log emails)",
+            reason
+        );
+    }
+
+    tracing::warn!("{}, emails will only be logged (development mode)", reason);
+    Ok(Arc::new(LogSender))
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// 记录已发送邮件的发送器
+    #[derive(Default)]
+    pub struct RecordingSender {
+        /// 已发送的邮件 `(收件人, 主题, 正文)`
+        pub sent: Mutex<Vec<(String, String, String)>>,
+    }
+
+    #[async_trait]
+    impl EmailSender for RecordingSender {
+        async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((to.to_string(), subject.to_string(), body.to_string()));
+            Ok(())
+        }
+    }
+
+    /// 总是发送失败的发送器
+    pub struct FailingSender;
+
+    #[async_trait]
+    impl EmailSender for FailingSender {
+        async fn send(&self, to: &str, _subject: &str, _body: &str) -> Result<()> {
+            Err(anyhow::anyhow!("Failed to send email to {}", to).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::test_support::BufferWriter;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[tokio::test]
+    async fn test_log_sender_writes_body_to_log() {
+        let writer = BufferWriter::default();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(writer.clone())
+                .with_ansi(false),
+        );
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        LogSender
+            .send("user@example.com", "邮箱验证码", "您的验证码是 482913")
+            .await
+            .unwrap();
+
+        let output = writer.contents();
+        assert!(output.contains("user@example.com"));
+        assert!(output.contains("482913"));
+    }
+
+    #[test]
+    fn test_log_sender_only_in_development_mode() {
+        let mut config = Config::from_env().unwrap();
+        config.smtp = None;
+
+        config.development_mode = true;
+        assert!(email_sender_from_config(&config).is_ok());
+
+        // 生产环境没有 SMTP 配置时拒绝启动，而不是把验证码写入日志
+        config.development_mode = false;
+        assert!(email_sender_from_config(&config).is_err());
+    }
+}
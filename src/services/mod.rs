@@ -16,6 +16,12 @@
  *
  * - `user_service`: 用户管理相关的业务逻辑
  * - `token_service`: Token 管理服务，处理 JWT token 的 Redis 持久化
+ * - `verification_service`: 邮箱验证服务，生成和校验邮箱验证码
+ * - `password_reset_service`: 密码重置服务，生成和校验一次性重置令牌
+ * - `audit_service`: 审计日志服务，记录登录、修改密码等安全相关操作
+ * - `token_cleanup`: 定时清理 Redis 中过期 token 的后台任务
+ * - `email_service`: 邮件发送抽象，投递验证码和密码重置令牌
+ * - `webhook_service`: 在注册、撤销会话等事件发生时向外部系统发送签名通知
  */
 
 /// 用户业务逻辑服务
@@ -24,6 +30,30 @@ pub mod user_service;
 /// Token 管理服务
 pub mod token_service;
 
+/// 邮箱验证服务
+pub mod verification_service;
+
+/// 密码重置服务
+pub mod password_reset_service;
+
+/// 审计日志服务
+pub mod audit_service;
+
+/// Token 定时清理任务
+pub mod token_cleanup;
+
+/// 邮件发送服务
+pub mod email_service;
+
+/// Webhook 通知服务
+pub mod webhook_service;
+
 // 重新导出所有服务，方便外部使用
+pub use audit_service::*;
+pub use email_service::*;
+pub use password_reset_service::*;
+pub use token_cleanup::*;
 pub use token_service::*;
 pub use user_service::*;
+pub use verification_service::*;
+pub use webhook_service::*;
@@ -0,0 +1,356 @@
+/*!
+ * 密码重置服务
+ *
+ * 为忘记密码的用户生成一次性重置令牌，并在校验令牌后设置新密码。
+ * 重置令牌与邮箱验证码一样存储在 Redis 中，过期后自动失效，使用一次后立即删除。
+ */
+
+use crate::{
+    db::DbPool,
+    error::{AppError, Result},
+    models::User,
+    redis::{RedisManager, RedisUtils},
+    services::{
+        user_service::{check_password_not_pwned, validate_new_password},
+        EmailSender, PasswordPolicy, TokenService, UserService,
+    },
+    utils::{CacheHelper, CryptoUtils, StringUtils},
+};
+
+/// 密码重置服务
+///
+/// 采用静态方法设计，与 `VerificationService` 保持一致。
+pub struct PasswordResetService;
+
+impl PasswordResetService {
+    /// 重置令牌有效期（30 分钟）
+    pub const TOKEN_EXPIRY_SECONDS: u64 = 30 * 60;
+
+    /// 重置令牌的随机字节数（十六进制编码后长度翻倍）
+    pub const TOKEN_BYTES: usize = 32;
+
+    /// 同一邮箱在限流窗口内允许申请重置的次数
+    pub const REQUEST_LIMIT: i64 = 3;
+
+    /// 申请重置的限流窗口（1 小时）
+    pub const REQUEST_WINDOW_SECONDS: u64 = 60 * 60;
+
+    /// 生成并存储密码重置令牌
+    ///
+    /// 令牌通过 `sender` 发送到用户邮箱，重复调用会覆盖之前的令牌。
+    /// 同一邮箱每小时最多申请 `REQUEST_LIMIT` 次，未注册的邮箱同样计数，避免通过限流暴露邮箱是否已注册。
+    /// 邮件发送失败只记录警告日志，否则调用方可以根据是否出错判断邮箱是否已注册。
+    ///
+    /// # 参数
+    ///
+    /// * `pool` - 数据库连接池
+    /// * `redis` - Redis 管理器
+    /// * `sender` - 邮件发送器
+    /// * `email` - 用户邮箱
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Option<String>>`：邮箱对应的用户存在时返回生成的令牌，
+    /// 否则返回 `None`（调用方不应向客户端暴露用户是否存在）
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Database`: 数据库操作失败
+    /// - `AppError::RateLimited`: 该邮箱申请过于频繁
+    /// - `AppError::Internal`: Redis 操作失败
+    pub async fn request_reset(
+        pool: &DbPool,
+        redis: &RedisManager,
+        sender: &dyn EmailSender,
+        email: &str,
+    ) -> Result<Option<String>> {
+        CacheHelper::new(RedisUtils::new(redis.clone()))
+            .enforce_rate_limit(
+                &Self::identifier(email),
+                Self::REQUEST_LIMIT,
+                Self::REQUEST_WINDOW_SECONDS,
+            )
+            .await?;
+
+        Self::issue_token(pool, redis, sender, email, Self::TOKEN_EXPIRY_SECONDS).await
+    }
+
+    /// 校验重置令牌并设置新密码
+    ///
+    /// 令牌只能使用一次。密码重置成功后撤销该用户的所有登录会话。
+    ///
+    /// # 参数
+    ///
+    /// * `pool` - 数据库连接池
+    /// * `redis` - Redis 管理器
+    /// * `email` - 用户邮箱
+    /// * `token` - 重置令牌
+    /// * `new_password` - 新密码
+    /// * `policy` - 密码策略（哈希参数和泄露检查）
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Validation`: 令牌错误或已过期，或新密码强度不足、出现在已知的数据泄露中
+    /// - `AppError::NotFound`: 用户不存在
+    /// - `AppError::Database`: 数据库操作失败
+    /// - `AppError::Internal`: Redis 操作失败
+    pub async fn reset_password(
+        pool: &DbPool,
+        redis: &RedisManager,
+        email: &str,
+        token: &str,
+        new_password: &str,
+        policy: PasswordPolicy,
+    ) -> Result<()> {
+        let user = find_user_by_email(pool, email)
+            .await?
+            .ok_or_else(|| AppError::Validation("Invalid or expired reset token".to_string()))?;
+
+        // 先检查新密码，避免因密码不符合要求而白白消耗令牌
+        validate_new_password(new_password, &policy)?;
+        check_password_not_pwned(new_password, &policy).await?;
+
+        let cache = CacheHelper::new(RedisUtils::new(redis.clone()));
+        if !cache
+            .verify_and_consume_code(&Self::identifier(email), token)
+            .await?
+        {
+            return Err(AppError::Validation(
+                "Invalid or expired reset token".to_string(),
+            ));
+        }
+
+        UserService::store_password(pool, user.id, new_password, &policy.argon2).await?;
+
+        // 密码重置后撤销所有已签发的令牌
+        TokenService::revoke_all_user_tokens(redis, user.id).await?;
+
+        Ok(())
+    }
+
+    /// 生成、存储并发送指定有效期的重置令牌
+    async fn issue_token(
+        pool: &DbPool,
+        redis: &RedisManager,
+        sender: &dyn EmailSender,
+        email: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<String>> {
+        if find_user_by_email(pool, email).await?.is_none() {
+            return Ok(None);
+        }
+
+        let token = CryptoUtils::random_hex(Self::TOKEN_BYTES);
+        let cache = CacheHelper::new(RedisUtils::new(redis.clone()));
+        cache
+            .set_verification_code(&Self::identifier(email), &token, ttl_seconds)
+            .await?;
+
+        if let Err(e) = sender
+            .send(
+                email,
+                "密码重置",
+                &format!(
+                    "您的密码重置令牌是 {}，{} 分钟内有效且只能使用一次。如果不是您本人操作，请忽略此邮件。",
+                    token,
+                    ttl_seconds / 60
+                ),
+            )
+            .await
+        {
+            tracing::warn!("发送密码重置邮件失败: {}", e);
+        }
+
+        Ok(Some(token))
+    }
+
+    /// 重置令牌在缓存中的标识符（邮箱不区分大小写）
+    fn identifier(email: &str) -> String {
+        format!("password_reset:{}", StringUtils::normalize_email(email))
+    }
+}
+
+/// 根据邮箱查询用户
+async fn find_user_by_email(pool: &DbPool, email: &str) -> Result<Option<User>> {
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE LOWER(email) = $1")
+        .bind(StringUtils::normalize_email(email))
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_support as db_support,
+        models::CreateUserRequest,
+        redis::test_support as redis_support,
+        services::{
+            email_service::test_support::{FailingSender, RecordingSender},
+            LogSender,
+        },
+        utils::verify_password,
+    };
+
+    const OLD_PASSWORD: &str = "Old-Secure-Passw0rd";
+    const NEW_PASSWORD: &str = "N3w-Secure-Passw0rd";
+
+    async fn create_test_user(pool: &DbPool) -> User {
+        UserService::create_user(
+            pool,
+            CreateUserRequest {
+                email: db_support::unique_email("reset"),
+                password: OLD_PASSWORD.to_string(),
+                name: "reset".to_string(),
+            },
+            PasswordPolicy::default(),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn password_matches(pool: &DbPool, user: &User, password: &str) -> bool {
+        let user = UserService::get_user_by_id(pool, user.id).await.unwrap();
+        verify_password(password, &user.password_hash).unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running PostgreSQL and Redis instances"]
+    async fn test_request_reset_for_unknown_email() {
+        let pool = db_support::pool().await;
+        let redis = redis_support::redis_manager().await;
+        let sender = RecordingSender::default();
+
+        let token = PasswordResetService::request_reset(
+            &pool,
+            &redis,
+            &sender,
+            &db_support::unique_email("nobody"),
+        )
+        .await
+        .unwrap();
+        assert!(token.is_none());
+        // 邮箱未注册时不发送邮件
+        assert!(sender.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running PostgreSQL and Redis instances"]
+    async fn test_request_reset_is_rate_limited_per_email() {
+        let pool = db_support::pool().await;
+        let redis = redis_support::redis_manager().await;
+        let email = db_support::unique_email("throttled");
+
+        for _ in 0..PasswordResetService::REQUEST_LIMIT {
+            PasswordResetService::request_reset(&pool, &redis, &LogSender, &email)
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            PasswordResetService::request_reset(&pool, &redis, &LogSender, &email).await,
+            Err(AppError::RateLimited { .. })
+        ));
+
+        // 其他邮箱不受影响
+        assert!(PasswordResetService::request_reset(
+            &pool,
+            &redis,
+            &LogSender,
+            &db_support::unique_email("throttled")
+        )
+        .await
+        .is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running PostgreSQL and Redis instances"]
+    async fn test_request_reset_ignores_send_failure() {
+        let pool = db_support::pool().await;
+        let redis = redis_support::redis_manager().await;
+        let user = create_test_user(&pool).await;
+
+        // 发送失败时与未注册的邮箱一样返回成功，不暴露邮箱是否已注册
+        let token = PasswordResetService::request_reset(&pool, &redis, &FailingSender, &user.email)
+            .await
+            .unwrap();
+        assert!(token.is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running PostgreSQL and Redis instances"]
+    async fn test_reset_password_and_reject_reused_token() {
+        let pool = db_support::pool().await;
+        let redis = redis_support::redis_manager().await;
+        let user = create_test_user(&pool).await;
+
+        let sender = RecordingSender::default();
+
+        let token = PasswordResetService::request_reset(&pool, &redis, &sender, &user.email)
+            .await
+            .unwrap()
+            .unwrap();
+        {
+            // 令牌通过邮件发送给用户
+            let sent = sender.sent.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            assert_eq!(sent[0].0, user.email);
+            assert!(sent[0].2.contains(&token));
+        }
+        PasswordResetService::reset_password(
+            &pool,
+            &redis,
+            &user.email,
+            &token,
+            NEW_PASSWORD,
+            PasswordPolicy::default(),
+        )
+        .await
+        .unwrap();
+        assert!(password_matches(&pool, &user, NEW_PASSWORD).await);
+
+        // 令牌只能使用一次
+        assert!(matches!(
+            PasswordResetService::reset_password(
+                &pool,
+                &redis,
+                &user.email,
+                &token,
+                "An0ther-Secure-Passw0rd",
+                PasswordPolicy::default(),
+            )
+            .await,
+            Err(AppError::Validation(_))
+        ));
+        assert!(password_matches(&pool, &user, NEW_PASSWORD).await);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running PostgreSQL and Redis instances"]
+    async fn test_reset_password_rejects_expired_token() {
+        let pool = db_support::pool().await;
+        let redis = redis_support::redis_manager().await;
+        let user = create_test_user(&pool).await;
+
+        let token = PasswordResetService::issue_token(&pool, &redis, &LogSender, &user.email, 1)
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+        assert!(matches!(
+            PasswordResetService::reset_password(
+                &pool,
+                &redis,
+                &user.email,
+                &token,
+                NEW_PASSWORD,
+                PasswordPolicy::default(),
+            )
+            .await,
+            Err(AppError::Validation(_))
+        ));
+        assert!(password_matches(&pool, &user, OLD_PASSWORD).await);
+    }
+}
@@ -0,0 +1,135 @@
+/*!
+ * Token 定时清理任务
+ *
+ * 在后台定期调用 `TokenService::cleanup_expired_tokens`，删除 Redis 中残留的过期 token。
+ * 多个实例同时运行时通过分布式锁保证同一时间只有一个实例执行清理。
+ */
+
+use std::{future::Future, time::Duration};
+
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{
+    error::Result,
+    redis::{RedisManager, RedisUtils},
+    services::TokenService,
+};
+
+/// 清理任务使用的分布式锁键名
+pub const TOKEN_CLEANUP_LOCK_KEY: &str = "lock:token_cleanup";
+
+/// 清理任务锁的过期时间（秒），防止持有锁的实例崩溃后其他实例无法清理
+const TOKEN_CLEANUP_LOCK_TTL_SECONDS: u64 = 300;
+
+/// 启动 token 定时清理任务
+///
+/// 启动后立即执行一次清理，之后每隔 `interval` 执行一次。
+/// 返回的 `JoinHandle` 可用于在停机时调用 `abort` 结束任务。
+///
+/// # 参数
+///
+/// * `redis` - Redis 管理器
+/// * `interval` - 清理间隔
+pub fn spawn_token_cleanup(redis: RedisManager, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(run_periodically(interval, move || {
+        let redis = redis.clone();
+        async move {
+            match run_token_cleanup(&redis).await {
+                Ok(Some(cleaned)) => {
+                    tracing::info!("Token cleanup removed {} expired tokens", cleaned)
+                }
+                Ok(None) => {
+                    tracing::debug!("Token cleanup skipped, another instance holds the lock")
+                }
+                Err(e) => tracing::warn!("Token cleanup failed: {}", e),
+            }
+        }
+    }))
+}
+
+/// 在分布式锁内执行一次过期 token 清理
+///
+/// # 返回值
+///
+/// 返回清理的 token 数量；其他实例正在清理时返回 `None`
+pub async fn run_token_cleanup(redis: &RedisManager) -> Result<Option<u32>> {
+    RedisUtils::new(redis.clone())
+        .try_with_lock(
+            TOKEN_CLEANUP_LOCK_KEY,
+            TOKEN_CLEANUP_LOCK_TTL_SECONDS,
+            || TokenService::cleanup_expired_tokens(redis),
+        )
+        .await?
+        .transpose()
+}
+
+/// 每隔 `interval` 执行一次 `job`，第一次立即执行
+///
+/// 单次执行耗时超过间隔时，下一次从上次结束后重新计时，不会连续补跑。
+async fn run_periodically<F, Fut>(interval: Duration, mut job: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        job().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tokio::sync::Notify;
+
+    #[tokio::test]
+    async fn test_run_periodically_invokes_job() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let ran = Arc::new(Notify::new());
+
+        let task = tokio::spawn(run_periodically(Duration::from_millis(1), {
+            let runs = runs.clone();
+            let ran = ran.clone();
+            move || {
+                let runs = runs.clone();
+                let ran = ran.clone();
+                async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    ran.notify_one();
+                }
+            }
+        }));
+
+        tokio::time::timeout(Duration::from_secs(1), ran.notified())
+            .await
+            .expect("job should run on the first tick");
+        task.abort();
+
+        assert!(runs.load(Ordering::SeqCst) >= 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_run_token_cleanup_uses_lock() {
+        let redis = test_support::redis_manager().await;
+
+        // 其他实例持有锁时跳过本次清理
+        let guard = RedisUtils::new(redis.clone())
+            .acquire_lock(TOKEN_CLEANUP_LOCK_KEY, 30)
+            .await
+            .unwrap()
+            .expect("lock should be free");
+        assert_eq!(run_token_cleanup(&redis).await.unwrap(), None);
+        guard.release().await.unwrap();
+
+        assert!(run_token_cleanup(&redis).await.unwrap().is_some());
+    }
+}
@@ -10,9 +10,13 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
+    config::Config,
     error::{AppError, Result},
-    redis::RedisManager,
-    utils::{generate_jwt, verify_jwt, Claims, DeviceInfo, DeviceType},
+    redis::{RedisBackend, RedisManager},
+    utils::{
+        decode_jwt, generate_jwt_with_expiry, AuthUser, Claims, CryptoUtils, DeviceInfo,
+        DeviceType, JwtKeys,
+    },
 };
 
 /// Token 信息结构体
@@ -30,33 +34,140 @@ pub struct TokenInfo {
     pub device_info: DeviceInfo,
     /// IP 地址（可选）
     pub ip_address: Option<String>,
+    /// Token 指纹（SHA-256 十六进制摘要，参见 `TokenService::token_fingerprint`）
+    ///
+    /// 用于识别当前会话，Redis 中也以指纹代替原始 token 作为键名
+    #[serde(default, alias = "token_hash")]
+    pub fingerprint: Option<String>,
+    /// 最后使用时间（验证 token 时更新，最多每分钟更新一次；旧数据为 0）
+    #[serde(default)]
+    pub last_used_at: i64,
+    /// 与访问令牌一起签发的刷新令牌指纹（撤销访问令牌时一并撤销；单独签发的 token 为 None）
+    #[serde(default)]
+    pub refresh_fingerprint: Option<String>,
+}
+
+impl TokenInfo {
+    /// 判断该会话是否属于指定的 token
+    ///
+    /// 通过比较 token 指纹实现，旧数据没有指纹时返回 false。
+    pub fn matches_token(&self, token: &str) -> bool {
+        self.fingerprint.as_deref() == Some(TokenService::token_fingerprint(token).as_str())
+    }
+
+    /// 判断是否需要更新最后使用时间
+    ///
+    /// 距离上次更新不足 `TokenService::LAST_USED_UPDATE_INTERVAL_SECONDS` 时不更新，
+    /// 避免每次请求都写一次 Redis。
+    pub fn needs_last_used_update(&self, now: i64) -> bool {
+        now - self.last_used_at >= TokenService::LAST_USED_UPDATE_INTERVAL_SECONDS
+    }
+}
+
+/// Token 对
+///
+/// 包含短期访问令牌和长期刷新令牌
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TokenPair {
+    /// JWT 访问令牌（短期有效）
+    pub access_token: String,
+    /// 不透明的刷新令牌（长期有效，使用一次后轮换）
+    pub refresh_token: String,
+    /// 访问令牌的有效期（秒）
+    pub expires_in: u64,
+}
+
+/// 会话策略
+///
+/// 控制签发新 token 时如何处理用户已有的登录会话
+#[derive(Debug, Clone, Copy)]
+pub struct SessionPolicy {
+    /// 是否每种设备类型只保留一个会话（新登录会撤销同类设备的旧会话）
+    pub single_session_per_device: bool,
+    /// 每个用户的最大活跃会话数（None 表示不限制）
+    pub max_sessions_per_user: Option<u32>,
+}
+
+impl Default for SessionPolicy {
+    fn default() -> Self {
+        Self {
+            single_session_per_device: true,
+            max_sessions_per_user: None,
+        }
+    }
+}
+
+impl From<&Config> for SessionPolicy {
+    fn from(config: &Config) -> Self {
+        Self {
+            single_session_per_device: config.single_session_per_device,
+            max_sessions_per_user: config.max_sessions_per_user,
+        }
+    }
 }
 
 /// Token 管理服务
 pub struct TokenService;
 
 impl TokenService {
-    /// Token 在 Redis 中的键前缀
-    const TOKEN_PREFIX: &'static str = "auth:token:";
+    /// Token 信息在 Redis 中的键前缀，键名使用 token 指纹，不保存原始 token
+    const TOKEN_PREFIX: &'static str = "auth:token_fp:";
+
+    /// 早期版本以原始 token 作为键名的前缀（`auth:token:{token}`）
+    ///
+    /// 升级后的一个版本内仍然读取这些键，避免已登录的用户全部失效；
+    /// 撤销时一并删除，新 token 不再写入，旧键会在过期后自然清除。
+    const LEGACY_TOKEN_PREFIX: &'static str = "auth:token:";
 
-    /// 用户 token 集合的键前缀（用于快速查找用户的所有 token）
+    /// 用户 token 指纹集合的键前缀（用于快速查找用户的所有 token）
     const USER_TOKENS_PREFIX: &'static str = "auth:user_tokens:";
 
-    /// 用户设备 token 的键前缀（用于单设备类型登录控制）
+    /// 用户设备 token 指纹的键前缀（用于单设备类型登录控制）
     const USER_DEVICE_TOKEN_PREFIX: &'static str = "auth:user_device:";
 
+    /// 刷新令牌信息在 Redis 中的键前缀，键名使用刷新令牌的指纹
+    const REFRESH_PREFIX: &'static str = "auth:refresh_fp:";
+
+    /// 早期版本以原始刷新令牌作为键名的前缀（`auth:refresh:{refresh_token}`），
+    /// 处理方式与 `LEGACY_TOKEN_PREFIX` 相同
+    const LEGACY_REFRESH_PREFIX: &'static str = "auth:refresh:";
+
+    /// 用户刷新令牌指纹集合的键前缀（用于撤销用户的所有刷新令牌）
+    const USER_REFRESH_PREFIX: &'static str = "auth:user_refresh:";
+
     /// Token 的默认过期时间（24小时，与JWT保持一致）
     const TOKEN_EXPIRY_SECONDS: u64 = 24 * 60 * 60;
 
+    /// 与刷新令牌配对的访问令牌过期时间（15分钟）
+    const ACCESS_TOKEN_EXPIRY_SECONDS: u64 = 15 * 60;
+
+    /// 刷新令牌的过期时间（30天）
+    const REFRESH_TOKEN_EXPIRY_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+    /// 更新 token 最后使用时间的最小间隔（1分钟）
+    pub const LAST_USED_UPDATE_INTERVAL_SECONDS: i64 = 60;
+
+    /// 清理过期 token 时每批 SCAN 的键数量
+    const SCAN_BATCH_SIZE: usize = 100;
+
+    /// 计算 token 指纹
+    ///
+    /// 返回 token 的 SHA-256 十六进制摘要。Redis 中只保存指纹，
+    /// 即使 Redis 数据泄露也无法直接得到可用的 token。
+    pub fn token_fingerprint(token: &str) -> String {
+        CryptoUtils::sha256_hex(token.as_bytes())
+    }
+
     /// 生成并存储 token（支持单设备类型登录）
     ///
     /// # 参数
     ///
     /// * `redis` - Redis 管理器
-    /// * `user_id` - 用户 ID
-    /// * `jwt_secret` - JWT 密钥
+    /// * `user` - 用户身份（ID、邮箱和角色）
+    /// * `jwt_keys` - JWT 签名和验证密钥
     /// * `device_info` - 设备信息
     /// * `ip_address` - IP 地址（可选）
+    /// * `policy` - 会话策略
     ///
     /// # 返回值
     ///
@@ -64,23 +175,245 @@ impl TokenService {
     ///
     /// # 注意
     ///
-    /// 此方法会自动撤销用户在同类设备上的其他登录会话
+    /// 启用 `single_session_per_device` 时，此方法会自动撤销用户在同类设备上的其他登录会话
     pub async fn create_token(
         redis: &RedisManager,
+        user: &AuthUser,
+        jwt_keys: &JwtKeys,
+        device_info: DeviceInfo,
+        ip_address: Option<String>,
+        policy: SessionPolicy,
+    ) -> Result<String> {
+        Self::issue_access_token(
+            redis,
+            user,
+            jwt_keys,
+            device_info,
+            ip_address,
+            Self::TOKEN_EXPIRY_SECONDS,
+            None,
+            policy,
+        )
+        .await
+    }
+
+    /// 生成访问令牌和刷新令牌对
+    ///
+    /// 访问令牌为短期有效的 JWT，刷新令牌为存储在 Redis 中的不透明随机字符串。
+    /// 访问令牌过期后，客户端可以使用刷新令牌换取新的令牌对。
+    ///
+    /// # 参数
+    ///
+    /// * `redis` - Redis 管理器
+    /// * `user` - 用户身份（ID、邮箱和角色）
+    /// * `jwt_keys` - JWT 签名和验证密钥
+    /// * `device_info` - 设备信息
+    /// * `ip_address` - IP 地址（可选）
+    /// * `policy` - 会话策略
+    ///
+    /// # 返回值
+    ///
+    /// 返回包含访问令牌和刷新令牌的 `TokenPair`
+    ///
+    /// # 注意
+    ///
+    /// 与 `create_token` 相同，会按照会话策略处理用户在同类设备上的其他登录会话。
+    /// 访问令牌记录了配对的刷新令牌，撤销该会话时刷新令牌会一并失效。
+    pub async fn create_token_pair(
+        redis: &RedisManager,
+        user: &AuthUser,
+        jwt_keys: &JwtKeys,
+        device_info: DeviceInfo,
+        ip_address: Option<String>,
+        policy: SessionPolicy,
+    ) -> Result<TokenPair> {
+        let refresh_token = CryptoUtils::base64_url_encode(&CryptoUtils::random_bytes(32));
+        let refresh_fingerprint = Self::token_fingerprint(&refresh_token);
+
+        let access_token = Self::issue_access_token(
+            redis,
+            user,
+            jwt_keys,
+            device_info.clone(),
+            ip_address.clone(),
+            Self::ACCESS_TOKEN_EXPIRY_SECONDS,
+            Some(refresh_fingerprint.clone()),
+            policy,
+        )
+        .await?;
+
+        Self::store_refresh_token(
+            redis,
+            user.id,
+            &refresh_fingerprint,
+            device_info,
+            ip_address,
+        )
+        .await?;
+
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            expires_in: Self::ACCESS_TOKEN_EXPIRY_SECONDS,
+        })
+    }
+
+    /// 消费刷新令牌
+    ///
+    /// 校验刷新令牌后立即将其作废（轮换），返回令牌中记录的用户和设备信息。
+    /// 每个刷新令牌只能使用一次，重复使用会返回身份验证错误。
+    /// 调用方应使用返回的信息重新加载用户，并通过 `create_token_pair` 签发新的令牌对，
+    /// 以便新的访问令牌携带用户最新的邮箱和角色。
+    ///
+    /// # 参数
+    ///
+    /// * `redis` - Redis 管理器
+    /// * `refresh_token` - 刷新令牌
+    ///
+    /// # 返回值
+    ///
+    /// 返回刷新令牌对应的 `TokenInfo`
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Authentication`: 刷新令牌无效、已过期或已被使用
+    pub async fn consume_refresh_token(
+        redis: &RedisManager,
+        refresh_token: &str,
+    ) -> Result<TokenInfo> {
+        let fingerprint = Self::token_fingerprint(refresh_token);
+
+        let backend = redis.backend();
+
+        // 使用 GETDEL 原子地读取并作废旧的刷新令牌，防止并发重复使用；
+        // 找不到时尝试早期版本以原始刷新令牌为键名的记录（集合中保存的也是原始刷新令牌）
+        let mut member = fingerprint.as_str();
+        let mut token_info_str = backend
+            .get_del(&format!("{}{}", Self::REFRESH_PREFIX, fingerprint))
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取刷新令牌失败: {}", e)))?;
+        if token_info_str.is_none() {
+            member = refresh_token;
+            token_info_str = backend
+                .get_del(&format!("{}{}", Self::LEGACY_REFRESH_PREFIX, refresh_token))
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取刷新令牌失败: {}", e)))?;
+        }
+
+        let token_info: TokenInfo = token_info_str
+            .and_then(|info_str| serde_json::from_str(&info_str).ok())
+            .ok_or_else(|| AppError::Authentication("刷新令牌无效或已过期".to_string()))?;
+
+        // 从用户刷新令牌集合中移除旧令牌
+        let user_refresh_key = format!("{}{}", Self::USER_REFRESH_PREFIX, token_info.user_id);
+        backend
+            .srem(&user_refresh_key, member)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis移除刷新令牌失败: {}", e)))?;
+
+        if token_info.expires_at < Utc::now().timestamp() {
+            return Err(AppError::Authentication("刷新令牌无效或已过期".to_string()));
+        }
+
+        Ok(token_info)
+    }
+
+    /// 撤销单个刷新令牌
+    ///
+    /// # 参数
+    ///
+    /// * `redis` - Redis 管理器
+    /// * `refresh_token` - 要撤销的刷新令牌
+    /// * `user_id` - 用户 ID（用于从用户刷新令牌集合中移除）
+    pub async fn revoke_refresh_token(
+        redis: &RedisManager,
+        refresh_token: &str,
+        user_id: Uuid,
+    ) -> Result<()> {
+        Self::revoke_refresh_fingerprint(redis, &Self::token_fingerprint(refresh_token), user_id)
+            .await?;
+
+        // 早期版本以原始刷新令牌作为键名和集合成员
+        Self::revoke_refresh_fingerprint(redis, refresh_token, user_id).await
+    }
+
+    /// 按指纹撤销单个刷新令牌
+    ///
+    /// `fingerprint` 为用户刷新令牌集合中的成员，早期版本的成员是原始刷新令牌，
+    /// 因此同时删除新旧两种键名。
+    async fn revoke_refresh_fingerprint(
+        redis: &RedisManager,
+        fingerprint: &str,
         user_id: Uuid,
-        jwt_secret: &str,
+    ) -> Result<()> {
+        let user_refresh_key = format!("{}{}", Self::USER_REFRESH_PREFIX, user_id);
+
+        let backend = redis.backend();
+
+        for prefix in [Self::REFRESH_PREFIX, Self::LEGACY_REFRESH_PREFIX] {
+            backend
+                .del(&format!("{}{}", prefix, fingerprint))
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis删除刷新令牌失败: {}", e)))?;
+        }
+
+        backend
+            .srem(&user_refresh_key, fingerprint)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis移除刷新令牌失败: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 生成并存储访问令牌
+    ///
+    /// # 参数
+    ///
+    /// * `redis` - Redis 管理器
+    /// * `user` - 用户身份（ID、邮箱和角色）
+    /// * `jwt_keys` - JWT 签名和验证密钥
+    /// * `device_info` - 设备信息
+    /// * `ip_address` - IP 地址（可选）
+    /// * `expiry_seconds` - 访问令牌有效期（秒）
+    /// * `refresh_fingerprint` - 配对的刷新令牌指纹（可选）
+    /// * `policy` - 会话策略
+    #[allow(clippy::too_many_arguments)]
+    async fn issue_access_token(
+        redis: &RedisManager,
+        user: &AuthUser,
+        jwt_keys: &JwtKeys,
         device_info: DeviceInfo,
         ip_address: Option<String>,
+        expiry_seconds: u64,
+        refresh_fingerprint: Option<String>,
+        policy: SessionPolicy,
     ) -> Result<String> {
-        // 先撤销用户在同类设备上的现有登录
-        Self::revoke_device_tokens(redis, user_id, &device_info.device_type).await?;
+        let user_id = user.id;
+
+        // 单设备类型单会话：先撤销用户在同类设备上的现有登录
+        if policy.single_session_per_device {
+            Self::revoke_device_tokens(redis, user_id, &device_info.device_type).await?;
+        }
+
+        // 会话数量达到上限时，淘汰最早创建的会话
+        if let Some(max_sessions) = policy.max_sessions_per_user {
+            let evicted = Self::evict_oldest_sessions(redis, user_id, max_sessions).await?;
+            if evicted > 0 {
+                tracing::info!(
+                    "用户 {} 会话数达到上限，已淘汰 {} 个最早的会话",
+                    user_id,
+                    evicted
+                );
+            }
+        }
 
         // 生成 JWT token
-        let token = generate_jwt(user_id, jwt_secret)?;
+        let token = generate_jwt_with_expiry(user, jwt_keys, expiry_seconds as i64)?;
+        let fingerprint = Self::token_fingerprint(&token);
 
         // 创建 token 信息
         let now = Utc::now();
-        let expires_at = now + Duration::hours(24);
+        let expires_at = now + Duration::seconds(expiry_seconds as i64);
 
         let token_info = TokenInfo {
             user_id,
@@ -88,10 +421,13 @@ impl TokenService {
             expires_at: expires_at.timestamp(),
             device_info: device_info.clone(),
             ip_address,
+            fingerprint: Some(fingerprint.clone()),
+            last_used_at: now.timestamp(),
+            refresh_fingerprint,
         };
 
-        // 在 Redis 中存储 token 信息
-        let token_key = format!("{}{}", Self::TOKEN_PREFIX, token);
+        // 在 Redis 中存储 token 信息（以指纹作为键名）
+        let token_key = format!("{}{}", Self::TOKEN_PREFIX, fingerprint);
         let user_tokens_key = format!("{}{}", Self::USER_TOKENS_PREFIX, user_id);
         let user_device_key = format!(
             "{}{}{}",
@@ -100,36 +436,34 @@ impl TokenService {
             device_info.device_type
         );
 
-        // 使用 Redis pipeline 提高性能
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
+        let backend = redis.backend();
 
         // 存储 token 信息，设置过期时间
-        let _: () = conn
-            .set_ex(
+        backend
+            .set(
                 &token_key,
-                serde_json::to_string(&token_info)
+                &serde_json::to_string(&token_info)
                     .map_err(|e| AppError::Internal(anyhow::anyhow!("JSON序列化失败: {}", e)))?,
-                Self::TOKEN_EXPIRY_SECONDS,
+                Some(expiry_seconds),
             )
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis存储token失败: {}", e)))?;
 
-        // 将 token 添加到用户的 token 集合中
-        let _: () = conn
-            .sadd(&user_tokens_key, &token)
+        // 将 token 指纹添加到用户的 token 集合中
+        backend
+            .sadd(&user_tokens_key, &fingerprint)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis添加用户token失败: {}", e)))?;
 
-        // 存储设备类型对应的 token（用于单设备登录控制）
-        let _: () = conn
-            .set_ex(&user_device_key, &token, Self::TOKEN_EXPIRY_SECONDS)
+        // 存储设备类型对应的 token 指纹（用于单设备登录控制）
+        backend
+            .set(&user_device_key, &fingerprint, Some(expiry_seconds))
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis存储设备token失败: {}", e)))?;
 
         // 为用户 token 集合设置过期时间（比 token 稍长一些）
-        let _: () = conn
-            .expire(&user_tokens_key, (Self::TOKEN_EXPIRY_SECONDS + 3600) as i64)
+        backend
+            .expire(&user_tokens_key, Self::TOKEN_EXPIRY_SECONDS + 3600)
             .await
             .map_err(|e| {
                 AppError::Internal(anyhow::anyhow!("Redis设置用户token过期时间失败: {}", e))
@@ -138,13 +472,104 @@ impl TokenService {
         Ok(token)
     }
 
+    /// 淘汰用户最早创建的会话，为新会话腾出位置
+    ///
+    /// # 参数
+    ///
+    /// * `redis` - Redis 管理器
+    /// * `user_id` - 用户 ID
+    /// * `max_sessions` - 允许的最大会话数（包含即将创建的新会话）
+    ///
+    /// # 返回值
+    ///
+    /// 返回被淘汰的会话数量
+    async fn evict_oldest_sessions(
+        redis: &RedisManager,
+        user_id: Uuid,
+        max_sessions: u32,
+    ) -> Result<u32> {
+        let mut sessions = Self::get_user_token_infos(redis, user_id).await?;
+
+        // 为即将创建的新会话预留一个位置
+        let keep = (max_sessions as usize).saturating_sub(1);
+        if sessions.len() <= keep {
+            return Ok(0);
+        }
+
+        sessions.sort_by_key(|(_, token_info)| token_info.created_at);
+
+        let evict_count = sessions.len() - keep;
+        for (fingerprint, _) in sessions.into_iter().take(evict_count) {
+            Self::revoke_fingerprint(redis, &fingerprint, user_id).await?;
+        }
+
+        Ok(evict_count as u32)
+    }
+
+    /// 存储刷新令牌
+    ///
+    /// 刷新令牌是 32 字节的随机字符串（URL 安全的 Base64 编码），
+    /// 其关联的 `TokenInfo` 以刷新令牌的指纹为键存储在 Redis 中。
+    async fn store_refresh_token(
+        redis: &RedisManager,
+        user_id: Uuid,
+        fingerprint: &str,
+        device_info: DeviceInfo,
+        ip_address: Option<String>,
+    ) -> Result<()> {
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(Self::REFRESH_TOKEN_EXPIRY_SECONDS as i64);
+
+        let token_info = TokenInfo {
+            user_id,
+            created_at: now.timestamp(),
+            expires_at: expires_at.timestamp(),
+            device_info,
+            ip_address,
+            fingerprint: Some(fingerprint.to_string()),
+            last_used_at: now.timestamp(),
+            refresh_fingerprint: None,
+        };
+
+        let refresh_key = format!("{}{}", Self::REFRESH_PREFIX, fingerprint);
+        let user_refresh_key = format!("{}{}", Self::USER_REFRESH_PREFIX, user_id);
+
+        let backend = redis.backend();
+
+        // 存储刷新令牌信息，设置过期时间
+        backend
+            .set(
+                &refresh_key,
+                &serde_json::to_string(&token_info)
+                    .map_err(|e| AppError::Internal(anyhow::anyhow!("JSON序列化失败: {}", e)))?,
+                Some(Self::REFRESH_TOKEN_EXPIRY_SECONDS),
+            )
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis存储刷新令牌失败: {}", e)))?;
+
+        // 将刷新令牌指纹添加到用户的刷新令牌集合中
+        backend
+            .sadd(&user_refresh_key, fingerprint)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis添加刷新令牌失败: {}", e)))?;
+
+        backend
+            .expire(&user_refresh_key, Self::REFRESH_TOKEN_EXPIRY_SECONDS)
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Redis设置刷新令牌集合过期时间失败: {}", e))
+            })?;
+
+        Ok(())
+    }
+
     /// 验证 token 有效性
     ///
     /// # 参数
     ///
     /// * `redis` - Redis 管理器
     /// * `token` - 要验证的 JWT token
-    /// * `jwt_secret` - JWT 密钥
+    /// * `jwt_keys` - JWT 签名和验证密钥（包含允许的时钟偏差，参见 `JwtKeys::with_leeway`）
     ///
     /// # 返回值
     ///
@@ -152,47 +577,81 @@ impl TokenService {
     pub async fn verify_token(
         redis: &RedisManager,
         token: &str,
-        jwt_secret: &str,
+        jwt_keys: &JwtKeys,
     ) -> Result<Claims> {
         // 首先验证 JWT token 的签名和格式
-        let claims = verify_jwt(token, jwt_secret)?;
-
-        // 检查 token 是否在 Redis 中存在（未被撤销）
-        let token_key = format!("{}{}", Self::TOKEN_PREFIX, token);
+        let claims = decode_jwt(token, jwt_keys)?;
 
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
+        // 检查 token 是否在 Redis 中存在（未被撤销），兼容早期版本以原始 token 为键名的记录
+        let backend = redis.backend();
 
-        let exists: bool = conn
-            .exists(&token_key)
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis检查token存在性失败: {}", e)))?;
+        let mut token_key = None;
+        for key in [
+            format!("{}{}", Self::TOKEN_PREFIX, Self::token_fingerprint(token)),
+            format!("{}{}", Self::LEGACY_TOKEN_PREFIX, token),
+        ] {
+            let exists = backend.exists(&key).await.map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Redis检查token存在性失败: {}", e))
+            })?;
+            if exists {
+                token_key = Some(key);
+                break;
+            }
+        }
 
-        if !exists {
+        let Some(token_key) = token_key else {
             return Err(AppError::Authentication(
                 "Token已被撤销或不存在".to_string(),
             ));
-        }
+        };
 
         // 可选：获取并验证 token 信息
-        let token_info_str: Option<String> = conn
+        let token_info_str = backend
             .get(&token_key)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取token信息失败: {}", e)))?;
 
         if let Some(info_str) = token_info_str {
-            let token_info: TokenInfo = serde_json::from_str(&info_str)
+            let mut token_info: TokenInfo = serde_json::from_str(&info_str)
                 .map_err(|e| AppError::Internal(anyhow::anyhow!("Token信息反序列化失败: {}", e)))?;
 
             // 验证 token 信息中的用户 ID 是否与 JWT claims 一致
             if token_info.user_id.to_string() != claims.sub {
                 return Err(AppError::Authentication("Token信息不一致".to_string()));
             }
+
+            // 更新最后使用时间（节流，失败不影响验证结果）
+            let now = Utc::now().timestamp();
+            if token_info.needs_last_used_update(now) {
+                token_info.last_used_at = now;
+                if let Err(e) = Self::update_token_info(backend, &token_key, &token_info).await {
+                    tracing::warn!("更新token最后使用时间失败: {}", e);
+                }
+            }
         }
 
         Ok(claims)
     }
 
+    /// 覆盖已存在的 token 信息，保留原有的过期时间
+    ///
+    /// 使用 `SET ... XX KEEPTTL`，token 在此期间被撤销时不会重新写入（需要 Redis 6.0+）。
+    async fn update_token_info(
+        backend: &dyn RedisBackend,
+        token_key: &str,
+        token_info: &TokenInfo,
+    ) -> Result<()> {
+        let value = serde_json::to_string(token_info)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("JSON序列化失败: {}", e)))?;
+
+        backend
+            .set_existing_keep_ttl(token_key, &value)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis更新token信息失败: {}", e)))?;
+
+        Ok(())
+    }
+
     /// 撤销单个 token
     ///
     /// # 参数
@@ -201,42 +660,61 @@ impl TokenService {
     /// * `token` - 要撤销的 token
     /// * `user_id` - 用户 ID（用于从用户 token 集合中移除）
     pub async fn revoke_token(redis: &RedisManager, token: &str, user_id: Uuid) -> Result<()> {
-        let token_key = format!("{}{}", Self::TOKEN_PREFIX, token);
+        Self::revoke_fingerprint(redis, &Self::token_fingerprint(token), user_id).await?;
+
+        // 早期版本以原始 token 作为键名和集合成员
+        Self::revoke_fingerprint(redis, token, user_id).await
+    }
+
+    /// 按指纹撤销单个 token
+    ///
+    /// 用户 token 集合中只保存指纹，淘汰和按设备撤销会话时使用此方法。
+    /// 与访问令牌配对的刷新令牌会一并撤销，避免被撤销的会话通过刷新令牌恢复。
+    /// 早期版本的集合成员是原始 token，因此同时删除新旧两种键名。
+    async fn revoke_fingerprint(
+        redis: &RedisManager,
+        fingerprint: &str,
+        user_id: Uuid,
+    ) -> Result<()> {
         let user_tokens_key = format!("{}{}", Self::USER_TOKENS_PREFIX, user_id);
 
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
+        let backend = redis.backend();
 
-        // 获取 token 信息以确定设备类型
-        let token_info_str: Option<String> = conn
-            .get(&token_key)
+        // 获取 token 信息以确定设备类型（信息无法解析时仍然删除 token）
+        let token_info = Self::get_token_info_by_fingerprint(redis, fingerprint)
             .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取token信息失败: {}", e)))?;
+            .ok()
+            .flatten();
 
-        if let Some(info_str) = token_info_str {
-            if let Ok(token_info) = serde_json::from_str::<TokenInfo>(&info_str) {
-                // 删除设备 token 记录
-                let user_device_key = format!(
-                    "{}{}{}",
-                    Self::USER_DEVICE_TOKEN_PREFIX,
-                    user_id,
-                    token_info.device_info.device_type
-                );
-                let _: () = conn.del(&user_device_key).await.map_err(|e| {
-                    AppError::Internal(anyhow::anyhow!("Redis删除设备token记录失败: {}", e))
-                })?;
+        if let Some(token_info) = token_info {
+            // 删除设备 token 记录
+            let user_device_key = format!(
+                "{}{}{}",
+                Self::USER_DEVICE_TOKEN_PREFIX,
+                user_id,
+                token_info.device_info.device_type
+            );
+            backend.del(&user_device_key).await.map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Redis删除设备token记录失败: {}", e))
+            })?;
+
+            // 撤销配对的刷新令牌
+            if let Some(refresh_fingerprint) = &token_info.refresh_fingerprint {
+                Self::revoke_refresh_fingerprint(redis, refresh_fingerprint, user_id).await?;
             }
         }
 
         // 删除 token 信息
-        let _: () = conn
-            .del(&token_key)
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis删除token失败: {}", e)))?;
+        for prefix in [Self::TOKEN_PREFIX, Self::LEGACY_TOKEN_PREFIX] {
+            backend
+                .del(&format!("{}{}", prefix, fingerprint))
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis删除token失败: {}", e)))?;
+        }
 
         // 从用户 token 集合中移除
-        let _: () = conn
-            .srem(&user_tokens_key, token)
+        backend
+            .srem(&user_tokens_key, fingerprint)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis移除用户token失败: {}", e)))?;
 
@@ -252,26 +730,28 @@ impl TokenService {
     pub async fn revoke_all_user_tokens(redis: &RedisManager, user_id: Uuid) -> Result<()> {
         let user_tokens_key = format!("{}{}", Self::USER_TOKENS_PREFIX, user_id);
 
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
+        let backend = redis.backend();
 
-        // 获取用户的所有 token
-        let tokens: Vec<String> = conn
+        // 获取用户所有 token 的指纹
+        let fingerprints = backend
             .smembers(&user_tokens_key)
             .await
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取用户tokens失败: {}", e)))?;
 
-        // 删除所有 token 信息
-        for token in tokens {
-            let token_key = format!("{}{}", Self::TOKEN_PREFIX, token);
-            let _: () = conn
-                .del(&token_key)
-                .await
-                .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis删除token失败: {}", e)))?;
+        // 删除所有 token 信息（包括早期版本以原始 token 为键名的记录）
+        for fingerprint in fingerprints {
+            for prefix in [Self::TOKEN_PREFIX, Self::LEGACY_TOKEN_PREFIX] {
+                backend
+                    .del(&format!("{}{}", prefix, fingerprint))
+                    .await
+                    .map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!("Redis删除token失败: {}", e))
+                    })?;
+            }
         }
 
         // 删除用户 token 集合
-        let _: () = conn.del(&user_tokens_key).await.map_err(|e| {
+        backend.del(&user_tokens_key).await.map_err(|e| {
             AppError::Internal(anyhow::anyhow!("Redis删除用户token集合失败: {}", e))
         })?;
 
@@ -281,6 +761,7 @@ impl TokenService {
             DeviceType::Mobile,
             DeviceType::Desktop,
             DeviceType::Api,
+            DeviceType::Bot,
         ] {
             let user_device_key = format!(
                 "{}{}{}",
@@ -288,11 +769,33 @@ impl TokenService {
                 user_id,
                 device_type
             );
-            let _: () = conn.del(&user_device_key).await.map_err(|e| {
+            backend.del(&user_device_key).await.map_err(|e| {
                 AppError::Internal(anyhow::anyhow!("Redis删除设备token记录失败: {}", e))
             })?;
         }
 
+        // 删除用户的所有刷新令牌
+        let user_refresh_key = format!("{}{}", Self::USER_REFRESH_PREFIX, user_id);
+        let refresh_fingerprints = backend
+            .smembers(&user_refresh_key)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取用户刷新令牌失败: {}", e)))?;
+
+        for fingerprint in refresh_fingerprints {
+            for prefix in [Self::REFRESH_PREFIX, Self::LEGACY_REFRESH_PREFIX] {
+                backend
+                    .del(&format!("{}{}", prefix, fingerprint))
+                    .await
+                    .map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!("Redis删除刷新令牌失败: {}", e))
+                    })?;
+            }
+        }
+
+        backend.del(&user_refresh_key).await.map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Redis删除用户刷新令牌集合失败: {}", e))
+        })?;
+
         Ok(())
     }
 
@@ -309,10 +812,7 @@ impl TokenService {
     pub async fn get_user_token_count(redis: &RedisManager, user_id: Uuid) -> Result<u32> {
         let user_tokens_key = format!("{}{}", Self::USER_TOKENS_PREFIX, user_id);
 
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
-
-        let count: u32 = conn.scard(&user_tokens_key).await.map_err(|e| {
+        let count = redis.backend().scard(&user_tokens_key).await.map_err(|e| {
             AppError::Internal(anyhow::anyhow!("Redis获取用户token数量失败: {}", e))
         })?;
 
@@ -330,15 +830,39 @@ impl TokenService {
     ///
     /// 返回 token 的详细信息
     pub async fn get_token_info(redis: &RedisManager, token: &str) -> Result<Option<TokenInfo>> {
-        let token_key = format!("{}{}", Self::TOKEN_PREFIX, token);
+        match Self::get_token_info_by_fingerprint(redis, &Self::token_fingerprint(token)).await? {
+            Some(token_info) => Ok(Some(token_info)),
+            // 早期版本以原始 token 作为键名
+            None => Self::read_token_info(redis, Self::LEGACY_TOKEN_PREFIX, token).await,
+        }
+    }
 
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
+    /// 按指纹获取 token 信息
+    ///
+    /// `fingerprint` 为用户 token 集合中的成员，早期版本的成员是原始 token，
+    /// 找不到指纹对应的记录时按早期版本的键名查找。
+    async fn get_token_info_by_fingerprint(
+        redis: &RedisManager,
+        fingerprint: &str,
+    ) -> Result<Option<TokenInfo>> {
+        match Self::read_token_info(redis, Self::TOKEN_PREFIX, fingerprint).await? {
+            Some(token_info) => Ok(Some(token_info)),
+            None => Self::read_token_info(redis, Self::LEGACY_TOKEN_PREFIX, fingerprint).await,
+        }
+    }
 
-        let token_info_str: Option<String> = conn
-            .get(&token_key)
-            .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取token信息失败: {}", e)))?;
+    /// 读取指定键前缀下的 token 信息
+    async fn read_token_info(
+        redis: &RedisManager,
+        prefix: &str,
+        id: &str,
+    ) -> Result<Option<TokenInfo>> {
+        let token_key = format!("{}{}", prefix, id);
+
+        let token_info_str =
+            redis.backend().get(&token_key).await.map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Redis获取token信息失败: {}", e))
+            })?;
 
         if let Some(info_str) = token_info_str {
             let token_info: TokenInfo = serde_json::from_str(&info_str)
@@ -351,16 +875,34 @@ impl TokenService {
 
     /// 撤销用户在特定设备类型上的所有 token
     ///
+    /// 遍历用户的 token 集合，仅撤销 `TokenInfo` 中设备类型匹配的 token，
+    /// 其他设备类型的会话不受影响。该设备类型的刷新令牌同样会被撤销，
+    /// 包括访问令牌已经过期、只剩刷新令牌的会话。
+    ///
     /// # 参数
     ///
     /// * `redis` - Redis 管理器
     /// * `user_id` - 用户 ID
     /// * `device_type` - 设备类型
+    ///
+    /// # 返回值
+    ///
+    /// 返回被撤销的 token 数量
     pub async fn revoke_device_tokens(
         redis: &RedisManager,
         user_id: Uuid,
         device_type: &DeviceType,
-    ) -> Result<()> {
+    ) -> Result<u32> {
+        let mut revoked = 0u32;
+
+        for (fingerprint, token_info) in Self::get_user_token_infos(redis, user_id).await? {
+            if &token_info.device_info.device_type == device_type {
+                Self::revoke_fingerprint(redis, &fingerprint, user_id).await?;
+                revoked += 1;
+            }
+        }
+
+        // 删除设备 token 记录
         let user_device_key = format!(
             "{}{}{}",
             Self::USER_DEVICE_TOKEN_PREFIX,
@@ -368,31 +910,60 @@ impl TokenService {
             device_type
         );
 
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
-
-        // 未查到直接通过
-        let existing_token: Option<String> = match conn.get(&user_device_key).await {
-            Ok(token) => token,
-            Err(_) => None,
-        };
-
-        if let Some(token) = existing_token {
-            // 撤销现有的 token
-            Self::revoke_token(redis, &token, user_id).await?;
-        }
-
-        // 删除设备 token 记录
-        let _: () = conn.del(&user_device_key).await.map_err(|e| {
+        redis.backend().del(&user_device_key).await.map_err(|e| {
             AppError::Internal(anyhow::anyhow!("Redis删除设备token记录失败: {}", e))
         })?;
 
-        Ok(())
+        Self::revoke_device_refresh_tokens(redis, user_id, device_type).await?;
+
+        Ok(revoked)
     }
 
-    /// 获取用户在特定设备类型上的活跃 token
+    /// 撤销用户在特定设备类型上的所有刷新令牌
     ///
-    /// # 参数
+    /// Redis 中已过期的刷新令牌会顺带从用户刷新令牌集合中移除。
+    async fn revoke_device_refresh_tokens(
+        redis: &RedisManager,
+        user_id: Uuid,
+        device_type: &DeviceType,
+    ) -> Result<()> {
+        let user_refresh_key = format!("{}{}", Self::USER_REFRESH_PREFIX, user_id);
+
+        let backend = redis.backend();
+
+        let fingerprints = backend
+            .smembers(&user_refresh_key)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取用户刷新令牌失败: {}", e)))?;
+
+        for fingerprint in fingerprints {
+            // 早期版本的集合成员是原始刷新令牌，按早期版本的键名查找
+            let mut token_info = None;
+            for prefix in [Self::REFRESH_PREFIX, Self::LEGACY_REFRESH_PREFIX] {
+                token_info = backend
+                    .get(&format!("{}{}", prefix, fingerprint))
+                    .await
+                    .map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!("Redis获取刷新令牌失败: {}", e))
+                    })?
+                    .and_then(|info_str| serde_json::from_str::<TokenInfo>(&info_str).ok());
+                if token_info.is_some() {
+                    break;
+                }
+            }
+
+            // 刷新令牌已过期或设备类型匹配时撤销
+            if token_info.is_none_or(|info| &info.device_info.device_type == device_type) {
+                Self::revoke_refresh_fingerprint(redis, &fingerprint, user_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 获取用户在特定设备类型上的活跃 token 指纹
+    ///
+    /// # 参数
     ///
     /// * `redis` - Redis 管理器
     /// * `user_id` - 用户 ID
@@ -400,8 +971,9 @@ impl TokenService {
     ///
     /// # 返回值
     ///
-    /// 返回该设备类型的活跃 token（如果存在）
-    pub async fn get_device_token(
+    /// 返回该设备类型最近签发的 token 的指纹（如果存在），
+    /// 可以与 `token_fingerprint` 的结果比较
+    pub async fn get_device_token_fingerprint(
         redis: &RedisManager,
         user_id: Uuid,
         device_type: &DeviceType,
@@ -413,19 +985,35 @@ impl TokenService {
             device_type
         );
 
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
+        let fingerprint = redis
+            .backend()
+            .get(&user_device_key)
+            .await
+            .unwrap_or_default();
 
-        let token: Option<String> = match conn.get(&user_device_key).await {
-            Ok(token) => token,
-            Err(_) => None,
-        };
+        Ok(fingerprint)
+    }
 
-        Ok(token)
+    /// 获取用户在特定设备类型上的活跃 token
+    ///
+    /// Redis 中不再保存原始 token，返回值与 `get_device_token_fingerprint` 相同，
+    /// 是 token 指纹（旧数据中可能仍是原始 token）。
+    #[deprecated(
+        note = "返回的是 token 指纹而不是原始 token，请使用 `get_device_token_fingerprint`"
+    )]
+    pub async fn get_device_token(
+        redis: &RedisManager,
+        user_id: Uuid,
+        device_type: &DeviceType,
+    ) -> Result<Option<String>> {
+        Self::get_device_token_fingerprint(redis, user_id, device_type).await
     }
 
     /// 获取用户所有设备的活跃会话信息
     ///
+    /// 读取用户的 token 集合，按 `TokenInfo` 中记录的设备类型分组返回，
+    /// 同一设备类型内按创建时间倒序排列。
+    ///
     /// # 参数
     ///
     /// * `redis` - Redis 管理器
@@ -438,100 +1026,1026 @@ impl TokenService {
         redis: &RedisManager,
         user_id: Uuid,
     ) -> Result<Vec<(DeviceType, TokenInfo)>> {
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
+        let mut sessions: Vec<(DeviceType, TokenInfo)> = Self::get_user_token_infos(redis, user_id)
+            .await?
+            .into_iter()
+            .map(|(_, token_info)| (token_info.device_info.device_type.clone(), token_info))
+            .collect();
+
+        sessions.sort_by(|(a_type, a_info), (b_type, b_info)| {
+            a_type
+                .to_string()
+                .cmp(&b_type.to_string())
+                .then(b_info.created_at.cmp(&a_info.created_at))
+        });
 
-        let mut sessions = Vec::new();
+        Ok(sessions)
+    }
 
-        // 遍历所有设备类型
-        for device_type in [
-            DeviceType::Web,
-            DeviceType::Mobile,
-            DeviceType::Desktop,
-            DeviceType::Api,
-        ] {
-            let user_device_key = format!(
-                "{}{}{}",
-                Self::USER_DEVICE_TOKEN_PREFIX,
-                user_id,
-                device_type
-            );
+    /// 读取用户 token 集合中所有仍然有效的 token 指纹及其信息
+    ///
+    /// Redis 中已过期的 token 会顺带从用户 token 集合中移除；旧版本留下的原始 token 仍然有效时按早期键名读取。
+    async fn get_user_token_infos(
+        redis: &RedisManager,
+        user_id: Uuid,
+    ) -> Result<Vec<(String, TokenInfo)>> {
+        let user_tokens_key = format!("{}{}", Self::USER_TOKENS_PREFIX, user_id);
+
+        let backend = redis.backend();
 
-            if let Ok(Some(token)) = conn.get::<_, Option<String>>(&user_device_key).await {
-                // 获取 token 信息
-                if let Ok(Some(token_info)) = Self::get_token_info(redis, &token).await {
-                    sessions.push((device_type, token_info));
+        let fingerprints = backend
+            .smembers(&user_tokens_key)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取用户tokens失败: {}", e)))?;
+
+        let mut token_infos = Vec::with_capacity(fingerprints.len());
+        for fingerprint in fingerprints {
+            match Self::get_token_info_by_fingerprint(redis, &fingerprint).await? {
+                Some(token_info) => token_infos.push((fingerprint, token_info)),
+                None => {
+                    // token 已过期，清理集合中的残留记录
+                    backend
+                        .srem(&user_tokens_key, &fingerprint)
+                        .await
+                        .map_err(|e| {
+                            AppError::Internal(anyhow::anyhow!("Redis移除用户过期token失败: {}", e))
+                        })?;
                 }
             }
         }
 
-        Ok(sessions)
+        Ok(token_infos)
+    }
+
+    /// 获取用户所有设备的活跃会话信息，并标记当前会话
+    ///
+    /// # 参数
+    ///
+    /// * `redis` - Redis 管理器
+    /// * `user_id` - 用户 ID
+    /// * `current_token` - 当前请求使用的 token
+    ///
+    /// # 返回值
+    ///
+    /// 返回包含设备类型、token信息以及是否为当前会话的向量
+    pub async fn get_user_device_sessions_with_current(
+        redis: &RedisManager,
+        user_id: Uuid,
+        current_token: &str,
+    ) -> Result<Vec<(DeviceType, TokenInfo, bool)>> {
+        let sessions = Self::get_user_device_sessions(redis, user_id).await?;
+
+        Ok(sessions
+            .into_iter()
+            .map(|(device_type, token_info)| {
+                let is_current = token_info.matches_token(current_token);
+                (device_type, token_info, is_current)
+            })
+            .collect())
     }
 
     /// 清理过期的 token（可选的维护功能）
     ///
-    /// 这个方法可以由定时任务调用，清理 Redis 中可能残留的过期 token
+    /// 这个方法可以由定时任务调用，清理 Redis 中可能残留的过期 token。
+    /// 使用 `SCAN` 游标分批遍历 token 键，避免 `KEYS` 在大键空间上阻塞 Redis。
     ///
     /// # 参数
     ///
     /// * `redis` - Redis 管理器
+    ///
+    /// # 返回值
+    ///
+    /// 返回清理的过期 token 数量
     pub async fn cleanup_expired_tokens(redis: &RedisManager) -> Result<u32> {
-        use redis::AsyncCommands;
-        let mut conn = redis.connection().clone();
+        let backend = redis.backend();
 
         let pattern = format!("{}*", Self::TOKEN_PREFIX);
+        let now = Utc::now().timestamp();
         let mut cleaned_count = 0u32;
+        let mut cursor: u64 = 0;
+
+        loop {
+            // 分批获取 token 键
+            let (next_cursor, keys) = backend
+                .scan(cursor, &pattern, Self::SCAN_BATCH_SIZE)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis扫描token键失败: {}", e)))?;
 
-        // 获取所有 token 键
-        let keys: Vec<String> = conn
-            .keys(&pattern)
+            // 逐批处理本次扫描到的键
+            for key in keys {
+                if Self::remove_if_expired(backend, &key, now).await? {
+                    cleaned_count += 1;
+                }
+            }
+
+            // 游标回到 0 表示遍历完成
+            cursor = next_cursor;
+            if cursor == 0 {
+                break;
+            }
+        }
+
+        Ok(cleaned_count)
+    }
+
+    /// 如果 token 已过期，则删除 token 及其关联记录
+    ///
+    /// # 参数
+    ///
+    /// * `backend` - Redis 存储后端
+    /// * `key` - token 键名
+    /// * `now` - 当前时间戳
+    ///
+    /// # 返回值
+    ///
+    /// 返回 token 是否已过期并被删除
+    async fn remove_if_expired(backend: &dyn RedisBackend, key: &str, now: i64) -> Result<bool> {
+        // 获取 token 信息
+        let token_info_str = backend
+            .get(key)
             .await
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取token键列表失败: {}", e)))?;
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis获取token信息失败: {}", e)))?;
 
-        let now = Utc::now().timestamp();
+        let Some(token_info) =
+            token_info_str.and_then(|info_str| serde_json::from_str::<TokenInfo>(&info_str).ok())
+        else {
+            return Ok(false);
+        };
 
-        for key in keys {
-            // 获取 token 信息
-            let token_info_str: Option<String> = conn.get(&key).await.map_err(|e| {
-                AppError::Internal(anyhow::anyhow!("Redis获取token信息失败: {}", e))
+        // 检查是否过期
+        if token_info.expires_at >= now {
+            return Ok(false);
+        }
+
+        backend
+            .del(key)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis删除过期token失败: {}", e)))?;
+
+        // 从用户 token 集合中移除
+        let fingerprint = key.strip_prefix(Self::TOKEN_PREFIX).unwrap_or("");
+        let user_tokens_key = format!("{}{}", Self::USER_TOKENS_PREFIX, token_info.user_id);
+        backend
+            .srem(&user_tokens_key, fingerprint)
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Redis移除用户过期token失败: {}", e))
             })?;
 
-            if let Some(info_str) = token_info_str {
-                if let Ok(token_info) = serde_json::from_str::<TokenInfo>(&info_str) {
-                    // 检查是否过期
-                    if token_info.expires_at < now {
-                        let _: () = conn.del(&key).await.map_err(|e| {
-                            AppError::Internal(anyhow::anyhow!("Redis删除过期token失败: {}", e))
-                        })?;
+        // 删除设备 token 记录
+        let user_device_key = format!(
+            "{}{}{}",
+            Self::USER_DEVICE_TOKEN_PREFIX,
+            token_info.user_id,
+            token_info.device_info.device_type
+        );
+        backend.del(&user_device_key).await.map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Redis删除过期设备token记录失败: {}", e))
+        })?;
 
-                        // 从用户 token 集合中移除
-                        let token = key.strip_prefix(Self::TOKEN_PREFIX).unwrap_or("");
-                        let user_tokens_key =
-                            format!("{}{}", Self::USER_TOKENS_PREFIX, token_info.user_id);
-                        let _: () = conn.srem(&user_tokens_key, token).await.map_err(|e| {
-                            AppError::Internal(anyhow::anyhow!("Redis移除用户过期token失败: {}", e))
-                        })?;
+        Ok(true)
+    }
+}
 
-                        // 删除设备 token 记录
-                        let user_device_key = format!(
-                            "{}{}{}",
-                            Self::USER_DEVICE_TOKEN_PREFIX,
-                            token_info.user_id,
-                            token_info.device_info.device_type
-                        );
-                        let _: () = conn.del(&user_device_key).await.map_err(|e| {
-                            AppError::Internal(anyhow::anyhow!(
-                                "Redis删除过期设备token记录失败: {}",
-                                e
-                            ))
-                        })?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support;
 
-                        cleaned_count += 1;
-                    }
-                }
+    /// 直接向 Redis 写入一条 token 记录（绕过 JWT 生成）
+    async fn store_token_info(redis: &RedisManager, token: &str, expires_at: i64) {
+        let token_info = TokenInfo {
+            user_id: Uuid::new_v4(),
+            created_at: Utc::now().timestamp(),
+            expires_at,
+            device_info: DeviceInfo::simple(DeviceType::Api, None),
+            ip_address: None,
+            fingerprint: None,
+            last_used_at: 0,
+            refresh_fingerprint: None,
+        };
+
+        redis
+            .backend()
+            .set(
+                &format!(
+                    "{}{}",
+                    TokenService::TOKEN_PREFIX,
+                    TokenService::token_fingerprint(token)
+                ),
+                &serde_json::to_string(&token_info).unwrap(),
+                Some(60),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_refresh_token_rotation() {
+        let redis = test_support::redis_manager().await;
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+
+        let pair = TokenService::create_token_pair(
+            &redis,
+            &AuthUser::new(user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Web, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        // 消费刷新令牌后使用原设备信息签发新的令牌对
+        let token_info = TokenService::consume_refresh_token(&redis, &pair.refresh_token)
+            .await
+            .unwrap();
+        assert_eq!(token_info.user_id, user_id);
+        let new_pair = TokenService::create_token_pair(
+            &redis,
+            &AuthUser::new(token_info.user_id),
+            &keys,
+            token_info.device_info,
+            token_info.ip_address,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+        assert_ne!(new_pair.refresh_token, pair.refresh_token);
+        assert!(
+            TokenService::verify_token(&redis, &new_pair.access_token, &keys)
+                .await
+                .is_ok()
+        );
+
+        // 旧的刷新令牌已被轮换作废
+        assert!(
+            TokenService::consume_refresh_token(&redis, &pair.refresh_token)
+                .await
+                .is_err()
+        );
+
+        // 撤销用户所有 token 后，刷新令牌同样失效
+        TokenService::revoke_all_user_tokens(&redis, user_id)
+            .await
+            .unwrap();
+        assert!(
+            TokenService::consume_refresh_token(&redis, &new_pair.refresh_token)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_cleanup_expired_tokens() {
+        let redis = test_support::redis_manager().await;
+        let expired_token = test_support::unique_key("expired");
+        let valid_token = test_support::unique_key("valid");
+        let now = Utc::now().timestamp();
+
+        store_token_info(&redis, &expired_token, now - 60).await;
+        store_token_info(&redis, &valid_token, now + 3600).await;
+
+        let cleaned = TokenService::cleanup_expired_tokens(&redis).await.unwrap();
+        assert!(cleaned >= 1);
+
+        assert!(TokenService::get_token_info(&redis, &expired_token)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(TokenService::get_token_info(&redis, &valid_token)
+            .await
+            .unwrap()
+            .is_some());
+
+        redis
+            .backend()
+            .del(&format!(
+                "{}{}",
+                TokenService::TOKEN_PREFIX,
+                TokenService::token_fingerprint(&valid_token)
+            ))
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_token_info_matches_token() {
+        let mut token_info = TokenInfo {
+            user_id: Uuid::new_v4(),
+            created_at: 0,
+            expires_at: 0,
+            device_info: DeviceInfo::simple(DeviceType::Web, None),
+            ip_address: None,
+            fingerprint: None,
+            last_used_at: 0,
+            refresh_fingerprint: None,
+        };
+        assert!(!token_info.matches_token("token-a"));
+
+        token_info.fingerprint = Some(TokenService::token_fingerprint("token-a"));
+        assert!(token_info.matches_token("token-a"));
+        assert!(!token_info.matches_token("token-b"));
+    }
+
+    #[test]
+    fn test_token_fingerprint() {
+        let fingerprint = TokenService::token_fingerprint("token-a");
+        assert_eq!(fingerprint, TokenService::token_fingerprint("token-a"));
+        assert_ne!(fingerprint, TokenService::token_fingerprint("token-b"));
+        assert_eq!(fingerprint.len(), 64);
+        assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[tokio::test]
+    async fn test_raw_tokens_are_not_stored() {
+        let redis = RedisManager::in_memory();
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+
+        let pair = TokenService::create_token_pair(
+            &redis,
+            &AuthUser::new(user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Web, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        let backend = redis.backend();
+        let user_tokens = backend
+            .smembers(&format!("{}{}", TokenService::USER_TOKENS_PREFIX, user_id))
+            .await
+            .unwrap();
+        assert_eq!(
+            user_tokens,
+            vec![TokenService::token_fingerprint(&pair.access_token)]
+        );
+        let user_refresh_tokens = backend
+            .smembers(&format!("{}{}", TokenService::USER_REFRESH_PREFIX, user_id))
+            .await
+            .unwrap();
+        assert_eq!(
+            user_refresh_tokens,
+            vec![TokenService::token_fingerprint(&pair.refresh_token)]
+        );
+
+        for token in [&pair.access_token, &pair.refresh_token] {
+            for prefix in [TokenService::TOKEN_PREFIX, TokenService::REFRESH_PREFIX] {
+                assert!(!backend
+                    .exists(&format!("{}{}", prefix, token))
+                    .await
+                    .unwrap());
             }
         }
+    }
 
-        Ok(cleaned_count)
+    #[test]
+    fn test_needs_last_used_update() {
+        let token_info = TokenInfo {
+            user_id: Uuid::new_v4(),
+            created_at: 1_000,
+            expires_at: 0,
+            device_info: DeviceInfo::simple(DeviceType::Web, None),
+            ip_address: None,
+            fingerprint: None,
+            last_used_at: 1_000,
+            refresh_fingerprint: None,
+        };
+        assert!(!token_info.needs_last_used_update(1_000));
+        assert!(!token_info.needs_last_used_update(1_059));
+        assert!(token_info.needs_last_used_update(1_060));
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_updates_last_used_at() {
+        let redis = RedisManager::in_memory();
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+
+        let token = TokenService::create_token(
+            &redis,
+            &AuthUser::new(user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Web, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+        let token_key = format!(
+            "{}{}",
+            TokenService::TOKEN_PREFIX,
+            TokenService::token_fingerprint(&token)
+        );
+
+        // 将最后使用时间回拨到节流窗口内，验证后不应写入
+        let mut token_info = TokenService::get_token_info(&redis, &token)
+            .await
+            .unwrap()
+            .unwrap();
+        let within_window = Utc::now().timestamp() - 30;
+        token_info.last_used_at = within_window;
+        TokenService::update_token_info(redis.backend(), &token_key, &token_info)
+            .await
+            .unwrap();
+
+        TokenService::verify_token(&redis, &token, &keys)
+            .await
+            .unwrap();
+        TokenService::verify_token(&redis, &token, &keys)
+            .await
+            .unwrap();
+        let token_info = TokenService::get_token_info(&redis, &token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(token_info.last_used_at, within_window);
+
+        // 超出节流窗口后验证会更新最后使用时间，并保留原有的过期时间
+        let mut token_info = token_info;
+        token_info.last_used_at -= TokenService::LAST_USED_UPDATE_INTERVAL_SECONDS;
+        TokenService::update_token_info(redis.backend(), &token_key, &token_info)
+            .await
+            .unwrap();
+
+        let before = Utc::now().timestamp();
+        TokenService::verify_token(&redis, &token, &keys)
+            .await
+            .unwrap();
+        let token_info = TokenService::get_token_info(&redis, &token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(token_info.last_used_at >= before);
+
+        assert!(redis.backend().ttl(&token_key).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_create_verify_and_revoke_token_in_memory() {
+        let redis = RedisManager::in_memory();
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+
+        let token = TokenService::create_token(
+            &redis,
+            &AuthUser::new(user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Web, None),
+            Some("203.0.113.7".to_string()),
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        let claims = TokenService::verify_token(&redis, &token, &keys)
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, user_id.to_string());
+        assert_eq!(
+            TokenService::get_user_token_count(&redis, user_id)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            TokenService::get_device_token_fingerprint(&redis, user_id, &DeviceType::Web)
+                .await
+                .unwrap(),
+            Some(TokenService::token_fingerprint(&token))
+        );
+        let token_info = TokenService::get_token_info(&redis, &token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(token_info.ip_address.as_deref(), Some("203.0.113.7"));
+        assert!(token_info.matches_token(&token));
+
+        TokenService::revoke_token(&redis, &token, user_id)
+            .await
+            .unwrap();
+        assert!(matches!(
+            TokenService::verify_token(&redis, &token, &keys).await,
+            Err(AppError::Authentication(_))
+        ));
+        assert_eq!(
+            TokenService::get_user_token_count(&redis, user_id)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            TokenService::get_device_token_fingerprint(&redis, user_id, &DeviceType::Web)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_rotation_in_memory() {
+        let redis = RedisManager::in_memory();
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+
+        let pair = TokenService::create_token_pair(
+            &redis,
+            &AuthUser::new(user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Mobile, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        let token_info = TokenService::consume_refresh_token(&redis, &pair.refresh_token)
+            .await
+            .unwrap();
+        assert_eq!(token_info.user_id, user_id);
+        assert_eq!(token_info.device_info.device_type, DeviceType::Mobile);
+
+        // 刷新令牌只能使用一次
+        assert!(matches!(
+            TokenService::consume_refresh_token(&redis, &pair.refresh_token).await,
+            Err(AppError::Authentication(_))
+        ));
+
+        // 撤销所有 token 后访问令牌失效
+        TokenService::revoke_all_user_tokens(&redis, user_id)
+            .await
+            .unwrap();
+        assert!(
+            TokenService::verify_token(&redis, &pair.access_token, &keys)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoked_sessions_cannot_refresh_in_memory() {
+        let redis = RedisManager::in_memory();
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+        let policy = SessionPolicy {
+            single_session_per_device: false,
+            max_sessions_per_user: None,
+        };
+        let user = AuthUser::new(user_id);
+        let login = |device_type, policy| {
+            TokenService::create_token_pair(
+                &redis,
+                &user,
+                &keys,
+                DeviceInfo::simple(device_type, None),
+                None,
+                policy,
+            )
+        };
+        let refresh_fails = |refresh_token: String| {
+            let redis = redis.clone();
+            async move {
+                matches!(
+                    TokenService::consume_refresh_token(&redis, &refresh_token).await,
+                    Err(AppError::Authentication(_))
+                )
+            }
+        };
+
+        // 退出登录后，配对的刷新令牌同时失效
+        let pair = login(DeviceType::Web, policy).await.unwrap();
+        TokenService::revoke_token(&redis, &pair.access_token, user_id)
+            .await
+            .unwrap();
+        assert!(refresh_fails(pair.refresh_token).await);
+
+        // 按设备类型退出后，该设备的刷新令牌失效，其他设备不受影响
+        let web = login(DeviceType::Web, policy).await.unwrap();
+        let mobile = login(DeviceType::Mobile, policy).await.unwrap();
+        TokenService::revoke_device_tokens(&redis, user_id, &DeviceType::Web)
+            .await
+            .unwrap();
+        assert!(refresh_fails(web.refresh_token).await);
+        assert!(
+            TokenService::consume_refresh_token(&redis, &mobile.refresh_token)
+                .await
+                .is_ok()
+        );
+
+        // 访问令牌已过期、只剩刷新令牌的会话同样会被撤销
+        let web = login(DeviceType::Web, policy).await.unwrap();
+        redis
+            .backend()
+            .del(&format!(
+                "{}{}",
+                TokenService::TOKEN_PREFIX,
+                TokenService::token_fingerprint(&web.access_token)
+            ))
+            .await
+            .unwrap();
+        TokenService::revoke_device_tokens(&redis, user_id, &DeviceType::Web)
+            .await
+            .unwrap();
+        assert!(refresh_fails(web.refresh_token).await);
+
+        // 会话数达到上限时，被淘汰会话的刷新令牌失效
+        TokenService::revoke_all_user_tokens(&redis, user_id)
+            .await
+            .unwrap();
+        let capped = SessionPolicy {
+            max_sessions_per_user: Some(1),
+            ..policy
+        };
+        let oldest = login(DeviceType::Api, capped).await.unwrap();
+        let newest = login(DeviceType::Api, capped).await.unwrap();
+        assert!(refresh_fails(oldest.refresh_token).await);
+        assert!(
+            TokenService::consume_refresh_token(&redis, &newest.refresh_token)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_legacy_raw_token_keys_in_memory() {
+        let redis = RedisManager::in_memory();
+        let backend = redis.backend();
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+        let now = Utc::now().timestamp();
+
+        // 按早期版本的格式写入以原始 token 为键名的记录
+        let legacy_info = |token: &str| {
+            serde_json::json!({
+                "user_id": user_id,
+                "created_at": now,
+                "expires_at": now + 60,
+                "device_info": DeviceInfo::simple(DeviceType::Web, None),
+                "ip_address": null,
+                "token_hash": TokenService::token_fingerprint(token),
+            })
+            .to_string()
+        };
+        let user_tokens_key = format!("{}{}", TokenService::USER_TOKENS_PREFIX, user_id);
+        let user_refresh_key = format!("{}{}", TokenService::USER_REFRESH_PREFIX, user_id);
+        let mut access_tokens = Vec::new();
+        for _ in 0..2 {
+            let token = generate_jwt_with_expiry(&AuthUser::new(user_id), &keys, 60).unwrap();
+            let key = format!("{}{}", TokenService::LEGACY_TOKEN_PREFIX, token);
+            backend
+                .set(&key, &legacy_info(&token), Some(60))
+                .await
+                .unwrap();
+            backend.sadd(&user_tokens_key, &token).await.unwrap();
+            access_tokens.push(token);
+        }
+        let mut refresh_tokens = Vec::new();
+        for refresh_token in ["legacy-refresh-a", "legacy-refresh-b"] {
+            let key = format!("{}{}", TokenService::LEGACY_REFRESH_PREFIX, refresh_token);
+            backend
+                .set(&key, &legacy_info(refresh_token), Some(60))
+                .await
+                .unwrap();
+            backend
+                .sadd(&user_refresh_key, refresh_token)
+                .await
+                .unwrap();
+            refresh_tokens.push(refresh_token);
+        }
+
+        // 升级后已登录的会话仍然有效，并能识别当前会话
+        for token in &access_tokens {
+            assert!(TokenService::verify_token(&redis, token, &keys)
+                .await
+                .is_ok());
+        }
+        let sessions =
+            TokenService::get_user_device_sessions_with_current(&redis, user_id, &access_tokens[0])
+                .await
+                .unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(
+            sessions.iter().filter(|(_, _, current)| *current).count(),
+            1
+        );
+
+        // 旧的刷新令牌可以使用一次
+        let token_info = TokenService::consume_refresh_token(&redis, refresh_tokens[0])
+            .await
+            .unwrap();
+        assert_eq!(token_info.user_id, user_id);
+        assert!(
+            TokenService::consume_refresh_token(&redis, refresh_tokens[0])
+                .await
+                .is_err()
+        );
+
+        // 撤销单个旧 token
+        TokenService::revoke_token(&redis, &access_tokens[0], user_id)
+            .await
+            .unwrap();
+        assert!(TokenService::verify_token(&redis, &access_tokens[0], &keys)
+            .await
+            .is_err());
+
+        // 撤销所有 token 时旧记录一并删除
+        TokenService::revoke_all_user_tokens(&redis, user_id)
+            .await
+            .unwrap();
+        assert!(TokenService::verify_token(&redis, &access_tokens[1], &keys)
+            .await
+            .is_err());
+        assert!(
+            TokenService::consume_refresh_token(&redis, refresh_tokens[1])
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_get_device_token_alias_in_memory() {
+        let redis = RedisManager::in_memory();
+        let user_id = Uuid::new_v4();
+
+        let token = TokenService::create_token(
+            &redis,
+            &AuthUser::new(user_id),
+            &JwtKeys::hs256("test-secret"),
+            DeviceInfo::simple(DeviceType::Desktop, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            TokenService::get_device_token(&redis, user_id, &DeviceType::Desktop)
+                .await
+                .unwrap(),
+            Some(TokenService::token_fingerprint(&token))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all_user_tokens_in_memory() {
+        let redis = RedisManager::in_memory();
+        let user_id = Uuid::new_v4();
+        let other_user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+
+        let mut tokens = Vec::new();
+        for device_type in [DeviceType::Web, DeviceType::Mobile] {
+            tokens.push(
+                TokenService::create_token(
+                    &redis,
+                    &AuthUser::new(user_id),
+                    &keys,
+                    DeviceInfo::simple(device_type, None),
+                    None,
+                    SessionPolicy::default(),
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        let other_token = TokenService::create_token(
+            &redis,
+            &AuthUser::new(other_user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Web, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        TokenService::revoke_all_user_tokens(&redis, user_id)
+            .await
+            .unwrap();
+        for token in &tokens {
+            assert!(TokenService::verify_token(&redis, token, &keys)
+                .await
+                .is_err());
+        }
+        assert!(TokenService::get_user_device_sessions(&redis, user_id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // 其他用户的会话不受影响
+        assert!(TokenService::verify_token(&redis, &other_token, &keys)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_sessions_flag_current_token() {
+        let redis = test_support::redis_manager().await;
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+
+        let web_token = TokenService::create_token(
+            &redis,
+            &AuthUser::new(user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Web, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+        TokenService::create_token(
+            &redis,
+            &AuthUser::new(user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Mobile, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        let sessions =
+            TokenService::get_user_device_sessions_with_current(&redis, user_id, &web_token)
+                .await
+                .unwrap();
+        assert_eq!(sessions.len(), 2);
+
+        let current: Vec<_> = sessions
+            .iter()
+            .filter(|(_, _, is_current)| *is_current)
+            .collect();
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].0, DeviceType::Web);
+
+        TokenService::revoke_all_user_tokens(&redis, user_id)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_device_sessions_and_revocation() {
+        let redis = test_support::redis_manager().await;
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+
+        let web_token = TokenService::create_token(
+            &redis,
+            &AuthUser::new(user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Web, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+        let mobile_token = TokenService::create_token(
+            &redis,
+            &AuthUser::new(user_id),
+            &keys,
+            DeviceInfo::simple(DeviceType::Mobile, None),
+            None,
+            SessionPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        let sessions = TokenService::get_user_device_sessions(&redis, user_id)
+            .await
+            .unwrap();
+        let device_types: Vec<DeviceType> = sessions.into_iter().map(|(t, _)| t).collect();
+        assert_eq!(device_types.len(), 2);
+        assert!(device_types.contains(&DeviceType::Web));
+        assert!(device_types.contains(&DeviceType::Mobile));
+
+        // 撤销 Web 端后，移动端会话仍然有效
+        let revoked = TokenService::revoke_device_tokens(&redis, user_id, &DeviceType::Web)
+            .await
+            .unwrap();
+        assert_eq!(revoked, 1);
+        assert!(TokenService::verify_token(&redis, &web_token, &keys)
+            .await
+            .is_err());
+        assert!(TokenService::verify_token(&redis, &mobile_token, &keys)
+            .await
+            .is_ok());
+
+        let sessions = TokenService::get_user_device_sessions(&redis, user_id)
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].0, DeviceType::Mobile);
+
+        TokenService::revoke_all_user_tokens(&redis, user_id)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_single_session_per_device() {
+        let redis = test_support::redis_manager().await;
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+        let user = AuthUser::new(user_id);
+
+        let login = |policy| {
+            TokenService::create_token(
+                &redis,
+                &user,
+                &keys,
+                DeviceInfo::simple(DeviceType::Web, None),
+                None,
+                policy,
+            )
+        };
+
+        // 同一设备类型再次登录后，第一次登录的 token 失效
+        let first = login(SessionPolicy::default()).await.unwrap();
+        let second = login(SessionPolicy::default()).await.unwrap();
+        assert!(TokenService::verify_token(&redis, &first, &keys)
+            .await
+            .is_err());
+        assert!(TokenService::verify_token(&redis, &second, &keys)
+            .await
+            .is_ok());
+
+        // 关闭该策略后，同类设备可以同时保持多个会话
+        let multi = SessionPolicy {
+            single_session_per_device: false,
+            ..SessionPolicy::default()
+        };
+        let third = login(multi).await.unwrap();
+        assert!(TokenService::verify_token(&redis, &second, &keys)
+            .await
+            .is_ok());
+        assert!(TokenService::verify_token(&redis, &third, &keys)
+            .await
+            .is_ok());
+
+        TokenService::revoke_all_user_tokens(&redis, user_id)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_max_sessions_per_user() {
+        let redis = test_support::redis_manager().await;
+        let user_id = Uuid::new_v4();
+        let keys = JwtKeys::hs256("test-secret");
+        let max_sessions = 2;
+        let policy = SessionPolicy {
+            single_session_per_device: false,
+            max_sessions_per_user: Some(max_sessions),
+        };
+
+        let mut tokens = Vec::new();
+        for _ in 0..=max_sessions {
+            let token = TokenService::create_token(
+                &redis,
+                &AuthUser::new(user_id),
+                &keys,
+                DeviceInfo::simple(DeviceType::Api, None),
+                None,
+                policy,
+            )
+            .await
+            .unwrap();
+            tokens.push(token);
+
+            // created_at 精度为秒，确保会话的创建时间不同
+            tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        }
+
+        assert_eq!(
+            TokenService::get_user_token_count(&redis, user_id)
+                .await
+                .unwrap(),
+            max_sessions
+        );
+
+        // 最早的会话被淘汰，其余会话仍然有效
+        assert!(TokenService::verify_token(&redis, &tokens[0], &keys)
+            .await
+            .is_err());
+        for token in &tokens[1..] {
+            assert!(TokenService::verify_token(&redis, token, &keys)
+                .await
+                .is_ok());
+        }
+
+        TokenService::revoke_all_user_tokens(&redis, user_id)
+            .await
+            .unwrap();
     }
 }
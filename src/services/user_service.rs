@@ -3,17 +3,83 @@
  *
  * 处理所有与用户相关的业务逻辑，包括用户创建、身份验证、
  * 用户查询等操作。该服务封装了复杂的业务规则和数据操作。
+ * 按主键查询、分页和增改操作通过通用的 `PgRepository` 完成，
+ * 按邮箱查询等用户特有的查询仍然直接编写 SQL。
  */
 
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
 use uuid::Uuid;
 
 use crate::{
-    db::DbPool,
-    error::{AppError, Result},
-    models::{CreateUserRequest, LoginRequest, User},
-    utils::{hash_password, verify_password},
+    config::{Argon2Params, Config},
+    db::{DbPool, PgRepository, Repository},
+    error::{AppError, FieldError, Result},
+    models::{CreateUserRequest, LoginRequest, UpdateUserRequest, User},
+    utils::{
+        hash_password, needs_rehash, verify_password, CryptoUtils, StrengthLevel, StringUtils,
+    },
 };
 
+/// 密码策略
+///
+/// 控制设置新密码（注册、修改密码、重置密码）时的校验规则、哈希参数和泄露检查
+#[derive(Debug, Clone, Copy)]
+pub struct PasswordPolicy {
+    /// 密码最小长度（字符数）
+    pub min_length: usize,
+    /// 密码要求的最低强度等级
+    pub min_strength: StrengthLevel,
+    /// Argon2 密码哈希成本参数
+    pub argon2: Argon2Params,
+    /// 密码在已知泄露数据中出现次数超过该值时拒绝（None 表示不检查，需要启用 `hibp` feature）
+    pub pwned_threshold: Option<u64>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            min_strength: StrengthLevel::Medium,
+            argon2: Argon2Params::default(),
+            pwned_threshold: None,
+        }
+    }
+}
+
+impl From<&Config> for PasswordPolicy {
+    fn from(config: &Config) -> Self {
+        Self {
+            min_length: config.password_min_length,
+            min_strength: config.password_min_strength,
+            argon2: config.argon2,
+            pwned_threshold: config.pwned_password_threshold,
+        }
+    }
+}
+
+/// 插入 `users` 表的字段
+#[derive(Serialize)]
+struct NewUser<'a> {
+    email: &'a str,
+    password_hash: &'a str,
+    name: &'a str,
+}
+
+/// 更新 `users` 表的字段，`None` 表示保持原值
+///
+/// `updated_at` 不在其中，由 `UserService::repository` 在数据库中设置为 `NOW()`。
+#[derive(Serialize, Default)]
+struct UserChanges<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password_hash: Option<&'a str>,
+}
+
 /// 用户服务结构体
 ///
 /// 提供用户管理相关的业务逻辑方法。
@@ -21,21 +87,30 @@ use crate::{
 pub struct UserService;
 
 impl UserService {
+    /// `users` 表的数据访问对象，列表按创建时间倒序排列，更新时把 `updated_at` 设置为 `NOW()`
+    pub fn repository(pool: &DbPool) -> PgRepository<User> {
+        PgRepository::new(pool.clone(), "users")
+            .order_by("created_at DESC")
+            .touch("updated_at")
+    }
+
     /// 创建新用户
     ///
     /// 处理用户注册逻辑，包括邮箱重复检查、密码加密和数据库插入。
     ///
     /// # 业务规则
     ///
-    /// 1. 检查邮箱是否已被注册
-    /// 2. 使用 Argon2 算法对密码进行哈希处理
-    /// 3. 在数据库中创建新用户记录
-    /// 4. 自动设置创建时间和更新时间
+    /// 1. 校验邮箱格式、名称和密码（长度和强度），一次返回所有字段的错误
+    /// 2. 检查邮箱是否已被注册
+    /// 3. 使用 Argon2 算法对密码进行哈希处理
+    /// 4. 在数据库中创建新用户记录
+    /// 5. 自动设置创建时间和更新时间
     ///
     /// # 参数
     ///
     /// * `pool` - 数据库连接池
     /// * `request` - 用户注册请求数据
+    /// * `policy` - 密码策略（哈希参数和泄露检查）
     ///
     /// # 返回值
     ///
@@ -43,28 +118,39 @@ impl UserService {
     ///
     /// # 错误
     ///
+    /// - `AppError::InvalidFields`: 邮箱格式错误、名称为空或密码不满足策略要求
     /// - `AppError::Conflict`: 邮箱已存在
+    /// - `AppError::Validation`: 密码出现在已知的数据泄露中
     /// - `AppError::PasswordHash`: 密码哈希失败
     /// - `AppError::Database`: 数据库操作失败
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// let request = CreateUserRequest {
     ///     email: "user@example.com".to_string(),
     ///     password: "securePassword123".to_string(),
     ///     name: "张三".to_string(),
     /// };
     ///
-    /// let user = UserService::create_user(&pool, request).await?;
+    /// let user = UserService::create_user(&pool, request, PasswordPolicy::from(&config)).await?;
     /// println!("Created user: {}", user.email);
     /// ```
-    pub async fn create_user(pool: &DbPool, request: CreateUserRequest) -> Result<User> {
-        // 检查邮箱是否已经被注册
-        let existing_user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
-            .bind(&request.email)
-            .fetch_optional(pool)
-            .await?;
+    pub async fn create_user(
+        pool: &DbPool,
+        request: CreateUserRequest,
+        policy: PasswordPolicy,
+    ) -> Result<User> {
+        // 校验请求数据
+        validate_create_user_request(&request, &policy)?;
+        let email = StringUtils::normalize_email(&request.email);
+
+        // 检查邮箱是否已经被注册（不区分大小写）
+        let existing_user =
+            sqlx::query_as::<_, User>("SELECT * FROM users WHERE LOWER(email) = $1")
+                .bind(&email)
+                .fetch_optional(pool)
+                .await?;
 
         if existing_user.is_some() {
             return Err(AppError::Conflict(
@@ -72,22 +158,21 @@ impl UserService {
             ));
         }
 
+        // 拒绝已知泄露的密码
+        check_password_not_pwned(&request.password, &policy).await?;
+
         // 对密码进行哈希处理
-        let password_hash = hash_password(&request.password)?;
+        let password_hash = hash_password(&request.password, &policy.argon2)?;
 
         // 在数据库中创建新用户
-        let user = sqlx::query_as::<_, User>(
-            r#"
-            INSERT INTO users (email, password_hash, name)
-            VALUES ($1, $2, $3)
-            RETURNING *
-            "#,
-        )
-        .bind(&request.email)
-        .bind(&password_hash)
-        .bind(&request.name)
-        .fetch_one(pool)
-        .await?;
+        let user = Self::repository(pool)
+            .insert(&NewUser {
+                email: &email,
+                password_hash: &password_hash,
+                name: &request.name,
+            })
+            .await
+            .map_err(email_conflict_error)?;
 
         Ok(user)
     }
@@ -107,11 +192,13 @@ impl UserService {
     /// - 对于不存在的邮箱和错误的密码都返回相同的错误信息，
     ///   避免泄露用户是否存在的信息
     /// - 使用安全的密码哈希验证算法
+    /// - 密码验证通过但哈希参数与当前配置不一致时，自动使用当前参数重新哈希并保存
     ///
     /// # 参数
     ///
     /// * `pool` - 数据库连接池
     /// * `request` - 用户登录请求数据
+    /// * `argon2_params` - 当前配置的密码哈希成本参数
     ///
     /// # 返回值
     ///
@@ -124,19 +211,23 @@ impl UserService {
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// let request = LoginRequest {
     ///     email: "user@example.com".to_string(),
     ///     password: "securePassword123".to_string(),
     /// };
     ///
-    /// let user = UserService::authenticate_user(&pool, request).await?;
+    /// let user = UserService::authenticate_user(&pool, request, &config.argon2).await?;
     /// println!("User {} logged in", user.email);
     /// ```
-    pub async fn authenticate_user(pool: &DbPool, request: LoginRequest) -> Result<User> {
+    pub async fn authenticate_user(
+        pool: &DbPool,
+        request: LoginRequest,
+        argon2_params: &Argon2Params,
+    ) -> Result<User> {
         // 根据邮箱查找用户
-        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
-            .bind(&request.email)
+        let mut user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE LOWER(email) = $1")
+            .bind(StringUtils::normalize_email(&request.email))
             .fetch_optional(pool)
             .await?
             .ok_or_else(|| AppError::Authentication("Invalid email or password".to_string()))?;
@@ -149,9 +240,42 @@ impl UserService {
             ));
         }
 
+        // 哈希参数已过时或为旧系统的 bcrypt 哈希：使用当前参数重新哈希，失败时不影响本次登录
+        if needs_rehash(&user.password_hash, argon2_params) {
+            match Self::rehash_password(pool, user.id, &request.password, argon2_params).await {
+                Ok(password_hash) => user.password_hash = password_hash,
+                Err(e) => tracing::warn!("重新哈希用户 {} 的密码失败: {}", user.id, e),
+            }
+        }
+
         Ok(user)
     }
 
+    /// 使用当前参数重新哈希密码并保存
+    ///
+    /// 与其他更新一样会刷新 `updated_at`（`users` 表的 `update_users_updated_at` 触发器
+    /// 会在任何 UPDATE 时刷新该字段）。
+    async fn rehash_password(
+        pool: &DbPool,
+        user_id: Uuid,
+        password: &str,
+        argon2_params: &Argon2Params,
+    ) -> Result<String> {
+        let password_hash = hash_password(password, argon2_params)?;
+
+        Self::repository(pool)
+            .update(
+                user_id,
+                &UserChanges {
+                    password_hash: Some(&password_hash),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        Ok(password_hash)
+    }
+
     /// 根据用户 ID 获取用户信息
     ///
     /// 查询指定 ID 的用户详细信息，通常用于获取当前登录用户的资料。
@@ -172,7 +296,7 @@ impl UserService {
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// use uuid::Uuid;
     ///
     /// let user_id = Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000")?;
@@ -180,15 +304,51 @@ impl UserService {
     /// println!("Found user: {}", user.name);
     /// ```
     pub async fn get_user_by_id(pool: &DbPool, user_id: Uuid) -> Result<User> {
-        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-            .bind(user_id)
-            .fetch_optional(pool)
+        let user = Self::repository(pool)
+            .find_by_id(user_id)
             .await?
             .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
         Ok(user)
     }
 
+    /// 根据 ID 列表批量获取用户
+    ///
+    /// 使用单条 `WHERE id = ANY($1)` 查询代替逐个调用 `get_user_by_id`，
+    /// 适用于会话列表、审计日志等需要关联用户信息的场景。
+    ///
+    /// # 参数
+    ///
+    /// * `pool` - 数据库连接池
+    /// * `user_ids` - 用户 ID 列表（重复的 ID 只查询一次）
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<HashMap<Uuid, User>>`，以用户 ID 为键；
+    /// 不存在的 ID 不会出现在结果中
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Database`: 数据库操作失败
+    pub async fn get_users_by_ids(pool: &DbPool, user_ids: &[Uuid]) -> Result<HashMap<Uuid, User>> {
+        let unique_ids: Vec<Uuid> = user_ids
+            .iter()
+            .copied()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if unique_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let users = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ANY($1)")
+            .bind(&unique_ids)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(users.into_iter().map(|user| (user.id, user)).collect())
+    }
+
     /// 获取所有用户列表
     ///
     /// 查询系统中的所有用户，按创建时间倒序排列。
@@ -212,12 +372,12 @@ impl UserService {
     ///
     /// # 注意事项
     ///
-    /// 在用户量很大的系统中，这个方法可能需要添加分页功能
-    /// 以避免一次性加载过多数据。
+    /// 该方法会一次性加载整张表，列表接口请使用
+    /// [`UserService::get_users_paginated`]。
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// let users = UserService::get_all_users(&pool).await?;
     /// println!("Total users: {}", users.len());
     ///
@@ -226,10 +386,748 @@ impl UserService {
     /// }
     /// ```
     pub async fn get_all_users(pool: &DbPool) -> Result<Vec<User>> {
-        let users = sqlx::query_as::<_, User>("SELECT * FROM users ORDER BY created_at DESC")
-            .fetch_all(pool)
+        Self::repository(pool).find_all().await
+    }
+
+    /// 分页获取用户列表
+    ///
+    /// 按创建时间倒序分页查询用户，同时返回用户总数，
+    /// 避免在用户量较大时一次性加载整张表。
+    ///
+    /// # 参数
+    ///
+    /// * `pool` - 数据库连接池
+    /// * `limit` - 每页条数
+    /// * `offset` - 跳过的记录数
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<(Vec<User>, i64)>`，分别为当前页用户和用户总数
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Database`: 数据库操作失败
+    ///
+    /// # 示例
+    ///
+    /// ```rust,ignore
+    /// let (users, total) = UserService::get_users_paginated(&pool, 20, 0).await?;
+    /// println!("Page size: {}, total users: {}", users.len(), total);
+    /// ```
+    pub async fn get_users_paginated(
+        pool: &DbPool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<User>, i64)> {
+        let repository = Self::repository(pool);
+        let users = repository.list(limit, offset).await?;
+        let total = repository.count().await?;
+
+        Ok((users, total))
+    }
+
+    /// 修改用户密码
+    ///
+    /// 验证当前密码后，将新密码哈希并写入数据库，同时更新 `updated_at`。
+    ///
+    /// # 业务规则
+    ///
+    /// 1. 使用 Argon2 验证当前密码
+    /// 2. 新密码强度不能为弱（参见 `CryptoUtils::check_password_strength`）
+    /// 3. 更新密码哈希和更新时间
+    ///
+    /// # 参数
+    ///
+    /// * `pool` - 数据库连接池
+    /// * `user_id` - 用户唯一标识符
+    /// * `old_password` - 当前密码
+    /// * `new_password` - 新密码
+    /// * `policy` - 密码策略（哈希参数和泄露检查）
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::NotFound`: 用户不存在
+    /// - `AppError::Authentication`: 当前密码错误
+    /// - `AppError::Validation`: 新密码强度不足或出现在已知的数据泄露中
+    /// - `AppError::PasswordHash`: 密码哈希失败
+    /// - `AppError::Database`: 数据库操作失败
+    ///
+    /// # 示例
+    ///
+    /// ```rust,ignore
+    /// UserService::change_password(
+    ///     &pool,
+    ///     user_id,
+    ///     "oldPassword123",
+    ///     "N3w-Secure-Passw0rd",
+    ///     PasswordPolicy::from(&config),
+    /// )
+    /// .await?;
+    /// ```
+    pub async fn change_password(
+        pool: &DbPool,
+        user_id: Uuid,
+        old_password: &str,
+        new_password: &str,
+        policy: PasswordPolicy,
+    ) -> Result<()> {
+        let user = Self::get_user_by_id(pool, user_id).await?;
+
+        // 验证当前密码
+        if !verify_password(old_password, &user.password_hash)? {
+            return Err(AppError::Authentication("Invalid old password".to_string()));
+        }
+
+        Self::set_password(pool, user_id, new_password, policy).await
+    }
+
+    /// 设置新密码
+    ///
+    /// 不校验当前密码，供修改密码流程在完成身份校验后调用。
+    ///
+    /// # 参数
+    ///
+    /// * `pool` - 数据库连接池
+    /// * `user_id` - 用户唯一标识符
+    /// * `new_password` - 新密码
+    /// * `policy` - 密码策略（哈希参数和泄露检查）
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Validation`: 新密码强度不足或出现在已知的数据泄露中
+    /// - `AppError::NotFound`: 用户不存在
+    /// - `AppError::PasswordHash`: 密码哈希失败
+    /// - `AppError::Database`: 数据库操作失败
+    pub async fn set_password(
+        pool: &DbPool,
+        user_id: Uuid,
+        new_password: &str,
+        policy: PasswordPolicy,
+    ) -> Result<()> {
+        // 检查新密码是否满足策略要求和是否已泄露
+        validate_new_password(new_password, &policy)?;
+        check_password_not_pwned(new_password, &policy).await?;
+
+        Self::store_password(pool, user_id, new_password, &policy.argon2).await
+    }
+
+    /// 哈希并保存新密码，同时刷新 `updated_at`
+    ///
+    /// 不做任何校验，调用方需要先检查新密码（参见 `set_password`）。
+    pub(crate) async fn store_password(
+        pool: &DbPool,
+        user_id: Uuid,
+        new_password: &str,
+        argon2_params: &Argon2Params,
+    ) -> Result<()> {
+        let password_hash = hash_password(new_password, argon2_params)?;
+
+        Self::repository(pool)
+            .update(
+                user_id,
+                &UserChanges {
+                    password_hash: Some(&password_hash),
+                    ..Default::default()
+                },
+            )
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        Ok(())
+    }
+
+    /// 更新用户资料
+    ///
+    /// 对用户资料进行部分更新，只修改请求中提供的字段，并刷新 `updated_at`。
+    ///
+    /// # 业务规则
+    ///
+    /// 1. 修改邮箱时检查新邮箱是否已被其他用户使用
+    /// 2. 未提供的字段保持原值
+    /// 3. 更新时间设置为当前时间
+    ///
+    /// # 参数
+    ///
+    /// * `pool` - 数据库连接池
+    /// * `user_id` - 用户唯一标识符
+    /// * `request` - 更新用户资料请求数据
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<User>`，成功时包含更新后的用户信息
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::NotFound`: 用户不存在
+    /// - `AppError::Conflict`: 邮箱已被其他用户使用
+    /// - `AppError::Database`: 数据库操作失败
+    ///
+    /// # 示例
+    ///
+    /// ```rust,ignore
+    /// let request = UpdateUserRequest {
+    ///     name: Some("李四".to_string()),
+    ///     email: None,
+    /// };
+    ///
+    /// let user = UserService::update_user(&pool, user_id, request).await?;
+    /// println!("Updated user: {}", user.name);
+    /// ```
+    pub async fn update_user(
+        pool: &DbPool,
+        user_id: Uuid,
+        request: UpdateUserRequest,
+    ) -> Result<User> {
+        let email = request.email.as_deref().map(StringUtils::normalize_email);
+
+        // 检查新邮箱是否已被其他用户注册（不区分大小写）
+        if let Some(email) = &email {
+            let existing_user = sqlx::query_as::<_, User>(
+                "SELECT * FROM users WHERE LOWER(email) = $1 AND id <> $2",
+            )
+            .bind(email)
+            .bind(user_id)
+            .fetch_optional(pool)
             .await?;
 
-        Ok(users)
+            if existing_user.is_some() {
+                return Err(AppError::Conflict(
+                    "User with this email already exists".to_string(),
+                ));
+            }
+        }
+
+        // 只更新提供的字段
+        let user = Self::repository(pool)
+            .update(
+                user_id,
+                &UserChanges {
+                    name: request.name.as_deref(),
+                    email: email.as_deref(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(email_conflict_error)?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        Ok(user)
+    }
+}
+
+/// 将邮箱唯一索引冲突转换为 `AppError::Conflict`
+///
+/// 并发注册同一邮箱时，两个请求可能都通过了存在性检查，由数据库唯一索引兜底。
+fn email_conflict_error(err: impl Into<AppError>) -> AppError {
+    match err.into() {
+        AppError::Database(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            AppError::Conflict("User with this email already exists".to_string())
+        }
+        err => err,
+    }
+}
+
+/// 校验用户注册请求
+///
+/// 检查邮箱格式、名称是否为空以及密码是否满足策略要求，
+/// 所有未通过校验的字段以 `AppError::InvalidFields` 一次性返回。
+pub(crate) fn validate_create_user_request(
+    request: &CreateUserRequest,
+    policy: &PasswordPolicy,
+) -> Result<()> {
+    let mut errors = Vec::new();
+
+    if !StringUtils::is_valid_email(request.email.trim()) {
+        errors.push(FieldError::new("email", "Invalid email format"));
+    }
+
+    if request.name.trim().is_empty() {
+        errors.push(FieldError::new("name", "Name must not be blank"));
+    }
+
+    if let Err(message) = check_password_requirements(&request.password, policy) {
+        errors.push(FieldError::new("password", message));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::InvalidFields(errors))
+    }
+}
+
+/// 检查新密码是否满足策略要求
+///
+/// 长度不足或强度低于要求时返回 `AppError::Validation`，错误信息包含改进建议。
+pub(crate) fn validate_new_password(password: &str, policy: &PasswordPolicy) -> Result<()> {
+    check_password_requirements(password, policy)
+        .map_err(|message| AppError::Validation(format!("New password is invalid: {}", message)))
+}
+
+/// 检查密码长度和强度，不满足要求时返回错误原因
+fn check_password_requirements(
+    password: &str,
+    policy: &PasswordPolicy,
+) -> std::result::Result<(), String> {
+    if password.chars().count() < policy.min_length {
+        return Err(format!(
+            "Password must be at least {} characters",
+            policy.min_length
+        ));
+    }
+
+    let strength = CryptoUtils::check_password_strength(password);
+    if strength.level < policy.min_strength {
+        return Err(format!(
+            "Password is too weak: {}",
+            strength.feedback.join("; ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// 检查密码是否出现在已知的数据泄露中
+///
+/// 只有启用 `hibp` feature 且配置了 `pwned_threshold` 时才会访问 Have I Been Pwned API。
+/// 查询失败时记录警告并放行，避免外部服务不可用导致用户无法注册或修改密码。
+pub(crate) async fn check_password_not_pwned(
+    password: &str,
+    policy: &PasswordPolicy,
+) -> Result<()> {
+    #[cfg(feature = "hibp")]
+    if let Some(threshold) = policy.pwned_threshold {
+        match CryptoUtils::check_password_pwned(password).await {
+            Ok(Some(count)) if count > threshold => {
+                return Err(AppError::Validation(
+                    "This password has appeared in a data breach, please choose a different one"
+                        .to_string(),
+                ));
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("泄露密码检查失败，已跳过: {}", e),
+        }
+    }
+
+    #[cfg(not(feature = "hibp"))]
+    let _ = (password, policy);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support;
+
+    /// 创建测试用户
+    async fn create_test_user(pool: &DbPool, name: &str) -> User {
+        UserService::create_user(
+            pool,
+            CreateUserRequest {
+                email: test_support::unique_email(name),
+                password: "N3w-Secure-Passw0rd".to_string(),
+                name: name.to_string(),
+            },
+            PasswordPolicy::default(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_new_password() {
+        let policy = PasswordPolicy::default();
+        assert!(matches!(
+            validate_new_password("abc", &policy),
+            Err(AppError::Validation(_))
+        ));
+        assert!(validate_new_password("N3w-Secure-Passw0rd", &policy).is_ok());
+
+        // 最低强度等级可配置
+        let strict = PasswordPolicy {
+            min_strength: StrengthLevel::Strong,
+            ..policy
+        };
+        assert!(validate_new_password("abcdefgh1A", &policy).is_ok());
+        assert!(validate_new_password("abcdefgh1A", &strict).is_err());
+    }
+
+    fn registration(email: &str, password: &str, name: &str) -> CreateUserRequest {
+        CreateUserRequest {
+            email: email.to_string(),
+            password: password.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    fn invalid_fields(result: Result<()>) -> Vec<String> {
+        match result {
+            Err(AppError::InvalidFields(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            other => panic!("expected InvalidFields, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_create_user_request() {
+        let policy = PasswordPolicy::default();
+        assert!(validate_create_user_request(
+            &registration("user@example.com", "N3w-Secure-Passw0rd", "张三"),
+            &policy
+        )
+        .is_ok());
+
+        // 邮箱格式错误
+        assert_eq!(
+            invalid_fields(validate_create_user_request(
+                &registration("not-an-email", "N3w-Secure-Passw0rd", "张三"),
+                &policy
+            )),
+            ["email"]
+        );
+
+        // 名称为空
+        assert_eq!(
+            invalid_fields(validate_create_user_request(
+                &registration("user@example.com", "N3w-Secure-Passw0rd", "   "),
+                &policy
+            )),
+            ["name"]
+        );
+
+        // 弱密码，以及长度不足
+        assert_eq!(
+            invalid_fields(validate_create_user_request(
+                &registration("user@example.com", "password", "张三"),
+                &policy
+            )),
+            ["password"]
+        );
+        let long_policy = PasswordPolicy {
+            min_length: 24,
+            ..policy
+        };
+        assert_eq!(
+            invalid_fields(validate_create_user_request(
+                &registration("user@example.com", "N3w-Secure-Passw0rd", "张三"),
+                &long_policy
+            )),
+            ["password"]
+        );
+
+        // 多个字段同时出错时全部返回
+        assert_eq!(
+            invalid_fields(validate_create_user_request(
+                &registration("bad", "abc", ""),
+                &policy
+            )),
+            ["email", "name", "password"]
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_updates_touch_updated_at_but_not_created_at() {
+        let pool = test_support::pool().await;
+        let user = create_test_user(&pool, "touch").await;
+
+        let updated = UserService::update_user(
+            &pool,
+            user.id,
+            UpdateUserRequest {
+                name: Some("touched".to_string()),
+                email: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(updated.updated_at > user.updated_at);
+        assert_eq!(updated.created_at, user.created_at);
+
+        // 没有显式设置 updated_at 的 UPDATE 由触发器刷新，RETURNING * 返回新值
+        let touched = sqlx::query_as::<_, User>(
+            "UPDATE users SET name = 'touched again' WHERE id = $1 RETURNING *",
+        )
+        .bind(user.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(touched.updated_at > updated.updated_at);
+        assert_eq!(touched.created_at, user.created_at);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_update_user_partial() {
+        let pool = test_support::pool().await;
+        let user = create_test_user(&pool, "partial").await;
+
+        // 只修改名称，邮箱保持不变
+        let updated = UserService::update_user(
+            &pool,
+            user.id,
+            UpdateUserRequest {
+                name: Some("新名称".to_string()),
+                email: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.name, "新名称");
+        assert_eq!(updated.email, user.email);
+        assert!(updated.updated_at > user.updated_at);
+
+        // 只修改邮箱，名称保持不变
+        let new_email = test_support::unique_email("partial-new");
+        let updated = UserService::update_user(
+            &pool,
+            user.id,
+            UpdateUserRequest {
+                name: None,
+                email: Some(new_email.clone()),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.name, "新名称");
+        assert_eq!(updated.email, new_email);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_get_users_by_ids() {
+        let pool = test_support::pool().await;
+        let first = create_test_user(&pool, "bulk-first").await;
+        let second = create_test_user(&pool, "bulk-second").await;
+        let missing = Uuid::new_v4();
+
+        let users = UserService::get_users_by_ids(&pool, &[first.id, missing, second.id, first.id])
+            .await
+            .unwrap();
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[&first.id].email, first.email);
+        assert_eq!(users[&second.id].email, second.email);
+        assert!(!users.contains_key(&missing));
+
+        assert!(UserService::get_users_by_ids(&pool, &[])
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_update_user_duplicate_email() {
+        let pool = test_support::pool().await;
+        let user = create_test_user(&pool, "first").await;
+        let other = create_test_user(&pool, "second").await;
+
+        let result = UserService::update_user(
+            &pool,
+            user.id,
+            UpdateUserRequest {
+                name: None,
+                email: Some(other.email.clone()),
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        // 保留自己的邮箱不算冲突
+        assert!(UserService::update_user(
+            &pool,
+            user.id,
+            UpdateUserRequest {
+                name: None,
+                email: Some(user.email.clone()),
+            },
+        )
+        .await
+        .is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_email_is_case_insensitive() {
+        let pool = test_support::pool().await;
+        let email = test_support::unique_email("Case.Mixed");
+        let password = "N3w-Secure-Passw0rd";
+
+        let user = UserService::create_user(
+            &pool,
+            CreateUserRequest {
+                email: email.to_uppercase(),
+                password: password.to_string(),
+                name: "case".to_string(),
+            },
+            PasswordPolicy::default(),
+        )
+        .await
+        .unwrap();
+        // 邮箱以小写形式存储
+        assert_eq!(user.email, email.to_lowercase());
+
+        // 仅大小写不同的邮箱视为同一邮箱
+        let result = UserService::create_user(
+            &pool,
+            CreateUserRequest {
+                email: email.to_lowercase(),
+                password: password.to_string(),
+                name: "case".to_string(),
+            },
+            PasswordPolicy::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        // 登录时邮箱不区分大小写
+        for login_email in [email.to_uppercase(), email.to_lowercase(), email.clone()] {
+            let authenticated = UserService::authenticate_user(
+                &pool,
+                LoginRequest {
+                    email: login_email,
+                    password: password.to_string(),
+                },
+                &Argon2Params::default(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(authenticated.id, user.id);
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_unique_violation_maps_to_conflict() {
+        let pool = test_support::pool().await;
+        let user = create_test_user(&pool, "unique").await;
+
+        // 绕过存在性检查直接插入，仅大小写不同的邮箱同样命中 lower(email) 唯一索引
+        let err = sqlx::query("INSERT INTO users (email, password_hash, name) VALUES ($1, $2, $3)")
+            .bind(user.email.to_uppercase())
+            .bind(&user.password_hash)
+            .bind("unique")
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        assert!(matches!(email_conflict_error(err), AppError::Conflict(_)));
+
+        // 其他数据库错误保持原样
+        let err = sqlx::query("SELECT * FROM missing_table")
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        assert!(matches!(email_conflict_error(err), AppError::Database(_)));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_concurrent_registration_returns_conflict() {
+        let pool = test_support::pool().await;
+        let email = test_support::unique_email("race");
+        let request = || CreateUserRequest {
+            email: email.clone(),
+            password: "N3w-Secure-Passw0rd".to_string(),
+            name: "race".to_string(),
+        };
+
+        let (first, second) = tokio::join!(
+            UserService::create_user(&pool, request(), PasswordPolicy::default()),
+            UserService::create_user(&pool, request(), PasswordPolicy::default()),
+        );
+        // 恰好一个请求成功，另一个返回 409 而不是 500
+        let results = [first, second];
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(results
+            .iter()
+            .any(|r| matches!(r, Err(AppError::Conflict(_)))));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_authenticate_user_rehashes_outdated_hash() {
+        let pool = test_support::pool().await;
+        let low_cost = Argon2Params {
+            memory_kib: 8,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let user = UserService::create_user(
+            &pool,
+            CreateUserRequest {
+                email: test_support::unique_email("rehash"),
+                password: "N3w-Secure-Passw0rd".to_string(),
+                name: "rehash".to_string(),
+            },
+            PasswordPolicy {
+                argon2: low_cost,
+                ..PasswordPolicy::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(!needs_rehash(&user.password_hash, &low_cost));
+
+        // 使用更高成本的配置登录后，存储的哈希升级为新参数
+        let high_cost = Argon2Params::default();
+        let authenticated = UserService::authenticate_user(
+            &pool,
+            LoginRequest {
+                email: user.email.clone(),
+                password: "N3w-Secure-Passw0rd".to_string(),
+            },
+            &high_cost,
+        )
+        .await
+        .unwrap();
+
+        let stored = UserService::get_user_by_id(&pool, user.id).await.unwrap();
+        assert_ne!(stored.password_hash, user.password_hash);
+        assert_eq!(stored.password_hash, authenticated.password_hash);
+        assert!(!needs_rehash(&stored.password_hash, &high_cost));
+        assert!(verify_password("N3w-Secure-Passw0rd", &stored.password_hash).unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running PostgreSQL instance"]
+    async fn test_authenticate_user_upgrades_bcrypt_hash() {
+        let pool = test_support::pool().await;
+        let user = UserService::create_user(
+            &pool,
+            CreateUserRequest {
+                email: test_support::unique_email("bcrypt"),
+                password: "N3w-Secure-Passw0rd".to_string(),
+                name: "bcrypt".to_string(),
+            },
+            PasswordPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        // 模拟从旧系统迁移的 bcrypt 哈希（Openwall crypt_blowfish 测试向量，密码为 "U*U"）
+        let bcrypt_hash = "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW";
+        sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+            .bind(bcrypt_hash)
+            .bind(user.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let params = Argon2Params::default();
+        UserService::authenticate_user(
+            &pool,
+            LoginRequest {
+                email: user.email.clone(),
+                password: "U*U".to_string(),
+            },
+            &params,
+        )
+        .await
+        .unwrap();
+
+        let stored = UserService::get_user_by_id(&pool, user.id).await.unwrap();
+        assert!(stored.password_hash.starts_with("$argon2id$"));
+        assert!(!needs_rehash(&stored.password_hash, &params));
+        assert!(verify_password("U*U", &stored.password_hash).unwrap());
     }
 }
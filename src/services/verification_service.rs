@@ -0,0 +1,272 @@
+/*!
+ * 邮箱验证服务
+ *
+ * 负责生成、存储和校验邮箱验证码，并在验证成功后更新用户的
+ * `email_verified` 状态。验证码存储在 Redis 中，过期后自动失效。
+ */
+
+use rand::Rng;
+
+use crate::{
+    db::DbPool,
+    error::{AppError, Result},
+    models::User,
+    redis::{RedisManager, RedisUtils},
+    services::EmailSender,
+    utils::{CacheHelper, StringUtils},
+};
+
+/// 邮箱验证服务
+///
+/// 采用静态方法设计，与 `UserService`、`TokenService` 保持一致。
+pub struct VerificationService;
+
+impl VerificationService {
+    /// 验证码有效期（15 分钟）
+    pub const CODE_EXPIRY_SECONDS: u64 = 15 * 60;
+
+    /// 验证码位数
+    pub const CODE_LENGTH: usize = 6;
+
+    /// 生成并存储邮箱验证码
+    ///
+    /// 为未验证邮箱的用户生成新的验证码并通过 `sender` 发送到该邮箱，
+    /// 重复调用会覆盖之前的验证码。
+    ///
+    /// # 参数
+    ///
+    /// * `pool` - 数据库连接池
+    /// * `redis` - Redis 管理器
+    /// * `sender` - 邮件发送器
+    /// * `email` - 用户邮箱
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Option<String>>`：邮箱对应的用户存在且未验证时返回生成的验证码，
+    /// 否则返回 `None`（不向调用方暴露用户是否存在）
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Database`: 数据库操作失败
+    /// - `AppError::Internal`: Redis 操作或邮件发送失败
+    pub async fn send_verification_code(
+        pool: &DbPool,
+        redis: &RedisManager,
+        sender: &dyn EmailSender,
+        email: &str,
+    ) -> Result<Option<String>> {
+        Self::issue_code(pool, redis, sender, email, Self::CODE_EXPIRY_SECONDS).await
+    }
+
+    /// 校验验证码并标记邮箱为已验证
+    ///
+    /// 验证码只能使用一次，校验成功后立即删除。
+    ///
+    /// # 参数
+    ///
+    /// * `pool` - 数据库连接池
+    /// * `redis` - Redis 管理器
+    /// * `email` - 用户邮箱
+    /// * `code` - 用户提交的验证码
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<User>`，成功时包含更新后的用户信息
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Validation`: 验证码错误或已过期
+    /// - `AppError::NotFound`: 用户不存在
+    /// - `AppError::Database`: 数据库操作失败
+    pub async fn verify_email(
+        pool: &DbPool,
+        redis: &RedisManager,
+        email: &str,
+        code: &str,
+    ) -> Result<User> {
+        let cache = CacheHelper::new(RedisUtils::new(redis.clone()));
+        if !cache
+            .verify_and_consume_code(&Self::identifier(email), code)
+            .await?
+        {
+            return Err(AppError::Validation(
+                "Invalid or expired verification code".to_string(),
+            ));
+        }
+
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET email_verified = TRUE, updated_at = NOW()
+            WHERE LOWER(email) = $1
+            RETURNING *
+            "#,
+        )
+        .bind(StringUtils::normalize_email(email))
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        Ok(user)
+    }
+
+    /// 生成、存储并发送指定有效期的验证码
+    async fn issue_code(
+        pool: &DbPool,
+        redis: &RedisManager,
+        sender: &dyn EmailSender,
+        email: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<String>> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE LOWER(email) = $1")
+            .bind(StringUtils::normalize_email(email))
+            .fetch_optional(pool)
+            .await?;
+
+        // 用户不存在或已验证时不生成验证码
+        match user {
+            Some(user) if !user.email_verified => {}
+            _ => return Ok(None),
+        }
+
+        let code = generate_code(Self::CODE_LENGTH);
+        let cache = CacheHelper::new(RedisUtils::new(redis.clone()));
+        cache
+            .set_verification_code(&Self::identifier(email), &code, ttl_seconds)
+            .await?;
+
+        sender
+            .send(
+                email,
+                "邮箱验证码",
+                &format!(
+                    "您的邮箱验证码是 {}，{} 分钟内有效且只能使用一次。",
+                    code,
+                    ttl_seconds / 60
+                ),
+            )
+            .await?;
+
+        Ok(Some(code))
+    }
+
+    /// 验证码在缓存中的标识符（邮箱不区分大小写）
+    fn identifier(email: &str) -> String {
+        format!("email:{}", StringUtils::normalize_email(email))
+    }
+}
+
+/// 生成指定位数的数字验证码
+fn generate_code(length: usize) -> String {
+    let mut rng = rand::thread_rng();
+    (0..length)
+        .map(|_| char::from(b'0' + rng.gen_range(0..10)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_support as db_support,
+        models::CreateUserRequest,
+        redis::test_support as redis_support,
+        services::{
+            email_service::test_support::RecordingSender, LogSender, PasswordPolicy, UserService,
+        },
+    };
+
+    async fn create_test_user(pool: &DbPool) -> User {
+        UserService::create_user(
+            pool,
+            CreateUserRequest {
+                email: db_support::unique_email("verify"),
+                password: "N3w-Secure-Passw0rd".to_string(),
+                name: "verify".to_string(),
+            },
+            PasswordPolicy::default(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let code = generate_code(VerificationService::CODE_LENGTH);
+        assert_eq!(code.len(), VerificationService::CODE_LENGTH);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running PostgreSQL and Redis instances"]
+    async fn test_verify_email_happy_path() {
+        let pool = db_support::pool().await;
+        let redis = redis_support::redis_manager().await;
+        let user = create_test_user(&pool).await;
+        assert!(!user.email_verified);
+        let sender = RecordingSender::default();
+
+        let code = VerificationService::send_verification_code(&pool, &redis, &sender, &user.email)
+            .await
+            .unwrap()
+            .unwrap();
+        {
+            // 验证码通过邮件发送给用户
+            let sent = sender.sent.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            assert_eq!(sent[0].0, user.email);
+            assert!(sent[0].2.contains(&code));
+        }
+        let verified = VerificationService::verify_email(&pool, &redis, &user.email, &code)
+            .await
+            .unwrap();
+        assert!(verified.email_verified);
+
+        // 已验证的邮箱不再生成验证码，验证码也不能重复使用
+        assert!(
+            VerificationService::send_verification_code(&pool, &redis, &sender, &user.email)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            VerificationService::verify_email(&pool, &redis, &user.email, &code)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires running PostgreSQL and Redis instances"]
+    async fn test_verify_email_rejects_wrong_or_expired_code() {
+        let pool = db_support::pool().await;
+        let redis = redis_support::redis_manager().await;
+        let user = create_test_user(&pool).await;
+
+        // 错误的验证码
+        let code =
+            VerificationService::send_verification_code(&pool, &redis, &LogSender, &user.email)
+                .await
+                .unwrap()
+                .unwrap();
+        let wrong_code = if code == "000000" { "111111" } else { "000000" };
+        assert!(matches!(
+            VerificationService::verify_email(&pool, &redis, &user.email, wrong_code).await,
+            Err(AppError::Validation(_))
+        ));
+
+        // 过期的验证码
+        let code = VerificationService::issue_code(&pool, &redis, &LogSender, &user.email, 1)
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        assert!(matches!(
+            VerificationService::verify_email(&pool, &redis, &user.email, &code).await,
+            Err(AppError::Validation(_))
+        ));
+
+        let user = UserService::get_user_by_id(&pool, user.id).await.unwrap();
+        assert!(!user.email_verified);
+    }
+}
@@ -0,0 +1,458 @@
+/*!
+ * Webhook 通知服务
+ *
+ * 在用户注册、会话被撤销等关键事件发生时，向配置的 URL 发送 JSON 通知。
+ * 请求体使用 HMAC-SHA256 签名，十六进制签名放在 `X-Signature` 请求头中，
+ * 接收方可以用相同的密钥重新计算签名来校验通知来源。
+ * 投递失败时按指数退避重试，重试耗尽后只记录警告日志，不影响业务请求。
+ */
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+use crate::{
+    config::{Config, WebhookConfig},
+    error::Result,
+    utils::CryptoUtils,
+};
+
+/// 签名请求头
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Signature";
+
+/// 事件类型请求头
+pub const WEBHOOK_EVENT_HEADER: &str = "X-Webhook-Event";
+
+/// 单次投递的超时时间
+const WEBHOOK_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 第一次重试前的等待时间，之后每次翻倍
+const WEBHOOK_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// 重试等待时间的上限，避免重试次数较多时等待时间无限增长
+const WEBHOOK_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// 同时进行（包括等待重试）的投递数上限，超出时丢弃新的通知并记录警告
+const WEBHOOK_MAX_PENDING_DELIVERIES: usize = 100;
+
+/// Webhook 事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    /// 新用户注册
+    #[serde(rename = "user.registered")]
+    UserRegistered,
+    /// 用户的登录会话被撤销
+    #[serde(rename = "session.revoked")]
+    SessionRevoked,
+}
+
+impl WebhookEvent {
+    /// 事件名称，与序列化结果一致
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::UserRegistered => "user.registered",
+            WebhookEvent::SessionRevoked => "session.revoked",
+        }
+    }
+}
+
+/// Webhook 请求体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// 通知 ID，重试时保持不变，接收方可以据此去重
+    pub id: Uuid,
+    /// 事件类型
+    pub event: WebhookEvent,
+    /// 事件发生时间（Unix 时间戳，秒）
+    pub timestamp: i64,
+    /// 事件数据
+    pub data: serde_json::Value,
+}
+
+/// Webhook 通知服务
+///
+/// 未配置 `WEBHOOK_URLS` 时为禁用状态，`notify` 不做任何操作。
+/// 内部的 HTTP 客户端和配置都可以廉价克隆，可直接放在 `AppState` 中。
+#[derive(Debug, Clone)]
+pub struct WebhookService {
+    client: reqwest::Client,
+    config: Option<Arc<WebhookConfig>>,
+    initial_backoff: Duration,
+    deliveries: Arc<Semaphore>,
+}
+
+impl WebhookService {
+    /// 根据 Webhook 配置创建服务
+    pub fn new(config: WebhookConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config: Some(Arc::new(config)),
+            initial_backoff: WEBHOOK_INITIAL_BACKOFF,
+            deliveries: Arc::new(Semaphore::new(WEBHOOK_MAX_PENDING_DELIVERIES)),
+        }
+    }
+
+    /// 创建不发送任何通知的服务
+    pub fn disabled() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config: None,
+            initial_backoff: WEBHOOK_INITIAL_BACKOFF,
+            deliveries: Arc::new(Semaphore::new(WEBHOOK_MAX_PENDING_DELIVERIES)),
+        }
+    }
+
+    /// 根据应用配置创建服务，未配置 Webhook 时返回禁用的服务
+    pub fn from_config(config: &Config) -> Self {
+        config
+            .webhook
+            .clone()
+            .map_or_else(Self::disabled, Self::new)
+    }
+
+    /// 是否配置了接收通知的 URL
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    /// 事件数据中是否可以包含邮箱、姓名等个人信息（`WEBHOOK_INCLUDE_PII`）
+    pub fn include_pii(&self) -> bool {
+        self.config
+            .as_ref()
+            .is_some_and(|config| config.include_pii)
+    }
+
+    /// 在后台向所有配置的 URL 发送事件通知
+    ///
+    /// 立即返回，不等待投递完成；投递失败（包括重试耗尽）只记录警告日志。
+    /// 同时进行的投递数达到 `WEBHOOK_MAX_PENDING_DELIVERIES` 时（如接收端长时间不可用），
+    /// 新的通知会被丢弃并记录警告，避免后台任务无限堆积。
+    ///
+    /// # 参数
+    ///
+    /// * `event` - 事件类型
+    /// * `data` - 事件数据
+    pub fn notify(&self, event: WebhookEvent, data: serde_json::Value) {
+        let Some(config) = &self.config else {
+            return;
+        };
+
+        let payload = WebhookPayload {
+            id: Uuid::new_v4(),
+            event,
+            timestamp: Utc::now().timestamp(),
+            data,
+        };
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!("序列化 Webhook 事件失败 (event: {}): {}", event.as_str(), e);
+                return;
+            }
+        };
+
+        let id = payload.id;
+        for url in &config.urls {
+            let Ok(permit) = self.deliveries.clone().try_acquire_owned() else {
+                tracing::warn!(
+                    "Webhook 投递任务过多，已丢弃通知 (url: {}, event: {}, id: {})",
+                    url,
+                    event.as_str(),
+                    id
+                );
+                continue;
+            };
+
+            let service = self.clone();
+            let url = url.clone();
+            let body = body.clone();
+            tokio::spawn(async move {
+                let _permit = permit;
+                if let Err(e) = service.deliver(&url, event, &body).await {
+                    tracing::warn!(
+                        "Webhook 投递失败 (url: {}, event: {}, id: {}): {}",
+                        url,
+                        event.as_str(),
+                        id,
+                        e
+                    );
+                }
+            });
+        }
+    }
+
+    /// 计算请求体的签名（十六进制 HMAC-SHA256）
+    pub fn sign(secret: &str, body: &[u8]) -> String {
+        CryptoUtils::hmac_sha256_hex(secret.as_bytes(), body)
+    }
+
+    /// 校验 `X-Signature` 请求头中的签名（恒定时间比较）
+    pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+        CryptoUtils::hex_decode(signature)
+            .map(|signature| CryptoUtils::verify_hmac_sha256(secret.as_bytes(), body, &signature))
+            .unwrap_or(false)
+    }
+
+    /// 向单个 URL 投递通知，失败时按指数退避重试
+    ///
+    /// 网络错误和非 2xx 响应都视为失败。重试等待时间每次翻倍，最长 `WEBHOOK_MAX_BACKOFF`。
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Internal`: 重试耗尽后仍然失败
+    async fn deliver(&self, url: &str, event: WebhookEvent, body: &[u8]) -> Result<()> {
+        let Some(config) = &self.config else {
+            return Ok(());
+        };
+        let signature = Self::sign(&config.secret, body);
+
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .client
+                .post(url)
+                .timeout(WEBHOOK_REQUEST_TIMEOUT)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(WEBHOOK_EVENT_HEADER, event.as_str())
+                .header(WEBHOOK_SIGNATURE_HEADER, &signature)
+                .body(body.to_vec())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            match result {
+                Ok(_) => return Ok(()),
+                Err(e) if attempt >= config.max_retries => {
+                    return Err(anyhow::anyhow!(
+                        "Webhook delivery failed after {} attempts: {}",
+                        attempt + 1,
+                        e
+                    )
+                    .into());
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        "Webhook 投递失败，{:?} 后重试 (url: {}, attempt: {}): {}",
+                        backoff,
+                        url,
+                        attempt + 1,
+                        e
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = next_backoff(backoff);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// 下一次重试前的等待时间：翻倍，但不超过 `WEBHOOK_MAX_BACKOFF`
+fn next_backoff(backoff: Duration) -> Duration {
+    backoff.saturating_mul(2).min(WEBHOOK_MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Bytes,
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Router,
+    };
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::sync::mpsc;
+
+    const SECRET: &str = "webhook-test-secret";
+
+    /// 收到的通知 `(事件类型请求头, 签名请求头, 请求体)`
+    type Received = (String, String, Bytes);
+
+    #[derive(Clone)]
+    struct MockState {
+        received: mpsc::UnboundedSender<Received>,
+        /// 前几次请求返回 500
+        failures_left: Arc<AtomicU32>,
+    }
+
+    async fn receive(
+        State(state): State<MockState>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+        state
+            .received
+            .send((
+                header(WEBHOOK_EVENT_HEADER),
+                header(WEBHOOK_SIGNATURE_HEADER),
+                body,
+            ))
+            .unwrap();
+
+        let fail = state
+            .failures_left
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if fail {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            StatusCode::NO_CONTENT
+        }
+    }
+
+    /// 启动模拟的 Webhook 接收端，返回接收地址和收到的通知
+    async fn mock_server(failures: u32) -> (String, mpsc::UnboundedReceiver<Received>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(MockState {
+                received: tx,
+                failures_left: Arc::new(AtomicU32::new(failures)),
+            });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{}/hook", addr), rx)
+    }
+
+    fn service(url: &str, max_retries: u32) -> WebhookService {
+        WebhookService {
+            initial_backoff: Duration::from_millis(10),
+            ..WebhookService::new(WebhookConfig {
+                urls: vec![url.to_string()],
+                secret: SECRET.to_string(),
+                max_retries,
+                include_pii: false,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notify_sends_signed_payload() {
+        let (url, mut rx) = mock_server(0).await;
+        let user_id = Uuid::new_v4();
+
+        service(&url, 0).notify(
+            WebhookEvent::UserRegistered,
+            serde_json::json!({ "user_id": user_id }),
+        );
+
+        let (event, signature, body) = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("webhook should be delivered")
+            .unwrap();
+        assert_eq!(event, "user.registered");
+        assert!(WebhookService::verify_signature(SECRET, &body, &signature));
+        assert!(!WebhookService::verify_signature(
+            "wrong-secret",
+            &body,
+            &signature
+        ));
+
+        let payload: WebhookPayload = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload.event, WebhookEvent::UserRegistered);
+        assert_eq!(payload.data["user_id"], user_id.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_retries_until_success() {
+        let (url, mut rx) = mock_server(2).await;
+        let body = br#"{"event":"session.revoked"}"#;
+
+        service(&url, 3)
+            .deliver(&url, WebhookEvent::SessionRevoked, body)
+            .await
+            .unwrap();
+
+        // 两次失败加一次成功，每次重试的签名都相同
+        for _ in 0..3 {
+            let (_, signature, received) = rx.try_recv().unwrap();
+            assert_eq!(&received[..], body);
+            assert_eq!(signature, WebhookService::sign(SECRET, body));
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deliver_gives_up_after_max_retries() {
+        let (url, mut rx) = mock_server(u32::MAX).await;
+
+        assert!(service(&url, 1)
+            .deliver(&url, WebhookEvent::SessionRevoked, b"{}")
+            .await
+            .is_err());
+
+        // 第一次请求加一次重试
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_notify_drops_deliveries_over_limit() {
+        let (url, mut rx) = mock_server(0).await;
+        let service = WebhookService {
+            deliveries: Arc::new(Semaphore::new(1)),
+            ..WebhookService::new(WebhookConfig {
+                urls: vec![url.clone(), url],
+                secret: SECRET.to_string(),
+                max_retries: 0,
+                include_pii: false,
+            })
+        };
+
+        // 只有一个投递名额，第二个 URL 的通知被丢弃
+        service.notify(WebhookEvent::SessionRevoked, serde_json::json!({}));
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("webhook should be delivered")
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(rx.try_recv().is_err());
+
+        // 投递完成后名额被释放
+        assert_eq!(service.deliveries.available_permits(), 1);
+    }
+
+    #[test]
+    fn test_next_backoff_is_capped() {
+        assert_eq!(next_backoff(Duration::from_secs(1)), Duration::from_secs(2));
+        assert_eq!(next_backoff(Duration::from_secs(40)), WEBHOOK_MAX_BACKOFF);
+        assert_eq!(next_backoff(WEBHOOK_MAX_BACKOFF), WEBHOOK_MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_include_pii_is_opt_in() {
+        let config = |include_pii| WebhookConfig {
+            urls: vec!["http://127.0.0.1/hook".to_string()],
+            secret: SECRET.to_string(),
+            max_retries: 0,
+            include_pii,
+        };
+        assert!(!WebhookService::new(config(false)).include_pii());
+        assert!(WebhookService::new(config(true)).include_pii());
+        assert!(!WebhookService::disabled().include_pii());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_service_does_nothing() {
+        let service = WebhookService::disabled();
+        assert!(!service.is_enabled());
+        service.notify(WebhookEvent::UserRegistered, serde_json::json!({}));
+    }
+}
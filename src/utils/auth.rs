@@ -6,11 +6,199 @@
  */
 
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::error::{AppError, Result};
+use crate::{
+    config::{Config, JwtAlgorithm},
+    error::{AppError, Result},
+    utils::CryptoUtils,
+};
+
+/// JWT 签名和验证密钥
+///
+/// 根据签名算法封装编码密钥和解码密钥：
+/// - `HS256`: 使用同一个共享密钥签名和验证
+/// - `RS256`: 使用 RSA 私钥签名、公钥验证，其他服务只需公钥即可验证 Token
+///
+/// 同时保存验证时允许的时钟偏差，默认为 `DEFAULT_LEEWAY_SECONDS`。
+///
+/// # 密钥轮换
+///
+/// 签发的 Token 在头部 `kid` 中记录主密钥的密钥 ID。HS256 可以通过
+/// `with_secondary_secrets` 添加只用于验证的旧密钥：更换 `JWT_SECRET` 后把旧密钥
+/// 放入 `JWT_SECONDARY_SECRETS`，旧 Token 在过期前仍然有效，之后再移除旧密钥。
+#[derive(Clone)]
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    /// 主密钥的密钥 ID
+    kid: String,
+    /// 只用于验证的备用密钥及其密钥 ID
+    secondary_keys: Vec<(String, DecodingKey)>,
+    leeway_seconds: u64,
+}
+
+impl JwtKeys {
+    /// 默认允许的时钟偏差（秒），与 `jsonwebtoken` 的默认值一致
+    pub const DEFAULT_LEEWAY_SECONDS: u64 = 60;
+
+    /// 使用共享密钥创建 HS256 密钥
+    ///
+    /// # 参数
+    ///
+    /// * `secret` - JWT 签名密钥
+    pub fn hs256(secret: &str) -> Self {
+        Self {
+            algorithm: Algorithm::HS256,
+            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            kid: key_id(secret.as_bytes()),
+            secondary_keys: Vec::new(),
+            leeway_seconds: Self::DEFAULT_LEEWAY_SECONDS,
+        }
+    }
+
+    /// 使用 PEM 格式的 RSA 密钥对创建 RS256 密钥
+    ///
+    /// # 参数
+    ///
+    /// * `private_key_pem` - PEM 格式的 RSA 私钥（PKCS#1 或 PKCS#8）
+    /// * `public_key_pem` - PEM 格式的 RSA 公钥
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Jwt`: PEM 格式无效
+    pub fn rs256_from_pem(private_key_pem: &[u8], public_key_pem: &[u8]) -> Result<Self> {
+        Ok(Self {
+            algorithm: Algorithm::RS256,
+            encoding_key: EncodingKey::from_rsa_pem(private_key_pem)?,
+            decoding_key: DecodingKey::from_rsa_pem(public_key_pem)?,
+            kid: key_id(public_key_pem),
+            secondary_keys: Vec::new(),
+            leeway_seconds: Self::DEFAULT_LEEWAY_SECONDS,
+        })
+    }
+
+    /// 添加只用于验证的 HS256 备用密钥
+    ///
+    /// 用于密钥轮换期间继续接受旧密钥签发的 Token。备用密钥只在 HS256 下生效，
+    /// RS256 密钥会忽略该设置（验证时只接受与主密钥算法一致的 Token）。
+    ///
+    /// # 参数
+    ///
+    /// * `secrets` - 旧的 JWT 签名密钥列表
+    pub fn with_secondary_secrets(mut self, secrets: &[String]) -> Self {
+        if self.algorithm != Algorithm::HS256 {
+            return self;
+        }
+
+        self.secondary_keys = secrets
+            .iter()
+            .map(|secret| {
+                (
+                    key_id(secret.as_bytes()),
+                    DecodingKey::from_secret(secret.as_bytes()),
+                )
+            })
+            .collect();
+        self
+    }
+
+    /// 设置验证时允许的时钟偏差
+    ///
+    /// # 参数
+    ///
+    /// * `leeway_seconds` - 允许的时钟偏差秒数，0 表示严格校验
+    pub fn with_leeway(mut self, leeway_seconds: u64) -> Self {
+        self.leeway_seconds = leeway_seconds;
+        self
+    }
+
+    /// 根据应用配置创建密钥
+    ///
+    /// `HS256` 使用 `jwt_secret`；`RS256` 从 `jwt_private_key_path` 和
+    /// `jwt_public_key_path` 读取 PEM 文件。时钟偏差取自 `jwt_leeway_seconds`，
+    /// HS256 的备用验证密钥取自 `jwt_secondary_secrets`。
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Internal`: RS256 缺少密钥路径或读取文件失败
+    /// - `AppError::Jwt`: PEM 格式无效
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let keys = match config.jwt_algorithm {
+            JwtAlgorithm::HS256 => Self::hs256(&config.jwt_secret)
+                .with_secondary_secrets(&config.jwt_secondary_secrets),
+            JwtAlgorithm::RS256 => {
+                if !config.jwt_secondary_secrets.is_empty() {
+                    tracing::warn!("JWT_SECONDARY_SECRETS is ignored when using RS256");
+                }
+                let private_key = read_key_file(
+                    config.jwt_private_key_path.as_deref(),
+                    "JWT_PRIVATE_KEY_PATH",
+                )?;
+                let public_key =
+                    read_key_file(config.jwt_public_key_path.as_deref(), "JWT_PUBLIC_KEY_PATH")?;
+                Self::rs256_from_pem(&private_key, &public_key)?
+            }
+        };
+
+        Ok(keys.with_leeway(config.jwt_leeway_seconds))
+    }
+
+    /// 获取签名算法
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// 获取验证时允许的时钟偏差（秒）
+    pub fn leeway_seconds(&self) -> u64 {
+        self.leeway_seconds
+    }
+
+    /// 获取主密钥的密钥 ID（签发 Token 时写入头部 `kid`）
+    pub fn kid(&self) -> &str {
+        &self.kid
+    }
+
+    /// 按尝试顺序返回验证密钥
+    ///
+    /// `kid` 与某个已知密钥匹配时只返回该密钥；否则（旧 Token 没有 `kid`
+    /// 或密钥 ID 未知）依次返回主密钥和所有备用密钥。
+    fn verification_keys(&self, kid: Option<&str>) -> Vec<&DecodingKey> {
+        let keys = std::iter::once((&self.kid, &self.decoding_key))
+            .chain(self.secondary_keys.iter().map(|(kid, key)| (kid, key)));
+
+        if let Some(kid) = kid {
+            if let Some((_, key)) = keys.clone().find(|(key_id, _)| key_id.as_str() == kid) {
+                return vec![key];
+            }
+        }
+
+        keys.map(|(_, key)| key).collect()
+    }
+}
+
+/// 根据密钥材料计算密钥 ID
+///
+/// 取 SHA-256 摘要的前 16 个十六进制字符，同一密钥在不同实例和重启后保持一致，
+/// 且不会在 Token 头部暴露密钥本身。
+fn key_id(key_material: &[u8]) -> String {
+    CryptoUtils::sha256_hex(key_material)[..16].to_string()
+}
+
+/// 读取 PEM 密钥文件
+fn read_key_file(path: Option<&str>, env_name: &str) -> Result<Vec<u8>> {
+    let path = path
+        .ok_or_else(|| AppError::Internal(anyhow::anyhow!("{} is required for RS256", env_name)))?;
+
+    std::fs::read(path)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to read key file {}: {}", path, e)))
+}
 
 /// JWT Token 声明 (Claims)
 ///
@@ -22,6 +210,15 @@ use crate::error::{AppError, Result};
 /// - `sub` (Subject): 主题，这里用于存储用户 ID
 /// - `exp` (Expiration): 过期时间戳
 /// - `iat` (Issued At): 发行时间戳
+/// - `jti` (JWT ID): Token 唯一标识，保证同一秒内签发的 Token 互不相同
+///
+/// `TokenService` 以 Token 指纹作为会话的键名，如果没有 `jti`，同一用户在同一秒内
+/// 的两次登录会得到完全相同的 Token，共用一个会话记录，会话数上限和按会话撤销都会失效。
+///
+/// # 自定义声明字段
+///
+/// - `email`: 用户邮箱（可选，旧 Token 中不存在该字段）
+/// - `role`: 用户角色（可选，旧 Token 中不存在该字段）
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     /// 用户 ID (Subject)
@@ -32,17 +229,29 @@ pub struct Claims {
 
     /// 发行时间戳 (Issued At)
     pub iat: i64,
+
+    /// Token 唯一标识 (JWT ID)
+    #[serde(default)]
+    pub jti: String,
+
+    /// 用户邮箱
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+
+    /// 用户角色
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
 }
 
 impl Claims {
     /// 创建新的 JWT 声明
     ///
-    /// 基于用户 ID 创建 JWT 声明，自动设置发行时间和过期时间。
+    /// 基于用户身份创建 JWT 声明，自动设置发行时间和过期时间。
     /// Token 的有效期为 24 小时。
     ///
     /// # 参数
     ///
-    /// * `user_id` - 用户唯一标识符
+    /// * `user` - 用户身份（ID、邮箱和角色）
     ///
     /// # 返回值
     ///
@@ -52,32 +261,105 @@ impl Claims {
     ///
     /// ```rust
     /// use uuid::Uuid;
-    /// use crate::utils::auth::Claims;
+    /// use hello_rust::utils::auth::{AuthUser, Claims};
     ///
-    /// let user_id = Uuid::new_v4();
-    /// let claims = Claims::new(user_id);
+    /// let user = AuthUser::new(Uuid::new_v4());
+    /// let claims = Claims::new(&user);
     /// println!("Token will expire at: {}", claims.exp);
     /// ```
-    pub fn new(user_id: Uuid) -> Self {
+    pub fn new(user: &AuthUser) -> Self {
+        Self::with_expiry(user, Duration::hours(24)) // Token 24小时后过期
+    }
+
+    /// 创建指定有效期的 JWT 声明
+    ///
+    /// 用于签发短期访问令牌等需要自定义有效期的场景。
+    ///
+    /// # 参数
+    ///
+    /// * `user` - 用户身份（ID、邮箱和角色）
+    /// * `expires_in` - Token 有效期
+    ///
+    /// # 返回值
+    ///
+    /// 返回包含用户信息和时间戳的 Claims 结构体
+    pub fn with_expiry(user: &AuthUser, expires_in: Duration) -> Self {
         let now = Utc::now();
-        let exp = now + Duration::hours(24); // Token 24小时后过期
+        let exp = now + expires_in;
 
         Claims {
-            sub: user_id.to_string(),
+            sub: user.id.to_string(),
             exp: exp.timestamp(),
             iat: now.timestamp(),
+            jti: Uuid::new_v4().to_string(),
+            email: user.email.clone(),
+            role: user.role.clone(),
         }
     }
 }
 
+/// 已认证用户
+///
+/// 由身份验证中间件从 JWT 声明中解析，并注入到请求扩展中。
+/// 旧 Token 中没有 `email`/`role` 字段时，对应字段为 `None`。
+///
+/// # 示例
+///
+/// ```rust
+/// use axum::Extension;
+/// use hello_rust::utils::AuthUser;
+///
+/// async fn handler(Extension(user): Extension<AuthUser>) {
+///     println!("User {} ({:?})", user.id, user.role);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthUser {
+    /// 用户 ID
+    pub id: Uuid,
+
+    /// 用户邮箱
+    pub email: Option<String>,
+
+    /// 用户角色
+    pub role: Option<String>,
+}
+
+impl AuthUser {
+    /// 创建只包含用户 ID 的身份
+    pub fn new(id: Uuid) -> Self {
+        Self {
+            id,
+            email: None,
+            role: None,
+        }
+    }
+
+    /// 从 JWT 声明中解析用户身份
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Authentication`: `sub` 不是合法的用户 ID
+    pub fn from_claims(claims: &Claims) -> Result<Self> {
+        let id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::Authentication("Invalid user ID in token".to_string()))?;
+
+        Ok(Self {
+            id,
+            email: claims.email.clone(),
+            role: claims.role.clone(),
+        })
+    }
+}
+
 /// 生成 JWT Token
 ///
-/// 使用用户 ID 和密钥生成签名的 JWT Token。
-/// Token 包含用户标识和过期时间信息。
+/// 使用用户身份和密钥生成签名的 JWT Token。
+/// Token 包含用户标识、邮箱、角色和过期时间信息。
 ///
 /// # 参数
 ///
-/// * `user_id` - 用户唯一标识符
+/// * `user` - 用户身份（ID、邮箱和角色）
 /// * `secret` - JWT 签名密钥
 ///
 /// # 返回值
@@ -98,25 +380,57 @@ impl Claims {
 ///
 /// ```rust
 /// use uuid::Uuid;
-/// use crate::utils::auth::generate_jwt;
+/// use hello_rust::utils::auth::{generate_jwt, AuthUser};
 ///
-/// let user_id = Uuid::new_v4();
+/// let user = AuthUser::new(Uuid::new_v4());
 /// let secret = "your-secret-key";
-/// let token = generate_jwt(user_id, secret)?;
+/// let token = generate_jwt(&user, secret)?;
 /// println!("Generated token: {}", token);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
-pub fn generate_jwt(user_id: Uuid, secret: &str) -> Result<String> {
-    // 创建包含用户信息的声明
-    let claims = Claims::new(user_id);
+pub fn generate_jwt(user: &AuthUser, secret: &str) -> Result<String> {
+    encode_jwt(&Claims::new(user), &JwtKeys::hs256(secret))
+}
 
-    // 使用默认的 JWT 头部 (HS256 算法)
-    let header = Header::default();
+/// 生成指定有效期的 JWT Token
+///
+/// 与 `generate_jwt` 相同，但可以自定义 Token 的有效期（秒），
+/// 并使用配置的签名算法和密钥，用于签发访问令牌。
+///
+/// # 参数
+///
+/// * `user` - 用户身份（ID、邮箱和角色）
+/// * `keys` - JWT 签名密钥
+/// * `expires_in_seconds` - Token 有效期（秒）
+///
+/// # 返回值
+///
+/// 返回 `Result<String>`，成功时包含 JWT Token 字符串
+///
+/// # 错误
+///
+/// - `AppError::Jwt`: JWT 编码失败
+pub fn generate_jwt_with_expiry(
+    user: &AuthUser,
+    keys: &JwtKeys,
+    expires_in_seconds: i64,
+) -> Result<String> {
+    let claims = Claims::with_expiry(user, Duration::seconds(expires_in_seconds));
+    encode_jwt(&claims, keys)
+}
 
-    // 创建编码密钥
-    let encoding_key = EncodingKey::from_secret(secret.as_ref());
+/// 使用指定密钥对声明进行签名编码
+///
+/// JWT 头部的算法与密钥的算法保持一致，`kid` 为主密钥的密钥 ID。
+///
+/// # 错误
+///
+/// - `AppError::Jwt`: JWT 编码失败
+pub fn encode_jwt(claims: &Claims, keys: &JwtKeys) -> Result<String> {
+    let mut header = Header::new(keys.algorithm);
+    header.kid = Some(keys.kid.clone());
 
-    // 编码生成 JWT Token
-    encode(&header, &claims, &encoding_key).map_err(AppError::Jwt)
+    encode(&header, claims, &keys.encoding_key).map_err(AppError::Jwt)
 }
 
 /// 验证 JWT Token
@@ -149,7 +463,7 @@ pub fn generate_jwt(user_id: Uuid, secret: &str) -> Result<String> {
 /// # 示例
 ///
 /// ```rust
-/// use crate::utils::auth::verify_jwt;
+/// use hello_rust::utils::auth::verify_jwt;
 ///
 /// let token = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...";
 /// let secret = "your-secret-key";
@@ -163,14 +477,218 @@ pub fn generate_jwt(user_id: Uuid, secret: &str) -> Result<String> {
 /// }
 /// ```
 pub fn verify_jwt(token: &str, secret: &str) -> Result<Claims> {
-    // 创建解码密钥
-    let decoding_key = DecodingKey::from_secret(secret.as_ref());
+    decode_jwt(token, &JwtKeys::hs256(secret))
+}
 
-    // 使用默认验证设置
-    let validation = Validation::default();
+/// 使用指定密钥验证 JWT Token
+///
+/// 只接受与密钥算法一致的 Token，防止算法混淆攻击。
+/// 根据头部 `kid` 选择主密钥或备用密钥，参见 `JwtKeys` 的密钥轮换说明。
+/// `exp` 和 `iat` 的校验都允许 `keys` 中配置的时钟偏差，
+/// 避免服务器之间的细微时间误差在签发或过期边界处导致 401。
+///
+/// # 错误
+///
+/// - `AppError::Jwt`: Token 无效、签名错误或已过期
+/// - `AppError::Jwt`: Token 的发行时间晚于当前时间（超出允许的偏差）
+pub fn decode_jwt(token: &str, keys: &JwtKeys) -> Result<Claims> {
+    let mut validation = Validation::new(keys.algorithm);
+    validation.leeway = keys.leeway_seconds;
+
+    let header = decode_header(token)?;
+
+    // 依次尝试候选密钥，全部失败时返回第一个密钥的错误
+    let mut first_error = None;
+    let claims = keys
+        .verification_keys(header.kid.as_deref())
+        .into_iter()
+        .find_map(|key| match decode::<Claims>(token, key, &validation) {
+            Ok(data) => Some(data.claims),
+            Err(e) => {
+                first_error.get_or_insert(e);
+                None
+            }
+        })
+        .ok_or_else(|| {
+            AppError::Jwt(
+                first_error
+                    .unwrap_or_else(|| jsonwebtoken::errors::ErrorKind::InvalidSignature.into()),
+            )
+        })?;
+
+    // jsonwebtoken 不校验 iat，这里拒绝明显来自"未来"的 Token
+    if claims.iat > Utc::now().timestamp() + keys.leeway_seconds as i64 {
+        return Err(AppError::Jwt(
+            jsonwebtoken::errors::ErrorKind::ImmatureSignature.into(),
+        ));
+    }
+
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RS256_PRIVATE_KEY: &str = include_str!("../../tests/fixtures/jwt/rs256_private.pem");
+    const RS256_PUBLIC_KEY: &str = include_str!("../../tests/fixtures/jwt/rs256_public.pem");
+    const RS256_OTHER_PUBLIC_KEY: &str =
+        include_str!("../../tests/fixtures/jwt/rs256_other_public.pem");
+
+    #[test]
+    fn test_hs256_round_trip() {
+        let user = AuthUser::new(Uuid::new_v4());
+        let token = generate_jwt(&user, "secret").unwrap();
+
+        assert_eq!(
+            verify_jwt(&token, "secret").unwrap().sub,
+            user.id.to_string()
+        );
+        assert!(verify_jwt(&token, "wrong-secret").is_err());
+    }
+
+    #[test]
+    fn test_rs256_round_trip() {
+        let keys =
+            JwtKeys::rs256_from_pem(RS256_PRIVATE_KEY.as_bytes(), RS256_PUBLIC_KEY.as_bytes())
+                .unwrap();
+        let user = AuthUser::new(Uuid::new_v4());
+        let token = generate_jwt_with_expiry(&user, &keys, 60).unwrap();
+
+        // 使用对应公钥验证成功
+        let claims = decode_jwt(&token, &keys).unwrap();
+        assert_eq!(claims.sub, user.id.to_string());
+
+        // 使用其他公钥验证失败
+        let wrong_keys = JwtKeys::rs256_from_pem(
+            RS256_PRIVATE_KEY.as_bytes(),
+            RS256_OTHER_PUBLIC_KEY.as_bytes(),
+        )
+        .unwrap();
+        assert!(decode_jwt(&token, &wrong_keys).is_err());
+
+        // HS256 密钥不能验证 RS256 Token
+        assert!(decode_jwt(&token, &JwtKeys::hs256("secret")).is_err());
+    }
 
-    // 解码并验证 Token
-    decode::<Claims>(token, &decoding_key, &validation)
-        .map(|data| data.claims)
-        .map_err(AppError::Jwt)
+    #[test]
+    fn test_leeway_allows_small_clock_skew() {
+        // 模拟时钟略快的服务器签发的 Token
+        let user = AuthUser::new(Uuid::new_v4());
+        let mut claims = Claims::with_expiry(&user, Duration::minutes(5));
+        claims.iat += 10;
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+
+        let keys = JwtKeys::hs256("secret");
+        assert_eq!(decode_jwt(&token, &keys).unwrap().sub, user.id.to_string());
+        assert!(decode_jwt(&token, &keys.clone().with_leeway(30)).is_ok());
+        assert!(matches!(
+            decode_jwt(&token, &keys.with_leeway(0)),
+            Err(AppError::Jwt(_))
+        ));
+    }
+
+    #[test]
+    fn test_rotated_secret_still_verifies() {
+        let user = AuthUser::new(Uuid::new_v4());
+        let old_keys = JwtKeys::hs256("old-secret");
+        let token = generate_jwt_with_expiry(&user, &old_keys, 60).unwrap();
+        assert_eq!(
+            decode_header(&token).unwrap().kid.as_deref(),
+            Some(old_keys.kid())
+        );
+
+        // 更换主密钥后，旧 Token 只有在旧密钥位于备用列表时才有效
+        assert!(decode_jwt(&token, &JwtKeys::hs256("new-secret")).is_err());
+        let rotated =
+            JwtKeys::hs256("new-secret").with_secondary_secrets(&["old-secret".to_string()]);
+        assert_eq!(
+            decode_jwt(&token, &rotated).unwrap().sub,
+            user.id.to_string()
+        );
+
+        // 新 Token 使用新的主密钥签名
+        let new_token = generate_jwt_with_expiry(&user, &rotated, 60).unwrap();
+        assert_eq!(
+            decode_header(&new_token).unwrap().kid.as_deref(),
+            Some(rotated.kid())
+        );
+        assert_ne!(rotated.kid(), old_keys.kid());
+        assert!(decode_jwt(&new_token, &old_keys).is_err());
+
+        // 没有 kid 的旧 Token 会依次尝试所有密钥
+        let legacy_token = encode(
+            &Header::default(),
+            &Claims::with_expiry(&user, Duration::minutes(1)),
+            &EncodingKey::from_secret(b"old-secret"),
+        )
+        .unwrap();
+        assert!(decode_jwt(&legacy_token, &rotated).is_ok());
+    }
+
+    #[test]
+    fn test_tokens_issued_in_same_second_are_distinct() {
+        // 会话以 Token 指纹为键，同一秒内签发的 Token 必须不同
+        let user = AuthUser::new(Uuid::new_v4());
+        let keys = JwtKeys::hs256("secret");
+        let first = generate_jwt_with_expiry(&user, &keys, 60).unwrap();
+        let second = generate_jwt_with_expiry(&user, &keys, 60).unwrap();
+
+        assert_ne!(first, second);
+        assert_ne!(
+            decode_jwt(&first, &keys).unwrap().jti,
+            decode_jwt(&second, &keys).unwrap().jti
+        );
+    }
+
+    #[test]
+    fn test_custom_claims_round_trip() {
+        let user = AuthUser {
+            id: Uuid::new_v4(),
+            email: Some("user@example.com".to_string()),
+            role: Some("admin".to_string()),
+        };
+        let token = generate_jwt(&user, "secret").unwrap();
+
+        let claims = verify_jwt(&token, "secret").unwrap();
+        assert_eq!(claims.email.as_deref(), Some("user@example.com"));
+        assert_eq!(claims.role.as_deref(), Some("admin"));
+        assert_eq!(AuthUser::from_claims(&claims).unwrap(), user);
+    }
+
+    #[test]
+    fn test_legacy_claims_without_custom_fields() {
+        // 旧版本签发的 Token 只包含 sub/exp/iat
+        #[derive(Serialize)]
+        struct LegacyClaims {
+            sub: String,
+            exp: i64,
+            iat: i64,
+        }
+
+        let user_id = Uuid::new_v4();
+        let now = Utc::now().timestamp();
+        let legacy = LegacyClaims {
+            sub: user_id.to_string(),
+            exp: now + 60,
+            iat: now,
+        };
+        let token = encode(
+            &Header::default(),
+            &legacy,
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+
+        let claims = verify_jwt(&token, "secret").unwrap();
+        assert_eq!(
+            AuthUser::from_claims(&claims).unwrap(),
+            AuthUser::new(user_id)
+        );
+    }
 }
@@ -1,7 +1,18 @@
-use std::cmp::Ord;
-use std::collections::{HashMap, HashSet};
+use std::cmp::{Ord, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::hash::Hash;
 
+/// 任意层级的嵌套结构
+///
+/// 用于 `CollectionUtils::flatten_deep`，每个节点要么是单个元素，要么是子节点列表。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nested<T> {
+    /// 单个元素
+    Leaf(T),
+    /// 子节点列表
+    List(Vec<Nested<T>>),
+}
+
 /// 集合工具结构体
 pub struct CollectionUtils;
 
@@ -45,6 +56,27 @@ impl CollectionUtils {
         result
     }
 
+    /// 按键去重
+    ///
+    /// 对每个元素计算键，键相同的元素只保留第一个，结果保持原有顺序。
+    pub fn dedup_by_key<T, K, F>(arr: &[T], key_fn: F) -> Vec<T>
+    where
+        T: Clone,
+        K: Hash + Eq,
+        F: Fn(&T) -> K,
+    {
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+
+        for item in arr {
+            if seen.insert(key_fn(item)) {
+                result.push(item.clone());
+            }
+        }
+
+        result
+    }
+
     /// 数组排序去重
     pub fn unique_sorted<T: Clone + Ord>(arr: &[T]) -> Vec<T> {
         let mut result: Vec<T> = arr.to_vec();
@@ -140,6 +172,29 @@ impl CollectionUtils {
             .collect()
     }
 
+    /// 使用固定种子乱序
+    ///
+    /// 相同的种子和输入总是得到相同的顺序，适合需要可复现结果的测试。
+    pub fn shuffle_seeded<T>(arr: &mut [T], seed: u64) {
+        use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+        arr.shuffle(&mut StdRng::seed_from_u64(seed));
+    }
+
+    /// 使用固定种子随机选择元素
+    ///
+    /// 相同的种子和输入总是选出相同的元素。
+    pub fn sample_seeded<T: Clone>(arr: &[T], count: usize, seed: u64) -> Vec<T> {
+        use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+        if count >= arr.len() {
+            return arr.to_vec();
+        }
+
+        arr.choose_multiple(&mut StdRng::seed_from_u64(seed), count)
+            .cloned()
+            .collect()
+    }
+
     /// 查找元素的所有索引
     pub fn find_all_indices<T: PartialEq>(arr: &[T], target: &T) -> Vec<usize> {
         arr.iter()
@@ -153,6 +208,26 @@ impl CollectionUtils {
         nested.iter().flat_map(|v| v.iter()).cloned().collect()
     }
 
+    /// 数组拉平（任意层级）
+    ///
+    /// 按深度优先顺序收集所有叶子元素。使用显式栈遍历，嵌套很深时也不会栈溢出。
+    pub fn flatten_deep<T: Clone>(nested: &[Nested<T>]) -> Vec<T> {
+        let mut result = Vec::new();
+        let mut stack = vec![nested.iter()];
+
+        while let Some(iter) = stack.last_mut() {
+            match iter.next() {
+                Some(Nested::Leaf(item)) => result.push(item.clone()),
+                Some(Nested::List(children)) => stack.push(children.iter()),
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+
+        result
+    }
+
     /// 数组分区
     pub fn partition<T: Clone, P>(arr: &[T], predicate: P) -> (Vec<T>, Vec<T>)
     where
@@ -185,6 +260,77 @@ impl CollectionUtils {
         result
     }
 
+    /// 生成 k 个元素的所有排列
+    ///
+    /// 按元素在数组中的位置区分元素，重复的值会产生重复的排列。
+    /// 结果数量为 n! / (n - k)!，增长极快，只适合小规模数据（如生成测试矩阵）。
+    /// `k` 超过数组长度时返回空列表，`k` 为 0 时返回一个空排列。
+    pub fn permutations<T: Clone>(arr: &[T], k: usize) -> Vec<Vec<T>> {
+        if k > arr.len() {
+            return Vec::new();
+        }
+
+        let mut result = Vec::new();
+        let mut current = Vec::with_capacity(k);
+        let mut used = vec![false; arr.len()];
+        Self::collect_permutations(arr, k, &mut used, &mut current, &mut result);
+        result
+    }
+
+    fn collect_permutations<T: Clone>(
+        arr: &[T],
+        k: usize,
+        used: &mut [bool],
+        current: &mut Vec<T>,
+        result: &mut Vec<Vec<T>>,
+    ) {
+        if current.len() == k {
+            result.push(current.clone());
+            return;
+        }
+
+        for (i, item) in arr.iter().enumerate() {
+            if used[i] {
+                continue;
+            }
+            used[i] = true;
+            current.push(item.clone());
+            Self::collect_permutations(arr, k, used, current, result);
+            current.pop();
+            used[i] = false;
+        }
+    }
+
+    /// 生成 k 个元素的所有组合
+    ///
+    /// 每个组合中的元素保持原数组中的顺序，结果按字典序（以位置计）排列。
+    /// 结果数量为 C(n, k)，增长很快，只适合小规模数据。
+    /// `k` 超过数组长度时返回空列表，`k` 为 0 时返回一个空组合。
+    pub fn combinations<T: Clone>(arr: &[T], k: usize) -> Vec<Vec<T>> {
+        let n = arr.len();
+        if k > n {
+            return Vec::new();
+        }
+
+        let mut result = Vec::new();
+        let mut indices: Vec<usize> = (0..k).collect();
+
+        loop {
+            result.push(indices.iter().map(|&i| arr[i].clone()).collect());
+
+            // 从右向左找到第一个还能后移的位置
+            let Some(pos) = (0..k).rev().find(|&pos| indices[pos] < n - k + pos) else {
+                return result;
+            };
+
+            indices[pos] += 1;
+            let start = indices[pos];
+            for (offset, index) in indices[pos + 1..].iter_mut().enumerate() {
+                *index = start + offset + 1;
+            }
+        }
+    }
+
     /// 数组旋转（左旋）
     pub fn rotate_left<T: Clone>(arr: &[T], positions: usize) -> Vec<T> {
         if arr.is_empty() {
@@ -315,8 +461,20 @@ impl CollectionUtils {
             .map(|(item, _)| item)
     }
 
+    /// 检查数组是否按升序排列（允许相等的相邻元素）
+    pub fn is_sorted<T: Ord>(arr: &[T]) -> bool {
+        arr.windows(2).all(|w| w[0] <= w[1])
+    }
+
     /// 二分查找
+    ///
+    /// 数组必须按升序排列，否则结果没有意义；debug 构建下会检查数组是否有序。
     pub fn binary_search<T: Ord>(arr: &[T], target: &T) -> Option<usize> {
+        debug_assert!(
+            Self::is_sorted(arr),
+            "binary_search requires a sorted slice"
+        );
+
         let mut left = 0;
         let mut right = arr.len();
 
@@ -333,21 +491,127 @@ impl CollectionUtils {
         None
     }
 
+    /// 按投影键二分查找
+    ///
+    /// 数组必须按 `key_fn` 计算出的键升序排列（如按 ID 排序的用户列表），
+    /// debug 构建下会检查是否有序。
+    pub fn binary_search_by_key<T, K, F>(arr: &[T], key: &K, key_fn: F) -> Option<usize>
+    where
+        K: Ord,
+        F: Fn(&T) -> K,
+    {
+        debug_assert!(
+            arr.windows(2).all(|w| key_fn(&w[0]) <= key_fn(&w[1])),
+            "binary_search_by_key requires a slice sorted by key"
+        );
+
+        let mut left = 0;
+        let mut right = arr.len();
+
+        while left < right {
+            let mid = left + (right - left) / 2;
+
+            match key_fn(&arr[mid]).cmp(key) {
+                std::cmp::Ordering::Equal => return Some(mid),
+                std::cmp::Ordering::Less => left = mid + 1,
+                std::cmp::Ordering::Greater => right = mid,
+            }
+        }
+
+        None
+    }
+
+    /// 选取最大的 k 个元素
+    ///
+    /// 使用大小为 k 的最小堆，时间复杂度 O(n log k)。
+    /// 返回的元素按从大到小排序，`k` 超过数组长度时返回全部元素。
+    pub fn top_k<T: Ord + Clone>(arr: &[T], k: usize) -> Vec<T> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut heap = BinaryHeap::with_capacity(k.min(arr.len()) + 1);
+        for item in arr {
+            heap.push(Reverse(item.clone()));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        // Reverse 的升序即原值的降序
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+
+    /// 选取最小的 k 个元素
+    ///
+    /// 使用大小为 k 的最大堆，时间复杂度 O(n log k)。
+    /// 返回的元素按从小到大排序，`k` 超过数组长度时返回全部元素。
+    pub fn bottom_k<T: Ord + Clone>(arr: &[T], k: usize) -> Vec<T> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut heap = BinaryHeap::with_capacity(k.min(arr.len()) + 1);
+        for item in arr {
+            heap.push(item.clone());
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        heap.into_sorted_vec()
+    }
+
     /// 快速排序
+    ///
+    /// 使用三数取中选择基准，只对较小的分区递归、在较大的分区上循环，
+    /// 递归深度不超过 O(log n)；递归层数超出限制时退化为 `sort_unstable`，
+    /// 保证已排序或大量重复元素的输入也不会栈溢出。
     pub fn quick_sort<T: Ord + Clone>(arr: &mut [T]) {
-        if arr.len() <= 1 {
-            return;
-        }
+        let depth_limit = 2 * (usize::BITS - arr.len().leading_zeros());
+        Self::quick_sort_with_limit(arr, depth_limit);
+    }
 
-        let pivot_index = Self::partition_for_sort(arr);
-        let (left, right) = arr.split_at_mut(pivot_index);
+    fn quick_sort_with_limit<T: Ord>(mut arr: &mut [T], mut depth_limit: u32) {
+        while arr.len() > 1 {
+            if depth_limit == 0 {
+                arr.sort_unstable();
+                return;
+            }
+            depth_limit -= 1;
 
-        Self::quick_sort(left);
-        Self::quick_sort(&mut right[1..]);
+            let pivot_index = Self::partition_for_sort(arr);
+            let (left, right) = std::mem::take(&mut arr).split_at_mut(pivot_index);
+            let right = &mut right[1..];
+
+            if left.len() < right.len() {
+                Self::quick_sort_with_limit(left, depth_limit);
+                arr = right;
+            } else {
+                Self::quick_sort_with_limit(right, depth_limit);
+                arr = left;
+            }
+        }
     }
 
     fn partition_for_sort<T: Ord>(arr: &mut [T]) -> usize {
         let pivot_index = arr.len() - 1;
+
+        // 三数取中：将首、中、尾三个元素的中位数放到末尾作为基准
+        let mid = pivot_index / 2;
+        if arr[mid] < arr[0] {
+            arr.swap(0, mid);
+        }
+        if arr[pivot_index] < arr[0] {
+            arr.swap(0, pivot_index);
+        }
+        if arr[mid] < arr[pivot_index] {
+            arr.swap(mid, pivot_index);
+        }
+
         let mut i = 0;
 
         for j in 0..pivot_index {
@@ -373,6 +637,32 @@ mod tests {
         assert_eq!(unique.len(), 3);
     }
 
+    #[test]
+    fn test_dedup_by_key() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Record {
+            id: u32,
+            name: &'static str,
+        }
+
+        let records = vec![
+            Record { id: 2, name: "b" },
+            Record { id: 1, name: "a" },
+            Record { id: 2, name: "b2" },
+            Record { id: 3, name: "c" },
+            Record { id: 1, name: "a2" },
+        ];
+        let deduped = CollectionUtils::dedup_by_key(&records, |record| record.id);
+        assert_eq!(
+            deduped,
+            vec![
+                Record { id: 2, name: "b" },
+                Record { id: 1, name: "a" },
+                Record { id: 3, name: "c" },
+            ]
+        );
+    }
+
     #[test]
     fn test_intersection() {
         let arr1 = vec![1, 2, 3, 4];
@@ -392,6 +682,78 @@ mod tests {
         assert_eq!(chunks[2], vec![7]);
     }
 
+    #[test]
+    fn test_flatten_deep() {
+        use Nested::{Leaf, List};
+
+        // [1, [2, [3, 4], 5], [[6]], 7]
+        let nested = vec![
+            Leaf(1),
+            List(vec![Leaf(2), List(vec![Leaf(3), Leaf(4)]), Leaf(5)]),
+            List(vec![List(vec![Leaf(6)])]),
+            List(vec![]),
+            Leaf(7),
+        ];
+        assert_eq!(
+            CollectionUtils::flatten_deep(&nested),
+            vec![1, 2, 3, 4, 5, 6, 7]
+        );
+        assert!(CollectionUtils::flatten_deep::<i32>(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(
+            CollectionUtils::combinations(&[1, 2, 3], 2),
+            vec![vec![1, 2], vec![1, 3], vec![2, 3]]
+        );
+        assert_eq!(CollectionUtils::combinations(&[1, 2, 3, 4, 5], 3).len(), 10);
+        assert_eq!(
+            CollectionUtils::combinations(&[1, 2], 0),
+            vec![Vec::<i32>::new()]
+        );
+        assert!(CollectionUtils::combinations(&[1, 2], 3).is_empty());
+    }
+
+    #[test]
+    fn test_permutations() {
+        assert_eq!(
+            CollectionUtils::permutations(&[1, 2, 3], 2),
+            vec![
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 1],
+                vec![2, 3],
+                vec![3, 1],
+                vec![3, 2]
+            ]
+        );
+        assert_eq!(CollectionUtils::permutations(&[1, 2, 3, 4], 4).len(), 24);
+        assert!(CollectionUtils::permutations(&[1, 2], 3).is_empty());
+    }
+
+    #[test]
+    fn test_seeded_shuffle_and_sample() {
+        let original: Vec<u32> = (0..50).collect();
+
+        let mut first = original.clone();
+        let mut second = original.clone();
+        CollectionUtils::shuffle_seeded(&mut first, 42);
+        CollectionUtils::shuffle_seeded(&mut second, 42);
+        assert_eq!(first, second);
+        assert_ne!(first, original);
+
+        let mut sorted = first.clone();
+        sorted.sort();
+        assert_eq!(sorted, original);
+
+        assert_eq!(
+            CollectionUtils::sample_seeded(&original, 5, 7),
+            CollectionUtils::sample_seeded(&original, 5, 7)
+        );
+        assert_eq!(CollectionUtils::sample_seeded(&original, 5, 7).len(), 5);
+    }
+
     #[test]
     fn test_frequency() {
         let arr = vec!['a', 'b', 'a', 'c', 'b', 'a'];
@@ -407,4 +769,75 @@ mod tests {
         assert_eq!(CollectionUtils::binary_search(&arr, &5), Some(2));
         assert_eq!(CollectionUtils::binary_search(&arr, &4), None);
     }
+
+    #[test]
+    fn test_top_k_and_bottom_k() {
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let arr: Vec<i32> = (0..1000).map(|_| rng.gen_range(-500..500)).collect();
+        let mut sorted = arr.clone();
+        sorted.sort();
+
+        for k in [0, 1, 10, 999, 1000, 2000] {
+            let expected_bottom: Vec<i32> = sorted.iter().take(k).copied().collect();
+            let expected_top: Vec<i32> = sorted.iter().rev().take(k).copied().collect();
+            assert_eq!(CollectionUtils::bottom_k(&arr, k), expected_bottom);
+            assert_eq!(CollectionUtils::top_k(&arr, k), expected_top);
+        }
+
+        assert!(CollectionUtils::top_k::<i32>(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_is_sorted() {
+        assert!(CollectionUtils::is_sorted::<i32>(&[]));
+        assert!(CollectionUtils::is_sorted(&[1, 2, 2, 3]));
+        assert!(!CollectionUtils::is_sorted(&[1, 3, 2]));
+    }
+
+    #[test]
+    fn test_binary_search_by_key() {
+        let users = vec![(1, "alice"), (4, "bob"), (7, "carol"), (9, "dave")];
+        assert_eq!(
+            CollectionUtils::binary_search_by_key(&users, &7, |user| user.0),
+            Some(2)
+        );
+        assert_eq!(
+            CollectionUtils::binary_search_by_key(&users, &5, |user| user.0),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "sorted slice")]
+    fn test_binary_search_rejects_unsorted_in_debug() {
+        CollectionUtils::binary_search(&[3, 1, 2], &1);
+    }
+
+    #[test]
+    fn test_quick_sort() {
+        let mut arr = vec![5, 3, 8, 1, 9, 2, 7, 3];
+        CollectionUtils::quick_sort(&mut arr);
+        assert_eq!(arr, vec![1, 2, 3, 3, 5, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_quick_sort_large_degenerate_inputs() {
+        // 已排序的大数组
+        let mut sorted: Vec<u32> = (0..100_000).collect();
+        CollectionUtils::quick_sort(&mut sorted);
+        assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+
+        // 逆序的大数组
+        let mut reversed: Vec<u32> = (0..100_000).rev().collect();
+        CollectionUtils::quick_sort(&mut reversed);
+        assert_eq!(reversed, (0..100_000).collect::<Vec<u32>>());
+
+        // 全部相同的大数组
+        let mut same = vec![7u8; 100_000];
+        CollectionUtils::quick_sort(&mut same);
+        assert!(same.iter().all(|&x| x == 7));
+    }
 }
@@ -1,4 +1,6 @@
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use serde_json::{from_str, to_string, Value};
+use std::collections::BTreeSet;
 use std::fmt::Display;
 use std::str::FromStr;
 use url::Url;
@@ -59,7 +61,7 @@ impl ConvertUtils {
     /// 十六进制字符串转字节数组
     pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
         // 简单的十六进制解码实现
-        if hex.len() % 2 != 0 {
+        if !hex.len().is_multiple_of(2) {
             return Err("Invalid hex string length".into());
         }
 
@@ -96,13 +98,46 @@ impl ConvertUtils {
     }
 
     /// URL 编码
+    ///
+    /// 对除 RFC 3986 非保留字符以外的所有字符进行百分号编码，空格编码为 `%20`。
+    /// 需要区分使用场景时请使用 `encode_query_component` 或 `encode_path_segment`。
     pub fn url_encode(s: &str) -> String {
-        urlencoding::encode(s).to_string()
+        Self::encode_path_segment(s)
     }
 
     /// URL 解码
+    ///
+    /// 同时将 `+` 解码为空格，与 `decode_query_component` 相同。
     pub fn url_decode(s: &str) -> Result<String, Box<dyn std::error::Error>> {
-        Ok(urlencoding::decode(s)?)
+        Ok(Self::decode_query_component(s)?)
+    }
+
+    /// 编码查询参数（`application/x-www-form-urlencoded`）
+    ///
+    /// 空格编码为 `+`，`+` 本身编码为 `%2B`。
+    pub fn encode_query_component(s: &str) -> String {
+        utf8_percent_encode(s, URL_COMPONENT)
+            .to_string()
+            .replace("%20", "+")
+    }
+
+    /// 解码查询参数，`+` 解码为空格
+    pub fn decode_query_component(s: &str) -> Result<String, std::str::Utf8Error> {
+        Self::decode_path_segment(&s.replace('+', " "))
+    }
+
+    /// 编码路径段
+    ///
+    /// 空格编码为 `%20`，`/` 也会被编码，避免改变路径结构。
+    pub fn encode_path_segment(s: &str) -> String {
+        utf8_percent_encode(s, URL_COMPONENT).to_string()
+    }
+
+    /// 解码路径段，`+` 保持原样
+    pub fn decode_path_segment(s: &str) -> Result<String, std::str::Utf8Error> {
+        percent_decode_str(s)
+            .decode_utf8()
+            .map(|decoded| decoded.into_owned())
     }
 
     /// 解析 URL
@@ -212,6 +247,52 @@ impl ConvertUtils {
             .join(",")
     }
 
+    /// JSON 对象数组转换为 CSV 文档
+    ///
+    /// 表头为所有对象键的并集，按字母顺序排列；对象缺少的字段输出为空单元格。
+    /// 字符串原样输出，`null` 输出为空，嵌套的对象和数组以 JSON 字符串输出。
+    /// 输入不是对象数组时返回错误。
+    pub fn json_array_to_csv(json: &str) -> Result<String, serde_json::Error> {
+        use serde::de::Error as _;
+
+        let value: Value = from_str(json)?;
+        let items = value
+            .as_array()
+            .ok_or_else(|| serde_json::Error::custom("expected a JSON array"))?;
+
+        let mut objects = Vec::with_capacity(items.len());
+        for item in items {
+            objects.push(
+                item.as_object().ok_or_else(|| {
+                    serde_json::Error::custom("expected an array of JSON objects")
+                })?,
+            );
+        }
+
+        // 收集所有键作为表头，按字母顺序排列
+        let headers: Vec<String> = objects
+            .iter()
+            .flat_map(|object| object.keys().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let mut lines = vec![Self::array_to_csv_row(&headers)];
+        for object in &objects {
+            let row: Vec<String> = headers
+                .iter()
+                .map(|key| match object.get(key) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                })
+                .collect();
+            lines.push(Self::array_to_csv_row(&row));
+        }
+
+        Ok(lines.join("\n"))
+    }
+
     /// 字节大小转换为人类可读格式
     pub fn bytes_to_human_readable(bytes: u64) -> String {
         const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB", "PB"];
@@ -348,40 +429,12 @@ pub struct ParsedUrl {
 // 添加必要的 use 语句
 use std::convert::TryFrom;
 
-// 为了支持 URL 解码，我们需要手动实现一个简单版本
-mod urlencoding {
-    pub fn encode(input: &str) -> String {
-        input
-            .chars()
-            .map(|c| match c {
-                'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
-                _ => format!("%{:02X}", c as u8),
-            })
-            .collect()
-    }
-
-    pub fn decode(input: &str) -> Result<String, Box<dyn std::error::Error>> {
-        let mut result = Vec::new();
-        let mut chars = input.chars();
-
-        while let Some(c) = chars.next() {
-            match c {
-                '%' => {
-                    let hex1 = chars.next().unwrap_or('0');
-                    let hex2 = chars.next().unwrap_or('0');
-                    let hex_str = format!("{}{}", hex1, hex2);
-                    if let Ok(byte) = u8::from_str_radix(&hex_str, 16) {
-                        result.push(byte);
-                    }
-                }
-                '+' => result.push(b' '),
-                _ => result.push(c as u8),
-            }
-        }
-
-        String::from_utf8(result).map_err(|e| e.into())
-    }
-}
+/// URL 组件编码字符集：除 RFC 3986 非保留字符（字母、数字、`-`、`_`、`.`、`~`）外全部编码
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
 
 #[cfg(test)]
 mod tests {
@@ -443,4 +496,46 @@ mod tests {
         assert_eq!(fields[2], r#"quoted "value""#);
         assert_eq!(fields[3], "123");
     }
+
+    #[test]
+    fn test_json_array_to_csv() {
+        let json = r#"[
+            {"name": "张三", "age": 30},
+            {"name": "Li, Si", "city": "北京", "tags": ["a", "b"]}
+        ]"#;
+
+        let csv = ConvertUtils::json_array_to_csv(json).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "age,city,name,tags");
+        assert_eq!(lines[1], "30,,张三,");
+        assert_eq!(lines[2], r#",北京,"Li, Si","[""a"",""b""]""#);
+
+        assert!(ConvertUtils::json_array_to_csv(r#"{"name": "张三"}"#).is_err());
+        assert!(ConvertUtils::json_array_to_csv("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_url_component_encoding() {
+        // 路径段中的空格编码为 %20，查询参数中编码为 +
+        assert_eq!(
+            ConvertUtils::encode_path_segment("my file.txt"),
+            "my%20file.txt"
+        );
+        assert_eq!(ConvertUtils::encode_query_component("a b+c"), "a+b%2Bc");
+        assert_eq!(ConvertUtils::encode_path_segment("a/b"), "a%2Fb");
+
+        // 多字节字符按 UTF-8 编码
+        assert_eq!(ConvertUtils::url_encode("中"), "%E4%B8%AD");
+        assert_eq!(ConvertUtils::url_decode("%E4%B8%AD+a").unwrap(), "中 a");
+
+        // 路径段解码不把 + 视为空格
+        assert_eq!(
+            ConvertUtils::decode_path_segment("a+b%20c").unwrap(),
+            "a+b c"
+        );
+        assert_eq!(
+            ConvertUtils::decode_query_component("a+b%2Bc").unwrap(),
+            "a b+c"
+        );
+    }
 }
@@ -1,9 +1,32 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
+use argon2::Argon2;
 use base64::{engine::general_purpose, Engine as _};
 use hex;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use subtle::ConstantTimeEq;
 use uuid::Uuid;
 
+/// HMAC-SHA256 类型别名
+type HmacSha256 = Hmac<Sha256>;
+
+/// AES-GCM 随机数长度（96 位）
+const AES_GCM_NONCE_LEN: usize = 12;
+
+/// Have I Been Pwned 密码范围查询 API（需要启用 `hibp` feature）
+#[cfg(feature = "hibp")]
+pub const PWNED_PASSWORDS_RANGE_API: &str = "https://api.pwnedpasswords.com/range/";
+
+/// Have I Been Pwned 范围查询的超时时间，避免 API 无响应时阻塞注册和修改密码
+#[cfg(feature = "hibp")]
+pub const PWNED_PASSWORDS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
 /// 加密工具结构体
 pub struct CryptoUtils;
 
@@ -125,6 +148,43 @@ impl CryptoUtils {
         String::from_utf8_lossy(&encrypted_bytes).to_string()
     }
 
+    /// AES-256-GCM 加密
+    ///
+    /// 每次加密生成随机的 96 位 nonce，输出格式为 `nonce || 密文 || 认证标签`。
+    pub fn encrypt_aes_gcm(plaintext: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, aes_gcm::Error> {
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+        let mut output = Self::random_bytes(AES_GCM_NONCE_LEN);
+        let ciphertext = cipher.encrypt(Nonce::from_slice(&output), plaintext)?;
+
+        output.extend_from_slice(&ciphertext);
+        Ok(output)
+    }
+
+    /// AES-256-GCM 解密
+    ///
+    /// 输入为 `encrypt_aes_gcm` 的输出。密钥错误、数据被篡改或长度不足时返回错误。
+    pub fn decrypt_aes_gcm(ciphertext: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, aes_gcm::Error> {
+        if ciphertext.len() < AES_GCM_NONCE_LEN {
+            return Err(aes_gcm::Error);
+        }
+
+        let (nonce, encrypted) = ciphertext.split_at(AES_GCM_NONCE_LEN);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+        cipher.decrypt(Nonce::from_slice(nonce), encrypted)
+    }
+
+    /// 从口令派生 256 位密钥（Argon2id）
+    ///
+    /// 相同的口令和盐总是得到相同的密钥。盐至少 8 字节，应随机生成并与密文一起保存。
+    pub fn derive_key_from_passphrase(
+        passphrase: &str,
+        salt: &[u8],
+    ) -> Result<[u8; 32], argon2::Error> {
+        let mut key = [0u8; 32];
+        Argon2::default().hash_password_into(passphrase.as_bytes(), salt, &mut key)?;
+        Ok(key)
+    }
+
     /// 生成安全的随机密码
     pub fn generate_password(
         length: usize,
@@ -224,38 +284,82 @@ impl CryptoUtils {
         }
     }
 
-    /// 计算文件哈希（模拟，实际使用时需要加载真实的哈希库）
-    pub fn hash_data_simple(_data: &[u8]) -> String {
-        Self::hex_encode(&Self::random_bytes(32)) // 简化实现
+    /// 计算 SHA-256 摘要，返回小写十六进制字符串
+    pub fn sha256_hex(data: &[u8]) -> String {
+        Self::hex_encode(&Sha256::digest(data))
+    }
+
+    /// 计算 SHA-512 摘要，返回小写十六进制字符串
+    pub fn sha512_hex(data: &[u8]) -> String {
+        Self::hex_encode(&Sha512::digest(data))
+    }
+
+    /// 计算数据内容哈希（SHA-256）
+    pub fn hash_data_simple(data: &[u8]) -> String {
+        Self::sha256_hex(data)
+    }
+
+    /// 计算 HMAC-SHA256 签名
+    pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+        // HMAC 接受任意长度的密钥，这里不会失败
+        let mut mac =
+            <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(data);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    /// 计算 HMAC-SHA256 签名并输出十六进制字符串
+    pub fn hmac_sha256_hex(key: &[u8], data: &[u8]) -> String {
+        Self::hex_encode(&Self::hmac_sha256(key, data))
+    }
+
+    /// 恒定时间比较两个字节序列是否相等
+    ///
+    /// 长度不同时立即返回 `false`（只泄露长度信息），
+    /// 长度相同时比较耗时与内容无关，用于比较签名、验证码等敏感数据。
+    pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+
+        a.ct_eq(b).into()
+    }
+
+    /// 验证 HMAC-SHA256 签名（恒定时间比较）
+    pub fn verify_hmac_sha256(key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+        let mut mac =
+            <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(data);
+        mac.verify_slice(signature).is_ok()
     }
 
-    /// 时间戳签名（简单实现）
+    /// 时间戳签名（HMAC-SHA256）
+    ///
+    /// 生成格式为 `时间戳.数据.签名` 的字符串，签名为十六进制的 HMAC-SHA256
     pub fn timestamp_signature(data: &str, secret: &str) -> String {
         use crate::utils::time::TimeUtils;
 
         let timestamp = TimeUtils::timestamp();
         let payload = format!("{}.{}", timestamp, data);
-        let hash = Self::hash_string(&format!("{}{}", payload, secret));
+        let signature = Self::hmac_sha256_hex(secret.as_bytes(), payload.as_bytes());
 
-        format!("{}.{:x}", payload, hash)
+        format!("{}.{}", payload, signature)
     }
 
     /// 验证时间戳签名
     pub fn verify_timestamp_signature(signature: &str, secret: &str, max_age_seconds: i64) -> bool {
         use crate::utils::time::TimeUtils;
 
-        let parts: Vec<&str> = signature.split('.').collect();
-        if parts.len() != 3 {
-            return false;
-        }
-
-        let timestamp: i64 = match parts[0].parse() {
-            Ok(ts) => ts,
-            Err(_) => return false,
+        // 签名位于最后一个 '.' 之后，时间戳位于第一个 '.' 之前
+        let (payload, provided_signature) = match signature.rsplit_once('.') {
+            Some(parts) => parts,
+            None => return false,
         };
 
-        let data = parts[1];
-        let provided_hash = parts[2];
+        let timestamp: i64 = match payload.split_once('.').map(|(ts, _)| ts.parse()) {
+            Some(Ok(ts)) => ts,
+            _ => return false,
+        };
 
         // 检查时间戳是否过期
         let current_timestamp = TimeUtils::timestamp();
@@ -263,11 +367,14 @@ impl CryptoUtils {
             return false;
         }
 
-        // 验证签名
-        let payload = format!("{}.{}", timestamp, data);
-        let expected_hash = format!("{:x}", Self::hash_string(&format!("{}{}", payload, secret)));
+        // 验证签名（恒定时间比较）
+        let provided_bytes = match Self::hex_decode(provided_signature) {
+            Ok(bytes) => bytes,
+            Err(_) => return false,
+        };
 
-        provided_hash == expected_hash
+        let expected = Self::hmac_sha256(secret.as_bytes(), payload.as_bytes());
+        Self::constant_time_eq(&expected, &provided_bytes)
     }
 
     /// URL 安全的 Base64 编码字符串
@@ -280,16 +387,104 @@ impl CryptoUtils {
         let bytes = Self::base64_url_decode(encoded)?;
         Ok(String::from_utf8(bytes)?)
     }
+
+    /// 查询密码是否出现在已知的数据泄露中（Have I Been Pwned）
+    ///
+    /// 使用 k-匿名范围查询：只把密码 SHA-1 摘要的前 5 个十六进制字符发送给 API，
+    /// 在本地比对返回的后缀列表，密码和完整摘要都不会离开本进程。
+    ///
+    /// 需要启用 `hibp` feature。
+    ///
+    /// # 返回值
+    ///
+    /// 密码出现在泄露数据中时返回 `Some(出现次数)`，否则返回 `None`
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Internal`: 网络请求失败、超过 `PWNED_PASSWORDS_TIMEOUT` 未响应或 API 返回错误状态码
+    #[cfg(feature = "hibp")]
+    pub async fn check_password_pwned(password: &str) -> crate::error::Result<Option<u64>> {
+        Self::check_password_pwned_with_api(password, PWNED_PASSWORDS_RANGE_API).await
+    }
+
+    /// 使用指定的范围查询 API 地址检查密码是否泄露
+    ///
+    /// `api_base` 以 `/` 结尾，请求地址为 `api_base` + 摘要前缀，便于测试或使用自建镜像。
+    #[cfg(feature = "hibp")]
+    pub async fn check_password_pwned_with_api(
+        password: &str,
+        api_base: &str,
+    ) -> crate::error::Result<Option<u64>> {
+        Self::check_password_pwned_with_timeout(password, api_base, PWNED_PASSWORDS_TIMEOUT).await
+    }
+
+    /// 使用指定的 API 地址和超时时间检查密码是否泄露
+    #[cfg(feature = "hibp")]
+    async fn check_password_pwned_with_timeout(
+        password: &str,
+        api_base: &str,
+        timeout: std::time::Duration,
+    ) -> crate::error::Result<Option<u64>> {
+        use sha1::Sha1;
+
+        let digest = Self::hex_encode(&Sha1::digest(password.as_bytes())).to_uppercase();
+        let (prefix, suffix) = digest.split_at(5);
+
+        let body = reqwest::Client::new()
+            .get(format!("{}{}", api_base, prefix))
+            // 请求填充响应，避免通过响应大小推断前缀
+            .header("Add-Padding", "true")
+            .timeout(timeout)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| anyhow::anyhow!("Pwned Passwords request failed: {}", e))?
+            .text()
+            .await
+            .map_err(|e| anyhow::anyhow!("Pwned Passwords response error: {}", e))?;
+
+        Ok(Self::parse_pwned_range(&body, suffix))
+    }
+
+    /// 在范围查询响应中查找摘要后缀
+    ///
+    /// 响应每行格式为 `后缀:次数`；填充行的次数为 0，视为未泄露。
+    #[cfg(feature = "hibp")]
+    fn parse_pwned_range(body: &str, suffix: &str) -> Option<u64> {
+        body.lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .find(|(line_suffix, _)| line_suffix.eq_ignore_ascii_case(suffix))
+            .and_then(|(_, count)| count.trim().parse().ok())
+            .filter(|&count| count > 0)
+    }
 }
 
 /// 密码强度等级
-#[derive(Debug, Clone, PartialEq)]
+///
+/// 按 `Weak < Medium < Strong` 排序，可以用于比较是否达到要求的最低等级。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum StrengthLevel {
     Weak,
     Medium,
     Strong,
 }
 
+impl std::str::FromStr for StrengthLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "weak" => Ok(StrengthLevel::Weak),
+            "medium" => Ok(StrengthLevel::Medium),
+            "strong" => Ok(StrengthLevel::Strong),
+            other => Err(anyhow::anyhow!(
+                "Unsupported password strength level: {}",
+                other
+            )),
+        }
+    }
+}
+
 /// 密码强度检查结果
 #[derive(Debug, Clone)]
 pub struct PasswordStrength {
@@ -371,4 +566,173 @@ mod tests {
         let simple_uuid = CryptoUtils::generate_uuid_simple();
         assert_eq!(simple_uuid.len(), 32); // 不包含连字符的 UUID 长度
     }
+
+    #[test]
+    fn test_timestamp_signature() {
+        let signature = CryptoUtils::timestamp_signature("user:42", "secret");
+        assert!(CryptoUtils::verify_timestamp_signature(
+            &signature, "secret", 60
+        ));
+
+        // 错误的密钥
+        assert!(!CryptoUtils::verify_timestamp_signature(
+            &signature, "wrong", 60
+        ));
+
+        // 篡改的数据
+        let tampered = signature.replace("user:42", "user:43");
+        assert!(!CryptoUtils::verify_timestamp_signature(
+            &tampered, "secret", 60
+        ));
+
+        // 格式错误
+        assert!(!CryptoUtils::verify_timestamp_signature(
+            "invalid", "secret", 60
+        ));
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 测试向量 2
+        let mac = CryptoUtils::hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            mac,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_aes_gcm_round_trip() {
+        let key = CryptoUtils::derive_key_from_passphrase("correct horse", b"salt-1234").unwrap();
+        let encrypted = CryptoUtils::encrypt_aes_gcm(b"secret data", &key).unwrap();
+
+        assert_ne!(&encrypted[AES_GCM_NONCE_LEN..], b"secret data");
+        assert_eq!(
+            CryptoUtils::decrypt_aes_gcm(&encrypted, &key).unwrap(),
+            b"secret data"
+        );
+
+        // 相同明文每次加密结果不同（随机 nonce）
+        assert_ne!(
+            CryptoUtils::encrypt_aes_gcm(b"secret data", &key).unwrap(),
+            encrypted
+        );
+    }
+
+    #[test]
+    fn test_aes_gcm_tamper_detection() {
+        let key = CryptoUtils::derive_key_from_passphrase("correct horse", b"salt-1234").unwrap();
+        let mut encrypted = CryptoUtils::encrypt_aes_gcm(b"secret data", &key).unwrap();
+
+        // 错误的密钥
+        let wrong_key =
+            CryptoUtils::derive_key_from_passphrase("wrong horse", b"salt-1234").unwrap();
+        assert!(CryptoUtils::decrypt_aes_gcm(&encrypted, &wrong_key).is_err());
+
+        // 翻转一个字节
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 0x01;
+        assert!(CryptoUtils::decrypt_aes_gcm(&encrypted, &key).is_err());
+
+        // 长度不足
+        assert!(CryptoUtils::decrypt_aes_gcm(&[0u8; 4], &key).is_err());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(CryptoUtils::constant_time_eq(b"123456", b"123456"));
+        assert!(CryptoUtils::constant_time_eq(b"", b""));
+        assert!(!CryptoUtils::constant_time_eq(b"123456", b"123457"));
+        assert!(!CryptoUtils::constant_time_eq(b"123456", b"12345"));
+    }
+
+    #[test]
+    fn test_sha_digests() {
+        // FIPS 180-2 标准测试向量
+        assert_eq!(
+            CryptoUtils::sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            CryptoUtils::hash_data_simple(b"abc"),
+            CryptoUtils::sha256_hex(b"abc")
+        );
+        assert_eq!(
+            CryptoUtils::sha512_hex(b"abc"),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+    }
+
+    #[cfg(feature = "hibp")]
+    #[tokio::test]
+    async fn test_check_password_pwned_with_mocked_api() {
+        use axum::{extract::Path, routing::get, Router};
+
+        // SHA-1("password") = 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+        async fn range(Path(prefix): Path<String>) -> String {
+            assert_eq!(prefix, "5BAA6");
+            [
+                "003D68EB55068C33ACE09247EE4C639306B:3",
+                "1E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824",
+                "0018A45C4D1DEF81644B54AB7F969B88D65:0",
+            ]
+            .join("\r\n")
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/range/:prefix", get(range));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let api_base = format!("http://{}/range/", addr);
+        assert_eq!(
+            CryptoUtils::check_password_pwned_with_api("password", &api_base)
+                .await
+                .unwrap(),
+            Some(9_545_824)
+        );
+    }
+
+    #[cfg(feature = "hibp")]
+    #[tokio::test]
+    async fn test_check_password_pwned_times_out() {
+        use axum::{routing::get, Router};
+
+        // API 无响应时在超时后返回错误，由调用方放行
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/range/:prefix",
+            get(|| async {
+                tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+                ""
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let api_base = format!("http://{}/range/", addr);
+        let started = std::time::Instant::now();
+        assert!(CryptoUtils::check_password_pwned_with_timeout(
+            "password",
+            &api_base,
+            std::time::Duration::from_millis(100),
+        )
+        .await
+        .is_err());
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[cfg(feature = "hibp")]
+    #[test]
+    fn test_parse_pwned_range() {
+        let body = "1E4C9B93F3F0682250B6CF8331B7EE68FD8:12\r\nABCDEF:0\r\n";
+        assert_eq!(
+            CryptoUtils::parse_pwned_range(body, "1e4c9b93f3f0682250b6cf8331b7ee68fd8"),
+            Some(12)
+        );
+        // 填充行和不存在的后缀都视为未泄露
+        assert_eq!(CryptoUtils::parse_pwned_range(body, "ABCDEF"), None);
+        assert_eq!(CryptoUtils::parse_pwned_range(body, "123456"), None);
+    }
 }
@@ -4,8 +4,69 @@
  * 提供设备类型识别和管理功能，用于实现单设备类型单点登录。
  */
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{fmt, sync::OnceLock};
+
+/// 识别爬虫和自动化客户端的 User-Agent 关键字（小写）
+///
+/// `bot` 同时覆盖 Googlebot、Bingbot 等搜索引擎爬虫。
+const BOT_PATTERNS: &[&str] = &[
+    "bot",
+    "spider",
+    "crawler",
+    "curl",
+    "wget",
+    "python-requests",
+];
+
+/// User-Agent 版本号解析规则
+///
+/// 每条规则的捕获组依次为主版本号和（可选的）次版本号。
+struct VersionPatterns {
+    ios: Regex,
+    mac_os: Regex,
+    android: Regex,
+    firefox: Regex,
+    edge: Regex,
+    opera: Regex,
+    chrome: Regex,
+    safari: Regex,
+}
+
+/// 获取版本号解析规则，首次调用时编译，避免每次解析 User-Agent 都重新编译正则
+fn version_patterns() -> &'static VersionPatterns {
+    static PATTERNS: OnceLock<VersionPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| VersionPatterns {
+        ios: Regex::new(r"(?i)\bOS (\d+)[_\d]* like Mac OS X").unwrap(),
+        mac_os: Regex::new(r"(?i)Mac OS X (\d+)[_.](\d+)").unwrap(),
+        android: Regex::new(r"(?i)Android (\d+)(?:\.(\d+))?").unwrap(),
+        firefox: Regex::new(r"(?i)(?:Firefox|FxiOS)/(\d+)").unwrap(),
+        edge: Regex::new(r"(?i)Edg(?:e|A|iOS)?/(\d+)").unwrap(),
+        opera: Regex::new(r"(?i)(?:OPR|Opera)[/ ](\d+)").unwrap(),
+        chrome: Regex::new(r"(?i)(?:Chrome|CriOS)/(\d+)").unwrap(),
+        safari: Regex::new(r"(?i)Version/(\d+)").unwrap(),
+    })
+}
+
+/// 在名称后附加从 User-Agent 中提取的版本号，例如 "Chrome 119"、"Android 8.1"
+///
+/// 匹配不到版本号时只返回名称。
+fn with_version(name: &str, pattern: &Regex, user_agent: &str) -> String {
+    let version = pattern.captures(user_agent).map(|caps| {
+        caps.iter()
+            .skip(1)
+            .flatten()
+            .map(|m| m.as_str())
+            .collect::<Vec<_>>()
+            .join(".")
+    });
+
+    match version {
+        Some(version) if !version.is_empty() => format!("{} {}", name, version),
+        _ => name.to_string(),
+    }
+}
 
 /// 设备类型枚举
 ///
@@ -21,6 +82,8 @@ pub enum DeviceType {
     Desktop,
     /// API 客户端/其他
     Api,
+    /// 爬虫或自动化客户端（curl、wget 等）
+    Bot,
 }
 
 impl fmt::Display for DeviceType {
@@ -30,18 +93,21 @@ impl fmt::Display for DeviceType {
             DeviceType::Mobile => write!(f, "mobile"),
             DeviceType::Desktop => write!(f, "desktop"),
             DeviceType::Api => write!(f, "api"),
+            DeviceType::Bot => write!(f, "bot"),
         }
     }
 }
 
 impl DeviceType {
     /// 从字符串解析设备类型
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "web" => DeviceType::Web,
             "mobile" => DeviceType::Mobile,
             "desktop" => DeviceType::Desktop,
             "api" => DeviceType::Api,
+            "bot" => DeviceType::Bot,
             _ => DeviceType::Api, // 默认为 API
         }
     }
@@ -79,6 +145,8 @@ impl DeviceInfo {
     /// # 示例
     ///
     /// ```rust
+    /// use hello_rust::utils::{DeviceInfo, DeviceType};
+    ///
     /// let user_agent = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36";
     /// let device_info = DeviceInfo::from_user_agent(user_agent, None);
     /// assert_eq!(device_info.device_type, DeviceType::Web);
@@ -106,6 +174,11 @@ impl DeviceInfo {
     fn detect_device_type_from_user_agent(user_agent: &str) -> DeviceType {
         let ua_lower = user_agent.to_lowercase();
 
+        // 先检测爬虫：许多爬虫的 User-Agent 同样包含 "Mozilla" 或 "Android"
+        if Self::is_bot_user_agent(&ua_lower) {
+            return DeviceType::Bot;
+        }
+
         // 检测移动设备
         if ua_lower.contains("mobile")
             || ua_lower.contains("iphone")
@@ -117,8 +190,10 @@ impl DeviceInfo {
             return DeviceType::Mobile;
         }
 
-        // 检测桌面应用（Electron 等）
-        if ua_lower.contains("electron") || ua_lower.contains("desktop") || ua_lower.contains("app")
+        // 检测桌面应用（Electron 等），忽略浏览器 User-Agent 中的 "AppleWebKit"
+        if ua_lower.contains("electron")
+            || ua_lower.contains("desktop")
+            || ua_lower.replace("applewebkit", "").contains("app")
         {
             return DeviceType::Desktop;
         }
@@ -138,11 +213,23 @@ impl DeviceInfo {
         DeviceType::Api
     }
 
+    /// 判断（小写的）User-Agent 是否来自爬虫或自动化客户端
+    fn is_bot_user_agent(ua_lower: &str) -> bool {
+        BOT_PATTERNS
+            .iter()
+            .any(|pattern| ua_lower.contains(pattern))
+    }
+
     /// 解析 User-Agent 中的详细信息
+    ///
+    /// 返回 `(操作系统, 浏览器)`，能识别版本号时附带版本，例如 `("iOS 17", "Safari 17")`、
+    /// `("Windows 10", "Chrome 119")`；识别不出版本号时只返回名称，无法识别时返回 `None`。
     fn parse_user_agent_details(user_agent: &str) -> (Option<String>, Option<String>) {
         let ua_lower = user_agent.to_lowercase();
+        let patterns = version_patterns();
 
         // 解析操作系统信息
+        // iOS 和 Android 的 User-Agent 分别包含 "Mac OS X" 和 "Linux"，需要先于它们检测
         let os_info = if ua_lower.contains("windows nt 10.0") {
             Some("Windows 10".to_string())
         } else if ua_lower.contains("windows nt 6.3") {
@@ -153,32 +240,32 @@ impl DeviceInfo {
             Some("Windows 7".to_string())
         } else if ua_lower.contains("windows") {
             Some("Windows".to_string())
+        } else if ua_lower.contains("iphone") || ua_lower.contains("ipad") {
+            Some(with_version("iOS", &patterns.ios, user_agent))
         } else if ua_lower.contains("mac os x") || ua_lower.contains("macos") {
-            Some("macOS".to_string())
+            Some(with_version("macOS", &patterns.mac_os, user_agent))
+        } else if ua_lower.contains("android") {
+            Some(with_version("Android", &patterns.android, user_agent))
         } else if ua_lower.contains("linux") {
             Some("Linux".to_string())
-        } else if ua_lower.contains("android") {
-            Some("Android".to_string())
-        } else if ua_lower.contains("ios")
-            || ua_lower.contains("iphone")
-            || ua_lower.contains("ipad")
-        {
+        } else if ua_lower.contains("ios") {
             Some("iOS".to_string())
         } else {
             None
         };
 
         // 解析浏览器信息
-        let browser_info = if ua_lower.contains("firefox") {
-            Some("Firefox".to_string())
-        } else if ua_lower.contains("edg/") {
-            Some("Microsoft Edge".to_string())
-        } else if ua_lower.contains("chrome") && !ua_lower.contains("edg") {
-            Some("Chrome".to_string())
-        } else if ua_lower.contains("safari") && !ua_lower.contains("chrome") {
-            Some("Safari".to_string())
-        } else if ua_lower.contains("opera") {
-            Some("Opera".to_string())
+        // Edge 和 Opera 的 User-Agent 同时包含 "Chrome"，Chrome 的同时包含 "Safari"，按从具体到宽泛的顺序检测
+        let browser_info = if ua_lower.contains("firefox/") || ua_lower.contains("fxios/") {
+            Some(with_version("Firefox", &patterns.firefox, user_agent))
+        } else if patterns.edge.is_match(user_agent) {
+            Some(with_version("Microsoft Edge", &patterns.edge, user_agent))
+        } else if ua_lower.contains("opr/") || ua_lower.contains("opera") {
+            Some(with_version("Opera", &patterns.opera, user_agent))
+        } else if ua_lower.contains("chrome") || ua_lower.contains("crios/") {
+            Some(with_version("Chrome", &patterns.chrome, user_agent))
+        } else if ua_lower.contains("safari") {
+            Some(with_version("Safari", &patterns.safari, user_agent))
         } else {
             None
         };
@@ -208,6 +295,7 @@ impl DeviceInfo {
                 None => Some("Desktop App".to_string()),
             },
             DeviceType::Api => Some("API Client".to_string()),
+            DeviceType::Bot => Some("Bot".to_string()),
         }
     }
 
@@ -229,6 +317,11 @@ impl DeviceInfo {
         format!("device:{}", self.device_type)
     }
 
+    /// 是否为爬虫或自动化客户端
+    pub fn is_bot(&self) -> bool {
+        self.device_type == DeviceType::Bot
+    }
+
     /// 获取设备显示名称
     pub fn display_name(&self) -> String {
         self.device_name
@@ -264,6 +357,79 @@ mod tests {
         assert_eq!(device_info.device_type, DeviceType::Desktop);
     }
 
+    #[test]
+    fn test_version_extraction() {
+        let chrome_ua = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36";
+        let device_info = DeviceInfo::from_user_agent(chrome_ua, None);
+        assert_eq!(device_info.browser_info.as_deref(), Some("Chrome 119"));
+        assert_eq!(device_info.os_info.as_deref(), Some("Windows 10"));
+        assert_eq!(
+            device_info.device_name.as_deref(),
+            Some("Chrome 119 on Windows 10")
+        );
+
+        let ios_safari_ua = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1.2 Mobile/15E148 Safari/604.1";
+        let device_info = DeviceInfo::from_user_agent(ios_safari_ua, None);
+        assert_eq!(device_info.device_type, DeviceType::Mobile);
+        assert_eq!(device_info.os_info.as_deref(), Some("iOS 17"));
+        assert_eq!(device_info.browser_info.as_deref(), Some("Safari 17"));
+        assert_eq!(device_info.device_name.as_deref(), Some("iOS 17 Device"));
+
+        let android_ua = "Mozilla/5.0 (Linux; Android 8.1.0; Pixel) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.6099.43 Mobile Safari/537.36";
+        let (os_info, browser_info) = DeviceInfo::parse_user_agent_details(android_ua);
+        assert_eq!(os_info.as_deref(), Some("Android 8.1"));
+        assert_eq!(browser_info.as_deref(), Some("Chrome 120"));
+
+        let edge_ua = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36 Edg/119.0.2151.58";
+        let (os_info, browser_info) = DeviceInfo::parse_user_agent_details(edge_ua);
+        assert_eq!(os_info.as_deref(), Some("macOS 10.15"));
+        assert_eq!(browser_info.as_deref(), Some("Microsoft Edge 119"));
+    }
+
+    #[test]
+    fn test_version_extraction_fallbacks() {
+        // 有名称但没有版本号时只返回名称
+        let (os_info, browser_info) =
+            DeviceInfo::parse_user_agent_details("Mozilla/5.0 (iPad) Safari");
+        assert_eq!(os_info.as_deref(), Some("iOS"));
+        assert_eq!(browser_info.as_deref(), Some("Safari"));
+
+        // 无法识别的 User-Agent
+        assert_eq!(
+            DeviceInfo::parse_user_agent_details("Custom Client"),
+            (None, None)
+        );
+        assert_eq!(DeviceInfo::parse_user_agent_details(""), (None, None));
+    }
+
+    #[test]
+    fn test_bot_detection() {
+        let googlebot_ua =
+            "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+        let device_info = DeviceInfo::from_user_agent(googlebot_ua, None);
+        assert_eq!(device_info.device_type, DeviceType::Bot);
+        assert!(device_info.is_bot());
+
+        // Googlebot 智能手机版同样包含 Android 和 Mobile
+        let googlebot_mobile_ua = "Mozilla/5.0 (Linux; Android 6.0.1; Nexus 5X Build/MMB29P) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/41.0.2272.96 Mobile Safari/537.36 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+        assert!(DeviceInfo::from_user_agent(googlebot_mobile_ua, None).is_bot());
+
+        for ua in ["curl/7.68.0", "Wget/1.21.2", "python-requests/2.31.0"] {
+            let device_info = DeviceInfo::from_user_agent(ua, None);
+            assert!(device_info.is_bot(), "{} should be a bot", ua);
+            assert_eq!(device_info.display_name(), "Bot");
+        }
+
+        let chrome_ua = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36";
+        assert!(!DeviceInfo::from_user_agent(chrome_ua, None).is_bot());
+
+        assert_eq!(DeviceType::from_str("bot"), DeviceType::Bot);
+        assert_eq!(
+            DeviceInfo::simple(DeviceType::Bot, None).get_device_key(),
+            "device:bot"
+        );
+    }
+
     #[test]
     fn test_device_key_generation() {
         let web_device = DeviceInfo::simple(DeviceType::Web, None);
@@ -1,5 +1,8 @@
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use unicode_width::UnicodeWidthStr;
+
+use super::TimeUtils;
 
 /// 格式化工具结构体
 pub struct FormatUtils;
@@ -41,6 +44,23 @@ impl FormatUtils {
         format!("{:.1$}%", ratio * 100.0, decimal_places as usize)
     }
 
+    /// 格式化英文序数词（如 `1st`、`2nd`、`11th`）
+    pub fn format_ordinal(n: u64) -> String {
+        let suffix = match (n % 10, n % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+        format!("{}{}", n, suffix)
+    }
+
+    /// 格式化中文序数词（如 `第1`）
+    pub fn format_ordinal_cn(n: u64) -> String {
+        format!("第{}", n)
+    }
+
     /// 格式化文件大小
     pub fn format_file_size(bytes: u64) -> String {
         const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB", "PB"];
@@ -128,23 +148,10 @@ impl FormatUtils {
     }
 
     /// 格式化相对时间
+    ///
+    /// 与 `TimeUtils::relative_time` 共用实现，支持过去和未来的时间。
     pub fn format_relative_time(datetime: &DateTime<Utc>) -> String {
-        let now = Utc::now();
-        let diff = now.signed_duration_since(*datetime);
-
-        if diff.num_seconds() < 60 {
-            "刚刚".to_string()
-        } else if diff.num_minutes() < 60 {
-            format!("{}分钟前", diff.num_minutes())
-        } else if diff.num_hours() < 24 {
-            format!("{}小时前", diff.num_hours())
-        } else if diff.num_days() < 30 {
-            format!("{}天前", diff.num_days())
-        } else if diff.num_days() < 365 {
-            format!("{}个月前", diff.num_days() / 30)
-        } else {
-            format!("{}年前", diff.num_days() / 365)
-        }
+        TimeUtils::relative_time(datetime)
     }
 
     /// 格式化电话号码（中国）
@@ -158,14 +165,60 @@ impl FormatUtils {
         }
     }
 
+    /// 隐藏字符串中间部分
+    ///
+    /// 保留前 `visible_prefix` 个和后 `visible_suffix` 个字符，其余字符逐个替换为 `mask_char`。
+    /// 按字符（而非字节）计数；保留部分超过字符串长度时全部隐藏。
+    pub fn mask(
+        input: &str,
+        visible_prefix: usize,
+        visible_suffix: usize,
+        mask_char: char,
+    ) -> String {
+        let len = input.chars().count();
+        if visible_prefix + visible_suffix > len {
+            return mask_char.to_string().repeat(len);
+        }
+
+        input
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                if i < visible_prefix || i >= len - visible_suffix {
+                    c
+                } else {
+                    mask_char
+                }
+            })
+            .collect()
+    }
+
+    /// 用固定的占位符隐藏字符串中间部分
+    ///
+    /// 保留前 `visible_prefix` 个和后 `visible_suffix` 个字符，中间部分整体替换为 `placeholder`，
+    /// 输出长度不暴露被隐藏部分的长度。按字符（而非字节）计数；保留部分超过字符串长度时只返回 `placeholder`。
+    pub fn mask_fixed(
+        input: &str,
+        visible_prefix: usize,
+        visible_suffix: usize,
+        placeholder: &str,
+    ) -> String {
+        let chars: Vec<char> = input.chars().collect();
+        if visible_prefix + visible_suffix > chars.len() {
+            return placeholder.to_string();
+        }
+
+        let prefix: String = chars[..visible_prefix].iter().collect();
+        let suffix: String = chars[chars.len() - visible_suffix..].iter().collect();
+        format!("{}{}{}", prefix, placeholder, suffix)
+    }
+
     /// 格式化身份证号（隐藏中间部分）
     pub fn format_id_card_masked(id_card: &str) -> String {
-        if id_card.len() == 18 {
-            format!("{}******{}", &id_card[0..6], &id_card[14..18])
-        } else if id_card.len() == 15 {
-            format!("{}******{}", &id_card[0..6], &id_card[12..15])
-        } else {
-            id_card.to_string()
+        match id_card.chars().count() {
+            18 => Self::mask_fixed(id_card, 6, 4, "******"),
+            15 => Self::mask_fixed(id_card, 6, 3, "******"),
+            _ => id_card.to_string(),
         }
     }
 
@@ -174,10 +227,7 @@ impl FormatUtils {
         let digits: String = card_number.chars().filter(|c| c.is_ascii_digit()).collect();
 
         if digits.len() >= 8 {
-            let visible_start = &digits[0..4];
-            let visible_end = &digits[digits.len() - 4..];
-            let masked_middle = "*".repeat(digits.len() - 8);
-            format!("{} {} {}", visible_start, masked_middle, visible_end)
+            Self::mask_fixed(&digits, 4, 4, " **** ")
         } else {
             card_number.to_string()
         }
@@ -189,14 +239,14 @@ impl FormatUtils {
             let username = &email[0..at_pos];
             let domain = &email[at_pos..];
 
-            if username.len() <= 2 {
-                format!("{}****{}", &username[0..1], domain)
+            let len = username.chars().count();
+            let masked = if len <= 2 {
+                Self::mask_fixed(username, 1, 0, "****")
             } else {
-                let visible_chars = (username.len() / 3).max(1);
-                let visible_start = &username[0..visible_chars];
-                let visible_end = &username[username.len() - visible_chars..];
-                format!("{}****{}{}", visible_start, visible_end, domain)
-            }
+                let visible_chars = (len / 3).max(1);
+                Self::mask_fixed(username, visible_chars, visible_chars, "****")
+            };
+            format!("{}{}", masked, domain)
         } else {
             email.to_string()
         }
@@ -208,13 +258,13 @@ impl FormatUtils {
             return String::new();
         }
 
-        // 计算每列的最大宽度
-        let mut col_widths = headers.iter().map(|h| h.len()).collect::<Vec<_>>();
+        // 计算每列的最大显示宽度（中日韩字符占两列）
+        let mut col_widths = headers.iter().map(|h| h.width()).collect::<Vec<_>>();
 
         for row in rows {
             for (i, cell) in row.iter().enumerate() {
                 if i < col_widths.len() {
-                    col_widths[i] = col_widths[i].max(cell.len());
+                    col_widths[i] = col_widths[i].max(cell.width());
                 }
             }
         }
@@ -224,7 +274,7 @@ impl FormatUtils {
         // 表头
         result.push('|');
         for (i, header) in headers.iter().enumerate() {
-            result.push_str(&format!(" {:width$} |", header, width = col_widths[i]));
+            result.push_str(&format!(" {} |", Self::pad_display(header, col_widths[i])));
         }
         result.push('\n');
 
@@ -239,8 +289,8 @@ impl FormatUtils {
         for row in rows {
             result.push('|');
             for (i, cell) in row.iter().enumerate() {
-                let width = col_widths.get(i).unwrap_or(&0);
-                result.push_str(&format!(" {:width$} |", cell, width = width));
+                let width = col_widths.get(i).copied().unwrap_or(0);
+                result.push_str(&format!(" {} |", Self::pad_display(cell, width)));
             }
             result.push('\n');
         }
@@ -257,14 +307,13 @@ impl FormatUtils {
     /// 格式化键值对列表
     pub fn format_key_value_list(data: &HashMap<String, String>) -> String {
         let mut result = String::new();
-        let max_key_length = data.keys().map(|k| k.len()).max().unwrap_or(0);
+        let max_key_width = data.keys().map(|k| k.width()).max().unwrap_or(0);
 
         for (key, value) in data {
             result.push_str(&format!(
-                "{:width$}: {}\n",
-                key,
-                value,
-                width = max_key_length
+                "{}: {}\n",
+                Self::pad_display(key, max_key_width),
+                value
             ));
         }
 
@@ -334,7 +383,7 @@ impl FormatUtils {
     /// 格式化框架文本
     pub fn format_boxed_text(text: &str, padding: usize) -> String {
         let lines: Vec<&str> = text.lines().collect();
-        let max_width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let max_width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
         let box_width = max_width + 2 * padding + 2; // +2 for borders
 
         let mut result = String::new();
@@ -345,12 +394,11 @@ impl FormatUtils {
         // 内容行
         for line in &lines {
             let padded_line = format!(
-                "│{:padding$}{:width$}{:padding$}│\n",
+                "│{:padding$}{}{:padding$}│\n",
                 "",
-                line,
+                Self::pad_display(line, max_width),
                 "",
-                padding = padding,
-                width = max_width
+                padding = padding
             );
             result.push_str(&padded_line);
         }
@@ -361,6 +409,15 @@ impl FormatUtils {
         result
     }
 
+    /// 按显示宽度右侧补齐空格
+    ///
+    /// 标准库的 `{:width$}` 按字符数补齐，中日韩等宽字符会导致错位，
+    /// 这里按终端显示宽度计算需要补充的空格数。
+    fn pad_display(text: &str, width: usize) -> String {
+        let padding = width.saturating_sub(text.width());
+        format!("{}{}", text, " ".repeat(padding))
+    }
+
     /// 格式化数字范围
     pub fn format_number_range(min: f64, max: f64, unit: &str) -> String {
         if (min - max).abs() < f64::EPSILON {
@@ -405,6 +462,27 @@ mod tests {
         assert_eq!(formatted, "$1,234.56");
     }
 
+    #[test]
+    fn test_format_ordinal() {
+        let cases = [
+            (1, "1st"),
+            (2, "2nd"),
+            (3, "3rd"),
+            (4, "4th"),
+            (11, "11th"),
+            (12, "12th"),
+            (13, "13th"),
+            (21, "21st"),
+            (101, "101st"),
+            (111, "111th"),
+        ];
+        for (n, expected) in cases {
+            assert_eq!(FormatUtils::format_ordinal(n), expected);
+        }
+        assert_eq!(FormatUtils::format_ordinal_cn(1), "第1");
+        assert_eq!(FormatUtils::format_ordinal_cn(111), "第111");
+    }
+
     #[test]
     fn test_format_file_size() {
         assert_eq!(FormatUtils::format_file_size(1024), "1.00 KB");
@@ -439,6 +517,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_masked_keeps_fixed_width() {
+        assert_eq!(
+            FormatUtils::format_email_masked("ab@example.com"),
+            "a****@example.com"
+        );
+        // 非 ASCII 用户名按字符截取，不会 panic
+        assert_eq!(
+            FormatUtils::format_email_masked("张三丰@example.com"),
+            "张****丰@example.com"
+        );
+        assert_eq!(
+            FormatUtils::format_bank_card_masked("6222 0212 3456 7890 123"),
+            "6222 **** 0123"
+        );
+        assert_eq!(
+            FormatUtils::format_id_card_masked("110101199001011234"),
+            "110101******1234"
+        );
+        assert_eq!(
+            FormatUtils::format_id_card_masked("110101900101123"),
+            "110101******123"
+        );
+    }
+
+    #[test]
+    fn test_mask_fixed() {
+        assert_eq!(
+            FormatUtils::mask_fixed("13812345678", 3, 4, "****"),
+            "138****5678"
+        );
+        assert_eq!(FormatUtils::mask_fixed("张三丰", 1, 0, "**"), "张**");
+        // 保留部分超过字符串长度时只返回占位符
+        assert_eq!(FormatUtils::mask_fixed("abc", 2, 2, "***"), "***");
+        assert_eq!(FormatUtils::mask_fixed("", 1, 1, "***"), "***");
+    }
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(FormatUtils::mask("13812345678", 3, 4, '*'), "138****5678");
+        assert_eq!(FormatUtils::mask("张三丰", 1, 0, '*'), "张**");
+        assert_eq!(FormatUtils::mask("abcd", 2, 2, '#'), "abcd");
+        // 保留部分超过字符串长度时全部隐藏
+        assert_eq!(FormatUtils::mask("abc", 2, 2, '*'), "***");
+        assert_eq!(FormatUtils::mask("", 1, 1, '*'), "");
+    }
+
     #[test]
     fn test_format_percentage() {
         assert_eq!(FormatUtils::format_percentage(0.1234, 2), "12.34%");
@@ -464,6 +589,33 @@ mod tests {
         assert!(table.contains("Alice"));
     }
 
+    #[test]
+    fn test_format_table_wide_chars() {
+        let headers = vec!["姓名", "Age"];
+        let rows = vec![
+            vec!["张三".to_string(), "25".to_string()],
+            vec!["Bob".to_string(), "30".to_string()],
+        ];
+
+        let table = FormatUtils::format_table(&headers, &rows);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "| 姓名 | Age |");
+        assert_eq!(lines[1], "| ---- | --- |");
+        assert_eq!(lines[2], "| 张三 | 25  |");
+        assert_eq!(lines[3], "| Bob  | 30  |");
+
+        // 每一行的显示宽度都应一致
+        let widths: Vec<usize> = lines.iter().map(|line| line.width()).collect();
+        assert!(widths.iter().all(|&w| w == widths[0]));
+    }
+
+    #[test]
+    fn test_format_boxed_text_wide_chars() {
+        let boxed = FormatUtils::format_boxed_text("你好\nHi", 1);
+        let widths: Vec<usize> = boxed.lines().map(|line| line.width()).collect();
+        assert!(widths.iter().all(|&w| w == widths[0]));
+    }
+
     #[test]
     fn test_format_boxed_text() {
         let boxed = FormatUtils::format_boxed_text("Hello\nWorld", 1);
@@ -32,6 +32,7 @@
  * - `format`: 格式化输出和显示
  * - `redis`: Redis 缓存和工具
  * - `device`: 设备类型检测和管理
+ * - `retry`: 暂时性错误的指数退避重试
  */
 
 /// JWT 身份验证工具
@@ -67,6 +68,9 @@ pub mod redis;
 /// 设备检测工具
 pub mod device;
 
+/// 重试工具
+pub mod retry;
+
 // 重新导出所有工具函数，方便外部使用
 pub use auth::*;
 pub use collection::*;
@@ -77,5 +81,6 @@ pub use format::*;
 pub use number::*;
 pub use password::*;
 pub use redis::*;
+pub use retry::*;
 pub use string::*;
 pub use time::*;
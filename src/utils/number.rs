@@ -1,6 +1,23 @@
 /// 数字工具结构体
 pub struct NumberUtils;
 
+/// 罗马数字符号表（按数值从大到小，包含减法形式）
+const ROMAN_NUMERALS: &[(u32, &str)] = &[
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
 impl NumberUtils {
     /// 安全地将字符串转换为数字
     pub fn parse_i32(s: &str) -> Option<i32> {
@@ -35,13 +52,13 @@ impl NumberUtils {
         if n == 2 {
             return true;
         }
-        if n % 2 == 0 {
+        if n.is_multiple_of(2) {
             return false;
         }
 
         let sqrt_n = (n as f64).sqrt() as u64;
         for i in (3..=sqrt_n).step_by(2) {
-            if n % i == 0 {
+            if n.is_multiple_of(i) {
                 return false;
             }
         }
@@ -67,12 +84,10 @@ impl NumberUtils {
     }
 
     /// 计算阶乘
-    pub fn factorial(n: u64) -> u64 {
-        if n <= 1 {
-            1
-        } else {
-            n * Self::factorial(n - 1)
-        }
+    ///
+    /// 结果超出 `u64` 范围（n ≥ 21）时返回 `None`
+    pub fn factorial(n: u64) -> Option<u64> {
+        (2..=n).try_fold(1u64, |acc, i| acc.checked_mul(i))
     }
 
     /// 计算斐波那契数列第 n 项
@@ -93,6 +108,36 @@ impl NumberUtils {
         }
     }
 
+    /// 计算斐波那契数列第 n 项
+    ///
+    /// 结果超出 `u64` 范围（n ≥ 94）时返回 `None`
+    pub fn fibonacci_checked(n: u64) -> Option<u64> {
+        // b 为下一项，溢出时记为 None，只有真正需要时才返回 None
+        let (mut a, mut b) = (0u64, Some(1u64));
+        for _ in 0..n {
+            let next = b?;
+            b = a.checked_add(next);
+            a = next;
+        }
+        Some(a)
+    }
+
+    /// 计算斐波那契数列第 n 项（任意精度）
+    ///
+    /// 需要启用 `bigint` feature
+    #[cfg(feature = "bigint")]
+    pub fn fibonacci_big(n: u64) -> num_bigint::BigUint {
+        let (mut a, mut b) = (
+            num_bigint::BigUint::from(0u8),
+            num_bigint::BigUint::from(1u8),
+        );
+        for _ in 0..n {
+            let next = &a + &b;
+            a = std::mem::replace(&mut b, next);
+        }
+        a
+    }
+
     /// 数字四舍五入到指定小数位
     pub fn round_to_decimal_places(n: f64, places: u32) -> f64 {
         let multiplier = 10_f64.powi(places as i32);
@@ -182,7 +227,7 @@ impl NumberUtils {
         sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
 
         let len = sorted.len();
-        if len % 2 == 0 {
+        if len.is_multiple_of(2) {
             Some((sorted[len / 2 - 1] + sorted[len / 2]) / 2.0)
         } else {
             Some(sorted[len / 2])
@@ -223,7 +268,7 @@ impl NumberUtils {
 
     /// 数字转换为进制字符串
     pub fn to_base(num: u64, base: u32) -> String {
-        if base < 2 || base > 36 {
+        if !(2..=36).contains(&base) {
             return "0".to_string();
         }
 
@@ -246,7 +291,7 @@ impl NumberUtils {
 
     /// 进制字符串转换为数字
     pub fn from_base(s: &str, base: u32) -> Option<u64> {
-        if base < 2 || base > 36 {
+        if !(2..=36).contains(&base) {
             return None;
         }
 
@@ -272,6 +317,48 @@ impl NumberUtils {
         Some(result)
     }
 
+    /// 数字转换为罗马数字（仅支持 1..=3999）
+    pub fn to_roman(n: u32) -> Option<String> {
+        if !(1..=3999).contains(&n) {
+            return None;
+        }
+
+        let mut result = String::new();
+        let mut remaining = n;
+
+        for &(value, symbol) in ROMAN_NUMERALS {
+            while remaining >= value {
+                result.push_str(symbol);
+                remaining -= value;
+            }
+        }
+
+        Some(result)
+    }
+
+    /// 罗马数字转换为数字
+    ///
+    /// 只接受标准写法（大小写均可），`IIII`、`IM` 等非标准写法返回 `None`
+    pub fn from_roman(s: &str) -> Option<u32> {
+        let upper = s.to_ascii_uppercase();
+        let mut rest = upper.as_str();
+        let mut result = 0u32;
+
+        for &(value, symbol) in ROMAN_NUMERALS {
+            while let Some(stripped) = rest.strip_prefix(symbol) {
+                result += value;
+                rest = stripped;
+            }
+        }
+
+        // 重新转换并比较，排除非标准写法
+        if !rest.is_empty() || Self::to_roman(result)? != upper {
+            return None;
+        }
+
+        Some(result)
+    }
+
     /// 数字格式化为货币字符串
     pub fn format_currency(amount: f64, currency_symbol: &str, decimal_places: u32) -> String {
         let formatted_amount = format!("{:.1$}", amount, decimal_places as usize);
@@ -293,6 +380,62 @@ impl NumberUtils {
         result
     }
 
+    /// 数字格式化为缩写字符串（如 `1.5K`、`12.0M`、`5.0B`）
+    ///
+    /// 按 `decimals` 位小数四舍五入后去掉多余的尾随零，带单位时至少保留一位小数
+    /// （`decimals` 为 0 时不保留）。四舍五入后达到下一单位时自动进位，如 `999_950 → 1.0M`。
+    pub fn format_abbreviated(n: f64, decimals: u32) -> String {
+        Self::format_with_units(
+            n,
+            decimals,
+            &[(1e3, "K"), (1e6, "M"), (1e9, "B"), (1e12, "T")],
+        )
+    }
+
+    /// 数字格式化为中文缩写字符串（如 `1.5万`、`3.2亿`）
+    ///
+    /// 规则与 `format_abbreviated` 相同，单位为万、亿、万亿
+    pub fn format_abbreviated_cn(n: f64, decimals: u32) -> String {
+        Self::format_with_units(n, decimals, &[(1e4, "万"), (1e8, "亿"), (1e12, "万亿")])
+    }
+
+    /// 按从小到大排列的单位缩写数字
+    fn format_with_units(n: f64, decimals: u32, units: &[(f64, &str)]) -> String {
+        if !n.is_finite() {
+            return n.to_string();
+        }
+
+        let sign = if n < 0.0 { "-" } else { "" };
+        let abs = n.abs();
+        let mut unit = units.iter().rposition(|&(size, _)| abs >= size);
+
+        loop {
+            let (size, suffix) = unit.map_or((1.0, ""), |i| units[i]);
+            let rounded = Self::round_to_decimal_places(abs / size, decimals);
+
+            // 四舍五入后达到下一单位时进位
+            let next = unit.map_or(0, |i| i + 1);
+            if next < units.len() && rounded * size >= units[next].0 {
+                unit = Some(next);
+                continue;
+            }
+
+            let formatted = format!("{:.1$}", rounded, decimals as usize);
+            let formatted = if formatted.contains('.') {
+                let trimmed = formatted.trim_end_matches('0');
+                match trimmed.strip_suffix('.') {
+                    Some(integer) if suffix.is_empty() => integer.to_string(),
+                    Some(integer) => format!("{}.0", integer),
+                    None => trimmed.to_string(),
+                }
+            } else {
+                formatted
+            };
+
+            return format!("{}{}{}", sign, formatted, suffix);
+        }
+    }
+
     /// 计算两点之间的距离
     pub fn distance_2d(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
         ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt()
@@ -339,6 +482,38 @@ mod tests {
         assert_eq!(NumberUtils::fibonacci(10), 55);
     }
 
+    #[test]
+    fn test_fibonacci_checked() {
+        assert_eq!(NumberUtils::fibonacci_checked(0), Some(0));
+        assert_eq!(NumberUtils::fibonacci_checked(10), Some(55));
+        assert_eq!(
+            NumberUtils::fibonacci_checked(93),
+            Some(12_200_160_415_121_876_738)
+        );
+        assert_eq!(NumberUtils::fibonacci_checked(94), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_fibonacci_big() {
+        assert_eq!(
+            NumberUtils::fibonacci_big(93),
+            num_bigint::BigUint::from(12_200_160_415_121_876_738u64)
+        );
+        assert_eq!(
+            NumberUtils::fibonacci_big(100).to_string(),
+            "354224848179261915075"
+        );
+    }
+
+    #[test]
+    fn test_factorial() {
+        assert_eq!(NumberUtils::factorial(0), Some(1));
+        assert_eq!(NumberUtils::factorial(5), Some(120));
+        assert_eq!(NumberUtils::factorial(20), Some(2_432_902_008_176_640_000));
+        assert_eq!(NumberUtils::factorial(21), None);
+    }
+
     #[test]
     fn test_percentage() {
         assert_eq!(NumberUtils::percentage(25.0, 100.0), 25.0);
@@ -351,6 +526,48 @@ mod tests {
         assert_eq!(NumberUtils::from_base("FF", 16), Some(255));
     }
 
+    #[test]
+    fn test_roman_numerals() {
+        assert_eq!(NumberUtils::to_roman(1994).as_deref(), Some("MCMXCIV"));
+        assert_eq!(NumberUtils::from_roman("MCMXCIV"), Some(1994));
+        assert_eq!(NumberUtils::from_roman("mcmxciv"), Some(1994));
+        for n in 1..=3999 {
+            let roman = NumberUtils::to_roman(n).unwrap();
+            assert_eq!(NumberUtils::from_roman(&roman), Some(n));
+        }
+
+        assert_eq!(NumberUtils::to_roman(0), None);
+        assert_eq!(NumberUtils::to_roman(4000), None);
+        assert_eq!(NumberUtils::from_roman("IIII"), None);
+        assert_eq!(NumberUtils::from_roman("IM"), None);
+        assert_eq!(NumberUtils::from_roman(""), None);
+        assert_eq!(NumberUtils::from_roman("ABC"), None);
+    }
+
+    #[test]
+    fn test_format_abbreviated() {
+        assert_eq!(NumberUtils::format_abbreviated(1500.0, 1), "1.5K");
+        assert_eq!(NumberUtils::format_abbreviated(12_000_000.0, 1), "12.0M");
+        assert_eq!(NumberUtils::format_abbreviated(5e9, 1), "5.0B");
+        assert_eq!(NumberUtils::format_abbreviated(1234.0, 2), "1.23K");
+        assert_eq!(NumberUtils::format_abbreviated(1200.0, 2), "1.2K");
+        assert_eq!(NumberUtils::format_abbreviated(1500.0, 0), "2K");
+        assert_eq!(NumberUtils::format_abbreviated(-1500.0, 1), "-1.5K");
+        assert_eq!(NumberUtils::format_abbreviated(999.0, 1), "999");
+        // 四舍五入后进位到下一单位
+        assert_eq!(NumberUtils::format_abbreviated(999_950.0, 1), "1.0M");
+    }
+
+    #[test]
+    fn test_format_abbreviated_cn() {
+        assert_eq!(NumberUtils::format_abbreviated_cn(15000.0, 1), "1.5万");
+        assert_eq!(
+            NumberUtils::format_abbreviated_cn(123_456_789.0, 2),
+            "1.23亿"
+        );
+        assert_eq!(NumberUtils::format_abbreviated_cn(9999.0, 1), "9999");
+    }
+
     #[test]
     fn test_statistics() {
         let numbers = vec![1.0, 2.0, 3.0, 4.0, 5.0];
@@ -3,14 +3,27 @@
  *
  * 使用 Argon2 算法提供安全的密码哈希和验证功能。
  * Argon2 是现代密码哈希的金标准，抗彩虹表和暴力破解攻击。
+ *
+ * 为兼容从旧系统迁移的用户，验证时也接受 bcrypt 哈希（`$2a$`/`$2b$`/`$2y$`），
+ * 这类哈希始终视为需要重新哈希，用户登录成功后会升级为 Argon2。
  */
 
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
+    Algorithm, Argon2, Version,
 };
 
-use crate::error::Result;
+use crate::{config::Argon2Params, error::Result};
+
+/// bcrypt 哈希的前缀
+const BCRYPT_PREFIXES: [&str; 3] = ["$2a$", "$2b$", "$2y$"];
+
+/// 判断存储的哈希是否为 bcrypt 格式
+pub fn is_bcrypt_hash(hash: &str) -> bool {
+    BCRYPT_PREFIXES
+        .iter()
+        .any(|prefix| hash.starts_with(prefix))
+}
 
 /// 哈希密码
 ///
@@ -27,6 +40,7 @@ use crate::error::Result;
 /// # 参数
 ///
 /// * `password` - 要哈希的明文密码
+/// * `params` - Argon2 成本参数（通常来自 `Config::argon2`）
 ///
 /// # 返回值
 ///
@@ -34,7 +48,7 @@ use crate::error::Result;
 ///
 /// # 错误
 ///
-/// - `AppError::PasswordHash`: 密码哈希处理失败
+/// - `AppError::PasswordHash`: 密码哈希处理失败或成本参数无效
 ///
 /// # 哈希格式
 ///
@@ -45,29 +59,34 @@ use crate::error::Result;
 /// - 哈希值 (Base64 编码)
 ///
 /// 格式示例：
-/// ```
+/// ```text
 /// $argon2id$v=19$m=19456,t=2,p=1$salt$hash
 /// ```
 ///
 /// # 示例
 ///
 /// ```rust
-/// use crate::utils::password::hash_password;
+/// use hello_rust::{config::Argon2Params, utils::password::hash_password};
 ///
 /// let password = "my_secure_password";
-/// let hash = hash_password(password)?;
+/// let params = Argon2Params::default();
+/// let hash = hash_password(password, &params)?;
 /// println!("Password hash: {}", hash);
 ///
 /// // 每次调用都会产生不同的哈希值
-/// let hash2 = hash_password(password)?;
+/// let hash2 = hash_password(password, &params)?;
 /// assert_ne!(hash, hash2); // 不同的哈希值
+/// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
-pub fn hash_password(password: &str) -> Result<String> {
+pub fn hash_password(password: &str, params: &Argon2Params) -> Result<String> {
     // 生成随机盐值
     let salt = SaltString::generate(&mut OsRng);
 
-    // 使用默认的 Argon2 配置
-    let argon2 = Argon2::default();
+    // 使用配置的成本参数构造 Argon2id 实例
+    let params = params
+        .to_params()
+        .map_err(|_| crate::error::AppError::PasswordHash)?;
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
 
     // 对密码进行哈希处理
     let password_hash = argon2
@@ -82,10 +101,12 @@ pub fn hash_password(password: &str) -> Result<String> {
 ///
 /// 验证明文密码是否与存储的哈希值匹配。
 /// 使用恒定时间比较算法，防止时序攻击。
+/// 成本参数从哈希字符串中读取，调整 `Argon2Params` 后旧密码仍然可以验证。
+/// bcrypt 格式的旧哈希使用 `bcrypt` 验证。
 ///
 /// # 验证过程
 ///
-/// 1. 解析存储的哈希字符串，提取算法参数和盐值
+/// 1. 根据前缀识别哈希格式，解析存储的哈希字符串，提取算法参数和盐值
 /// 2. 使用相同的参数和盐值对输入密码进行哈希
 /// 3. 使用恒定时间算法比较两个哈希值
 ///
@@ -113,22 +134,28 @@ pub fn hash_password(password: &str) -> Result<String> {
 /// # 示例
 ///
 /// ```rust
-/// use crate::utils::password::{hash_password, verify_password};
+/// use hello_rust::{config::Argon2Params, utils::password::{hash_password, verify_password}};
 ///
 /// let password = "my_secure_password";
-/// let hash = hash_password(password)?;
+/// let hash = hash_password(password, &Argon2Params::default())?;
 ///
 /// // 验证正确密码
 /// assert!(verify_password(password, &hash)?);
 ///
 /// // 验证错误密码
 /// assert!(!verify_password("wrong_password", &hash)?);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
 pub fn verify_password(password: &str, hash: &str) -> Result<bool> {
+    // 从旧系统迁移的 bcrypt 哈希
+    if is_bcrypt_hash(hash) {
+        return bcrypt::verify(password, hash).map_err(|_| crate::error::AppError::PasswordHash);
+    }
+
     // 解析存储的哈希字符串
     let parsed_hash = PasswordHash::new(hash).map_err(|_| crate::error::AppError::PasswordHash)?;
 
-    // 使用默认的 Argon2 验证器
+    // 验证器使用哈希字符串中记录的算法和参数，与默认配置无关
     let argon2 = Argon2::default();
 
     // 验证密码（使用恒定时间比较）
@@ -137,3 +164,122 @@ pub fn verify_password(password: &str, hash: &str) -> Result<bool> {
         Err(_) => Ok(false), // 密码错误
     }
 }
+
+/// 检查密码哈希是否需要重新计算
+///
+/// 解析存储的哈希字符串中的算法、版本和成本参数，与当前配置比较。
+/// 调整 `Argon2Params` 后，可以在用户登录成功时据此把旧哈希升级为新参数。
+///
+/// # 参数
+///
+/// * `hash` - 存储的密码哈希值
+/// * `current_params` - 当前配置的 Argon2 成本参数
+///
+/// # 返回值
+///
+/// 哈希不是 Argon2id v19（包括 bcrypt 哈希）、参数与当前配置不一致或无法解析时返回 `true`
+///
+/// # 示例
+///
+/// ```rust
+/// use hello_rust::{config::Argon2Params, utils::password::{hash_password, needs_rehash}};
+///
+/// let params = Argon2Params::default();
+/// let hash = hash_password("my_secure_password", &params)?;
+/// assert!(!needs_rehash(&hash, &params));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn needs_rehash(hash: &str, current_params: &Argon2Params) -> bool {
+    if is_bcrypt_hash(hash) {
+        return true;
+    }
+
+    let Ok(parsed_hash) = PasswordHash::new(hash) else {
+        return true;
+    };
+
+    // 算法或版本不同
+    if parsed_hash.algorithm != Algorithm::Argon2id.ident()
+        || parsed_hash.version != Some(Version::V0x13.into())
+    {
+        return true;
+    }
+
+    // 成本参数不同
+    match argon2::Params::try_from(&parsed_hash) {
+        Ok(params) => {
+            params.m_cost() != current_params.memory_kib
+                || params.t_cost() != current_params.iterations
+                || params.p_cost() != current_params.parallelism
+        }
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 低成本参数，加快测试速度
+    const LOW_COST: Argon2Params = Argon2Params {
+        memory_kib: 8,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    #[test]
+    fn test_hash_and_verify_with_custom_params() {
+        let hash = hash_password("my_secure_password", &LOW_COST).unwrap();
+        assert!(hash.starts_with("$argon2id$v=19$m=8,t=1,p=1$"));
+        assert!(verify_password("my_secure_password", &hash).unwrap());
+        assert!(!verify_password("wrong_password", &hash).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_params_rejected() {
+        let params = Argon2Params {
+            memory_kib: 1,
+            iterations: 0,
+            parallelism: 1,
+        };
+        assert!(params.to_params().is_err());
+        assert!(hash_password("my_secure_password", &params).is_err());
+    }
+
+    #[test]
+    fn test_needs_rehash() {
+        let hash = hash_password("my_secure_password", &LOW_COST).unwrap();
+        assert!(!needs_rehash(&hash, &LOW_COST));
+
+        // 任一成本参数变化都需要重新哈希
+        let higher_memory = Argon2Params {
+            memory_kib: 16,
+            ..LOW_COST
+        };
+        let more_iterations = Argon2Params {
+            iterations: 2,
+            ..LOW_COST
+        };
+        assert!(needs_rehash(&hash, &higher_memory));
+        assert!(needs_rehash(&hash, &more_iterations));
+
+        // 其他算法或无法解析的哈希同样需要重新哈希
+        let argon2i_hash = hash.replacen("$argon2id$", "$argon2i$", 1);
+        assert!(needs_rehash(&argon2i_hash, &LOW_COST));
+        assert!(needs_rehash("not-a-hash", &LOW_COST));
+    }
+
+    #[test]
+    fn test_verify_bcrypt_hash() {
+        // Openwall crypt_blowfish 测试向量
+        let hash = "$2a$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW";
+        assert!(is_bcrypt_hash(hash));
+        assert!(verify_password("U*U", hash).unwrap());
+        assert!(!verify_password("wrong_password", hash).unwrap());
+
+        // bcrypt 哈希登录后总是升级为 Argon2
+        assert!(needs_rehash(hash, &LOW_COST));
+        let argon2_hash = hash_password("U*U", &LOW_COST).unwrap();
+        assert!(!is_bcrypt_hash(&argon2_hash));
+    }
+}
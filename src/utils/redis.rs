@@ -17,6 +17,92 @@ pub mod cache_keys {
     pub const RATE_LIMIT_PREFIX: &str = "rate_limit:";
     /// 临时验证码前缀
     pub const VERIFICATION_PREFIX: &str = "verification:";
+    /// 验证码错误尝试次数前缀
+    pub const VERIFICATION_ATTEMPTS_PREFIX: &str = "verification_attempts:";
+    /// 令牌桶限流前缀
+    pub const TOKEN_BUCKET_PREFIX: &str = "token_bucket:";
+    /// 审计日志前缀
+    pub const AUDIT_PREFIX: &str = "audit:";
+    /// 审计事件发布频道前缀
+    pub const AUDIT_CHANNEL_PREFIX: &str = "audit_events:";
+    /// 幂等请求记录前缀
+    pub const IDEMPOTENCY_PREFIX: &str = "idempotency:";
+}
+
+/// 令牌桶限流的 Lua 脚本
+///
+/// 按距离上次补充的时间补充令牌（不超过容量），有令牌时消耗一个并允许请求。
+/// 使用 Redis 服务器时间，避免多个应用实例之间的时钟偏差。
+/// 返回 `{是否允许, 剩余令牌数（向下取整）}`。
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local refill_per_second = tonumber(ARGV[2])
+local time = redis.call("TIME")
+local now = tonumber(time[1]) + tonumber(time[2]) / 1000000
+
+local bucket = redis.call("HMGET", KEYS[1], "tokens", "last_refill")
+local tokens = tonumber(bucket[1])
+local last_refill = tonumber(bucket[2])
+if tokens == nil or last_refill == nil then
+    tokens = capacity
+    last_refill = now
+end
+
+tokens = math.min(capacity, tokens + math.max(0, now - last_refill) * refill_per_second)
+
+local allowed = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+end
+
+redis.call("HSET", KEYS[1], "tokens", tostring(tokens), "last_refill", tostring(now))
+-- 桶被补满之后的状态与新建的桶相同，可以直接过期
+redis.call("EXPIRE", KEYS[1], math.ceil(capacity / refill_per_second) + 1)
+
+return {allowed, math.floor(tokens)}
+"#;
+
+/// 校验并消费验证码的 Lua 脚本
+///
+/// `KEYS[1]` 为验证码，`KEYS[2]` 为错误尝试次数，`ARGV[1]` 为提交的验证码，`ARGV[2]` 为最大尝试次数。
+/// 比较和删除在同一个脚本中完成，并发的请求中只有一个能消费验证码。
+/// 验证码错误时累加尝试次数（与验证码同时过期），达到上限后删除验证码。
+/// 返回 1 表示验证通过，0 表示验证失败。
+const VERIFY_CODE_SCRIPT: &str = r#"
+local stored = redis.call("GET", KEYS[1])
+if not stored then
+    return 0
+end
+
+if stored == ARGV[1] then
+    redis.call("DEL", KEYS[1], KEYS[2])
+    return 1
+end
+
+local attempts = redis.call("INCR", KEYS[2])
+if attempts == 1 then
+    local ttl = redis.call("PTTL", KEYS[1])
+    if ttl > 0 then
+        redis.call("PEXPIRE", KEYS[2], ttl)
+    end
+end
+if attempts >= tonumber(ARGV[2]) then
+    redis.call("DEL", KEYS[1], KEYS[2])
+end
+return 0
+"#;
+
+/// 按模式删除缓存时每批 `SCAN` 的键数量提示
+const SCAN_BATCH_SIZE: usize = 500;
+
+/// 令牌桶限流结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBucketStatus {
+    /// 是否允许本次请求
+    pub allowed: bool,
+    /// 本次请求之后桶中剩余的令牌数（向下取整）
+    pub remaining: u32,
 }
 
 /// 缓存辅助工具结构体
@@ -25,11 +111,25 @@ pub struct CacheHelper {
 }
 
 impl CacheHelper {
+    /// 验证码允许的最大错误尝试次数，达到后验证码失效，需要重新获取
+    pub const MAX_CODE_ATTEMPTS: u32 = 5;
+
     /// 创建新的缓存辅助工具实例
     pub fn new(redis_utils: RedisUtils) -> Self {
         Self { redis_utils }
     }
 
+    /// 获取指定命名空间的缓存视图
+    ///
+    /// 命名空间内的操作只需要传入逻辑键，完整的键为 `{name}:{key}`，
+    /// 例如 `cache.namespace("user")` 中的键 `42` 对应 `user:42`。
+    pub fn namespace(&self, name: &str) -> Namespace<'_> {
+        Namespace {
+            cache: self,
+            prefix: format!("{}:", name),
+        }
+    }
+
     /// 缓存用户信息
     ///
     /// # 参数
@@ -188,6 +288,87 @@ impl CacheHelper {
         Ok(current <= limit)
     }
 
+    /// 执行限流检查，超出限制时返回限流错误
+    ///
+    /// 与 `rate_limit` 相同的计数逻辑，但在超出限制时直接返回
+    /// `AppError::RateLimited`，并将当前时间窗口的剩余秒数作为重试时间。
+    ///
+    /// # 参数
+    ///
+    /// * `identifier` - 限流标识符（如用户ID、IP地址等）
+    /// * `limit` - 限制次数
+    /// * `window_seconds` - 时间窗口（秒）
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<(), AppError>` - 超出限制时返回 `AppError::RateLimited`
+    pub async fn enforce_rate_limit(
+        &self,
+        identifier: &str,
+        limit: i64,
+        window_seconds: u64,
+    ) -> Result<()> {
+        if self.rate_limit(identifier, limit, window_seconds).await? {
+            return Ok(());
+        }
+
+        let key = format!("{}{}", cache_keys::RATE_LIMIT_PREFIX, identifier);
+        let retry_after = self.redis_utils.ttl(&key).await?.or(Some(window_seconds));
+
+        Err(AppError::RateLimited { retry_after })
+    }
+
+    /// 令牌桶限流
+    ///
+    /// 与固定窗口的 `rate_limit` 不同，令牌桶按固定速率平滑补充令牌，
+    /// 同时允许不超过 `capacity` 的突发请求。桶的状态存储在 Redis 哈希
+    /// （`tokens`、`last_refill` 字段）中，通过 Lua 脚本原子地补充和消耗令牌。
+    ///
+    /// # 参数
+    ///
+    /// * `identifier` - 限流标识符（如用户ID、IP地址等）
+    /// * `capacity` - 桶容量（允许的最大突发请求数）
+    /// * `refill_per_second` - 每秒补充的令牌数
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<TokenBucketStatus, AppError>` - 是否允许请求以及剩余令牌数
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Internal`: 参数无效（容量为 0 或补充速率不是正数）或 Redis 操作失败
+    pub async fn rate_limit_token_bucket(
+        &self,
+        identifier: &str,
+        capacity: u32,
+        refill_per_second: f64,
+    ) -> Result<TokenBucketStatus> {
+        if capacity == 0 || !(refill_per_second.is_finite() && refill_per_second > 0.0) {
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Invalid token bucket parameters: capacity={}, refill_per_second={}",
+                capacity,
+                refill_per_second
+            )));
+        }
+
+        let key = format!("{}{}", cache_keys::TOKEN_BUCKET_PREFIX, identifier);
+        let mut conn = self.redis_utils.manager.connection()?.clone();
+        let (allowed, remaining): (u32, u32) = redis::Script::new(TOKEN_BUCKET_SCRIPT)
+            .key(key)
+            .arg(capacity)
+            .arg(refill_per_second)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Redis token bucket script failed: {}", e))
+            })?;
+
+        Ok(TokenBucketStatus {
+            allowed: allowed == 1,
+            remaining,
+        })
+    }
+
     /// 获取当前限流计数
     ///
     /// # 参数
@@ -211,6 +392,8 @@ impl CacheHelper {
 
     /// 设置验证码
     ///
+    /// 新的验证码会覆盖旧验证码，并重置错误尝试次数。
+    ///
     /// # 参数
     ///
     /// * `identifier` - 标识符（如邮箱、手机号等）
@@ -227,13 +410,21 @@ impl CacheHelper {
         ttl_seconds: u64,
     ) -> Result<()> {
         let key = format!("{}{}", cache_keys::VERIFICATION_PREFIX, identifier);
+        let attempts_key = format!("{}{}", cache_keys::VERIFICATION_ATTEMPTS_PREFIX, identifier);
         self.redis_utils
             .set_string(key, code, Some(ttl_seconds))
-            .await
+            .await?;
+        self.redis_utils.delete(attempts_key).await?;
+
+        Ok(())
     }
 
     /// 验证并消费验证码
     ///
+    /// 比较和删除是原子操作，同一验证码只能被消费一次。
+    /// 错误尝试达到 `MAX_CODE_ATTEMPTS` 次后验证码失效，即使之后提交正确的验证码也会失败，
+    /// 因此不需要恒定时间比较来防止逐位猜测。
+    ///
     /// # 参数
     ///
     /// * `identifier` - 标识符
@@ -244,18 +435,21 @@ impl CacheHelper {
     /// 返回 `Result<bool, AppError>` - true表示验证通过，false表示验证失败
     pub async fn verify_and_consume_code(&self, identifier: &str, code: &str) -> Result<bool> {
         let key = format!("{}{}", cache_keys::VERIFICATION_PREFIX, identifier);
+        let attempts_key = format!("{}{}", cache_keys::VERIFICATION_ATTEMPTS_PREFIX, identifier);
+
+        let mut conn = self.redis_utils.manager.connection()?.clone();
+        let verified: u32 = redis::Script::new(VERIFY_CODE_SCRIPT)
+            .key(key)
+            .key(attempts_key)
+            .arg(code)
+            .arg(Self::MAX_CODE_ATTEMPTS)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Redis verify code script failed: {}", e))
+            })?;
 
-        if let Some(stored_code) = self.redis_utils.get_string(&key).await? {
-            if stored_code == code {
-                // 验证成功，删除验证码
-                self.redis_utils.delete(key).await?;
-                Ok(true)
-            } else {
-                Ok(false)
-            }
-        } else {
-            Ok(false)
-        }
+        Ok(verified == 1)
     }
 
     /// 添加到列表缓存（如活动日志、消息队列等）
@@ -287,7 +481,7 @@ impl CacheHelper {
         // 如果设置了最大长度，则修剪列表
         if let Some(max_len) = max_length {
             use redis::AsyncCommands;
-            let mut conn = self.redis_utils.manager.connection().clone();
+            let mut conn = self.redis_utils.manager.connection()?.clone();
             let _: () = conn
                 .ltrim(list_key, 0, (max_len as isize) - 1)
                 .await
@@ -314,7 +508,7 @@ impl CacheHelper {
     {
         use redis::AsyncCommands;
 
-        let mut conn = self.redis_utils.manager.connection().clone();
+        let mut conn = self.redis_utils.manager.connection()?.clone();
         let items: Vec<String> = conn
             .lrange(list_key, start as isize, end as isize)
             .await
@@ -348,7 +542,7 @@ impl CacheHelper {
     ) -> Result<()> {
         use redis::AsyncCommands;
 
-        let mut conn = self.redis_utils.manager.connection().clone();
+        let mut conn = self.redis_utils.manager.connection()?.clone();
 
         for (key, value) in items {
             if let Some(seconds) = ttl_seconds {
@@ -377,7 +571,7 @@ impl CacheHelper {
     pub async fn batch_get(&self, keys: Vec<String>) -> Result<Vec<Option<String>>> {
         use redis::AsyncCommands;
 
-        let mut conn = self.redis_utils.manager.connection().clone();
+        let mut conn = self.redis_utils.manager.connection()?.clone();
         let values: Vec<Option<String>> = conn
             .mget(keys)
             .await
@@ -386,27 +580,310 @@ impl CacheHelper {
         Ok(values)
     }
 
+    /// 按模式批量删除缓存（如清除所有 `user:*` 缓存）
+    ///
+    /// 使用 `SCAN MATCH` 分批遍历匹配的键并用 `UNLINK` 删除，避免 `KEYS` 阻塞 Redis。
+    /// 为防止误删全部数据，模式必须以非空的字面前缀开头（如 `user:*`，而不是 `*` 或 `*:1`）。
+    /// 遍历期间新写入的匹配键不保证会被删除。
+    ///
+    /// # 参数
+    ///
+    /// * `pattern` - Redis glob 模式
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<u64, AppError>` - 删除的键数量
+    ///
+    /// # 错误
+    ///
+    /// - `AppError::Validation`: 模式没有字面前缀
+    /// - `AppError::Internal`: Redis 操作失败
+    pub async fn delete_by_pattern(&self, pattern: &str) -> Result<u64> {
+        let prefix_len = pattern.find(['*', '?', '[', '\\']).unwrap_or(pattern.len());
+        if prefix_len == 0 {
+            return Err(AppError::Validation(
+                "Cache key pattern must start with a non-empty prefix".to_string(),
+            ));
+        }
+
+        let mut conn = self.redis_utils.manager.connection()?.clone();
+        let mut cursor: u64 = 0;
+        let mut deleted: u64 = 0;
+
+        loop {
+            let (next_cursor, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH_SIZE)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| AppError::Internal(anyhow::anyhow!("Redis scan failed: {}", e)))?;
+
+            if !keys.is_empty() {
+                let count: u64 = redis::cmd("UNLINK")
+                    .arg(&keys)
+                    .query_async(&mut conn)
+                    .await
+                    .map_err(|e| {
+                        AppError::Internal(anyhow::anyhow!("Redis unlink failed: {}", e))
+                    })?;
+                deleted += count;
+            }
+
+            if next_cursor == 0 {
+                return Ok(deleted);
+            }
+            cursor = next_cursor;
+        }
+    }
+
     /// 检查缓存健康状态
     ///
     /// # 返回值
     ///
     /// 返回 `Result<bool, AppError>` - true表示Redis连接正常
     pub async fn health_check(&self) -> Result<bool> {
-        // 通过简单的设置和删除操作来检查连接状态
-        let test_key = "health_check_test";
-        let test_value = "ok";
+        // 通过 PING 命令检查连接状态，连接失败视为不健康
+        match self.redis_utils.ping().await {
+            Ok(pong) => Ok(pong),
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+/// 带键前缀的缓存视图
+///
+/// 由 `CacheHelper::namespace` 创建，自动为所有键加上命名空间前缀，
+/// 避免调用方手动拼接键名。
+pub struct Namespace<'a> {
+    cache: &'a CacheHelper,
+    prefix: String,
+}
+
+impl Namespace<'_> {
+    /// 返回逻辑键对应的完整键名
+    pub fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    /// 获取 JSON 对象
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<Option<T>, AppError>`，键不存在时返回 None
+    pub async fn get_json<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.cache.redis_utils.get_json(self.key(key)).await
+    }
 
-        match self
+    /// 设置 JSON 对象
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 逻辑键
+    /// * `value` - 可序列化的值
+    /// * `ttl_seconds` - 过期时间（秒），None 表示使用默认过期时间
+    pub async fn set_json<T>(&self, key: &str, value: &T, ttl_seconds: Option<u64>) -> Result<()>
+    where
+        T: Serialize,
+    {
+        self.cache
             .redis_utils
-            .set_string(test_key, test_value, Some(1))
+            .set_json(self.key(key), value, ttl_seconds)
             .await
-        {
-            Ok(_) => {
-                // 尝试删除测试键
-                let _ = self.redis_utils.delete(test_key).await;
-                Ok(true)
-            }
-            Err(_) => Ok(false),
+    }
+
+    /// 删除键
+    ///
+    /// # 返回值
+    ///
+    /// 返回 `Result<bool, AppError>`，true 表示键存在并被删除
+    pub async fn delete(&self, key: &str) -> Result<bool> {
+        self.cache.redis_utils.delete(self.key(key)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::test_support;
+
+    #[test]
+    fn test_namespace_key() {
+        let cache = CacheHelper::new(RedisUtils::new(crate::RedisManager::in_memory()));
+        assert_eq!(cache.namespace("user").key("42"), "user:42");
+        assert_eq!(
+            cache.namespace("session").key("abc"),
+            format!("{}abc", cache_keys::SESSION_PREFIX)
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_namespaces_do_not_collide() {
+        let cache = CacheHelper::new(RedisUtils::new(test_support::redis_manager().await));
+        let users = cache.namespace(&test_support::unique_key("users"));
+        let orders = cache.namespace(&test_support::unique_key("orders"));
+
+        users.set_json("1", &"alice", Some(60)).await.unwrap();
+        orders.set_json("1", &42, Some(60)).await.unwrap();
+
+        assert_eq!(
+            users.get_json::<String>("1").await.unwrap(),
+            Some("alice".to_string())
+        );
+        assert_eq!(orders.get_json::<i32>("1").await.unwrap(), Some(42));
+        assert_eq!(
+            cache.redis_utils.get_string(users.key("1")).await.unwrap(),
+            Some("\"alice\"".to_string())
+        );
+
+        assert!(users.delete("1").await.unwrap());
+        assert_eq!(users.get_json::<String>("1").await.unwrap(), None);
+        assert_eq!(orders.get_json::<i32>("1").await.unwrap(), Some(42));
+        assert!(orders.delete("1").await.unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_delete_by_pattern() {
+        let manager = test_support::redis_manager().await;
+        let redis_utils = RedisUtils::new(manager.clone());
+        let cache = CacheHelper::new(RedisUtils::new(manager));
+        let prefix = test_support::unique_key("pattern");
+        let other = test_support::unique_key("pattern");
+
+        for i in 0..5 {
+            redis_utils
+                .set_string(format!("{}:{}", prefix, i), i, Some(60))
+                .await
+                .unwrap();
+        }
+        redis_utils
+            .set_string(format!("{}:0", other), 0, Some(60))
+            .await
+            .unwrap();
+
+        let deleted = cache
+            .delete_by_pattern(&format!("{}:*", prefix))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 5);
+        assert!(!redis_utils.exists(format!("{}:0", prefix)).await.unwrap());
+        // 其他前缀下的键不受影响
+        assert!(redis_utils.exists(format!("{}:0", other)).await.unwrap());
+
+        // 没有字面前缀的模式被拒绝
+        for pattern in ["", "*", "?:1", "[a-z]*"] {
+            assert!(matches!(
+                cache.delete_by_pattern(pattern).await,
+                Err(AppError::Validation(_))
+            ));
+        }
+
+        redis_utils.delete(format!("{}:0", other)).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_verification_code_is_consumed_once() {
+        let cache = CacheHelper::new(RedisUtils::new(test_support::redis_manager().await));
+        let identifier = test_support::unique_key("code");
+
+        cache
+            .set_verification_code(&identifier, "123456", 60)
+            .await
+            .unwrap();
+
+        // 并发提交正确的验证码时只有一个请求通过
+        let results = futures_util::future::join_all(
+            (0..5).map(|_| cache.verify_and_consume_code(&identifier, "123456")),
+        )
+        .await;
+        let verified = results.into_iter().filter(|r| *r.as_ref().unwrap()).count();
+        assert_eq!(verified, 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_verification_code_invalidated_after_max_attempts() {
+        let cache = CacheHelper::new(RedisUtils::new(test_support::redis_manager().await));
+        let identifier = test_support::unique_key("code");
+
+        cache
+            .set_verification_code(&identifier, "123456", 60)
+            .await
+            .unwrap();
+        for _ in 0..CacheHelper::MAX_CODE_ATTEMPTS {
+            assert!(!cache
+                .verify_and_consume_code(&identifier, "000000")
+                .await
+                .unwrap());
+        }
+
+        // 错误次数达到上限后，正确的验证码也不再有效
+        assert!(!cache
+            .verify_and_consume_code(&identifier, "123456")
+            .await
+            .unwrap());
+
+        // 重新发送验证码后重置尝试次数
+        cache
+            .set_verification_code(&identifier, "654321", 60)
+            .await
+            .unwrap();
+        assert!(!cache
+            .verify_and_consume_code(&identifier, "000000")
+            .await
+            .unwrap());
+        assert!(cache
+            .verify_and_consume_code(&identifier, "654321")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis instance"]
+    async fn test_rate_limit_token_bucket() {
+        let cache = CacheHelper::new(RedisUtils::new(test_support::redis_manager().await));
+        let identifier = test_support::unique_key("bucket");
+
+        // 突发请求不超过容量时全部允许
+        for expected_remaining in [2, 1, 0] {
+            let status = cache
+                .rate_limit_token_bucket(&identifier, 3, 5.0)
+                .await
+                .unwrap();
+            assert!(status.allowed);
+            assert_eq!(status.remaining, expected_remaining);
         }
+
+        // 令牌耗尽后拒绝
+        let status = cache
+            .rate_limit_token_bucket(&identifier, 3, 5.0)
+            .await
+            .unwrap();
+        assert!(!status.allowed);
+        assert_eq!(status.remaining, 0);
+
+        // 等待补充令牌后恢复
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        let status = cache
+            .rate_limit_token_bucket(&identifier, 3, 5.0)
+            .await
+            .unwrap();
+        assert!(status.allowed);
+
+        assert!(cache
+            .rate_limit_token_bucket(&identifier, 0, 5.0)
+            .await
+            .is_err());
+
+        let key = format!("{}{}", cache_keys::TOKEN_BUCKET_PREFIX, identifier);
+        cache.redis_utils.delete(key).await.unwrap();
     }
 }
@@ -0,0 +1,188 @@
+/*!
+ * 重试工具模块
+ *
+ * 为可能因网络抖动而暂时失败的操作（如启动时连接数据库和 Redis）
+ * 提供带指数退避和随机抖动的重试。
+ */
+
+use std::{fmt::Display, future::Future, time::Duration};
+
+use rand::Rng;
+
+/// 启动阶段连接外部服务的最大尝试次数
+pub const STARTUP_ATTEMPTS: u32 = 5;
+
+/// 启动阶段重试的初始等待时间
+pub const STARTUP_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// 单次等待时间上限
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// 带指数退避的重试
+///
+/// 执行 `operation`，失败且错误满足 `is_retryable` 时等待后重试，最多尝试 `attempts` 次。
+/// 第 n 次重试前等待 `base_delay * 2^(n-1)`（不超过 30 秒），并随机缩短至 50%~100%，
+/// 避免多个实例同时重试。
+///
+/// # 参数
+///
+/// * `attempts` - 最大尝试次数（包含第一次，0 视为 1）
+/// * `base_delay` - 第一次重试前的等待时间
+/// * `is_retryable` - 判断错误是否值得重试
+/// * `operation` - 要执行的异步操作
+///
+/// # 返回值
+///
+/// 返回第一次成功的结果；错误不可重试或达到最大尝试次数时返回最后一次的错误
+///
+/// # 示例
+///
+/// ```rust
+/// use std::time::Duration;
+/// use hello_rust::utils::retry::{is_transient_redis_error, with_backoff};
+///
+/// # async fn example(client: redis::Client) -> redis::RedisResult<()> {
+/// let manager = with_backoff(5, Duration::from_millis(500), is_transient_redis_error, || {
+///     client.get_connection_manager()
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn with_backoff<T, E, F, Fut, P>(
+    attempts: u32,
+    base_delay: Duration,
+    is_retryable: P,
+    mut operation: F,
+) -> Result<T, E>
+where
+    E: Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    P: Fn(&E) -> bool,
+{
+    let attempts = attempts.max(1);
+    let mut delay = base_delay;
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let err = match operation().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+
+        if attempt >= attempts || !is_retryable(&err) {
+            return Err(err);
+        }
+
+        let jittered = delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0));
+        tracing::warn!(
+            "第 {}/{} 次尝试失败，{:?} 后重试: {}",
+            attempt,
+            attempts,
+            jittered,
+            err
+        );
+        tokio::time::sleep(jittered).await;
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
+
+/// 判断 Redis 错误是否为暂时性的连接错误
+pub fn is_transient_redis_error(err: &redis::RedisError) -> bool {
+    err.is_io_error()
+        || err.is_connection_refusal()
+        || err.is_connection_dropped()
+        || err.is_timeout()
+}
+
+/// 判断数据库错误是否为暂时性的连接错误
+///
+/// 包括 IO 错误、获取连接超时，以及 PostgreSQL 的连接异常（SQLSTATE `08xxx`）
+/// 和数据库正在启动（`57P03`）。
+pub fn is_transient_sqlx_error(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(db_err) => db_err
+            .code()
+            .is_some_and(|code| code.starts_with("08") || code == "57P03"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_backoff_retries_until_success() {
+        let mut calls = 0;
+        let result: Result<u32, &str> = with_backoff(
+            5,
+            Duration::from_millis(1),
+            |_| true,
+            || {
+                calls += 1;
+                let call = calls;
+                async move {
+                    if call < 3 {
+                        Err("transient")
+                    } else {
+                        Ok(call)
+                    }
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(result, Ok(3));
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_with_backoff_gives_up() {
+        // 达到最大尝试次数后返回最后一次的错误
+        let mut calls = 0;
+        let result: Result<(), String> = with_backoff(
+            3,
+            Duration::from_millis(1),
+            |_| true,
+            || {
+                calls += 1;
+                let call = calls;
+                async move { Err(format!("failure {}", call)) }
+            },
+        )
+        .await;
+        assert_eq!(result, Err("failure 3".to_string()));
+        assert_eq!(calls, 3);
+
+        // 不可重试的错误立即返回
+        let mut calls = 0;
+        let result: Result<(), &str> = with_backoff(
+            3,
+            Duration::from_millis(1),
+            |_| false,
+            || {
+                calls += 1;
+                async { Err("fatal") }
+            },
+        )
+        .await;
+        assert_eq!(result, Err("fatal"));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_transient_error_predicates() {
+        assert!(is_transient_sqlx_error(&sqlx::Error::PoolTimedOut));
+        assert!(!is_transient_sqlx_error(&sqlx::Error::RowNotFound));
+
+        let refused: redis::RedisError =
+            std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into();
+        assert!(is_transient_redis_error(&refused));
+        let type_error: redis::RedisError = (redis::ErrorKind::TypeError, "bad type").into();
+        assert!(!is_transient_redis_error(&type_error));
+    }
+}
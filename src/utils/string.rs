@@ -99,6 +99,47 @@ impl StringUtils {
             .join(" ")
     }
 
+    /// 生成 URL slug
+    ///
+    /// 转为小写，保留字母和数字（包括中文等 Unicode 字符），
+    /// 其余字符视为分隔符，连续分隔符合并为单个连字符，并去掉首尾连字符。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use hello_rust::utils::StringUtils;
+    ///
+    /// assert_eq!(StringUtils::slugify("Hello, World!"), "hello-world");
+    /// assert_eq!(StringUtils::slugify("Rust 入门 教程"), "rust-入门-教程");
+    /// ```
+    pub fn slugify(s: &str) -> String {
+        let mut slug = String::with_capacity(s.len());
+        let mut pending_separator = false;
+
+        for c in s.chars() {
+            if c.is_alphanumeric() {
+                if pending_separator && !slug.is_empty() {
+                    slug.push('-');
+                }
+                pending_separator = false;
+                slug.extend(c.to_lowercase());
+            } else {
+                pending_separator = true;
+            }
+        }
+
+        slug
+    }
+
+    /// 生成限制最大长度的 URL slug
+    ///
+    /// 长度按字节计算（与 `truncate` 一致），不会截断多字节字符，截断后不会以连字符结尾。
+    pub fn slugify_with_max_length(s: &str, max_length: usize) -> String {
+        Self::truncate(&Self::slugify(s), max_length)
+            .trim_end_matches('-')
+            .to_string()
+    }
+
     /// 反转字符串（支持 Unicode）
     pub fn reverse(s: &str) -> String {
         s.graphemes(true).rev().collect()
@@ -150,20 +191,12 @@ impl StringUtils {
 
     /// 移除前缀
     pub fn remove_prefix(s: &str, prefix: &str) -> String {
-        if s.starts_with(prefix) {
-            s[prefix.len()..].to_string()
-        } else {
-            s.to_string()
-        }
+        s.strip_prefix(prefix).unwrap_or(s).to_string()
     }
 
     /// 移除后缀
     pub fn remove_suffix(s: &str, suffix: &str) -> String {
-        if s.ends_with(suffix) {
-            s[..s.len() - suffix.len()].to_string()
-        } else {
-            s.to_string()
-        }
+        s.strip_suffix(suffix).unwrap_or(s).to_string()
     }
 
     /// 提取数字
@@ -190,6 +223,13 @@ impl StringUtils {
         re.is_match(email)
     }
 
+    /// 规范化邮箱地址（去除首尾空白并转为小写）
+    ///
+    /// 用户邮箱统一以规范化形式存储和查询，避免大小写不同的同一邮箱重复注册
+    pub fn normalize_email(email: &str) -> String {
+        email.trim().to_lowercase()
+    }
+
     /// 验证手机号格式（中国）
     pub fn is_valid_phone_cn(phone: &str) -> bool {
         let re = Regex::new(r"^1[3-9]\d{9}$").unwrap();
@@ -232,9 +272,11 @@ impl StringUtils {
     }
 
     /// 计算字符串相似度（编辑距离）
+    ///
+    /// 返回 0.0 到 1.0 之间的值，按字素簇数量（参见 `char_count`）归一化。
     pub fn similarity(s1: &str, s2: &str) -> f64 {
         let distance = Self::levenshtein_distance(s1, s2);
-        let max_len = s1.len().max(s2.len());
+        let max_len = Self::char_count(s1).max(Self::char_count(s2));
 
         if max_len == 0 {
             1.0
@@ -243,21 +285,55 @@ impl StringUtils {
         }
     }
 
+    /// 模糊搜索：按与查询的相似度对候选项排序
+    ///
+    /// 使用忽略大小写的归一化编辑距离（参见 `similarity`）打分，
+    /// 返回得分最高的最多 `limit` 个候选项及其得分，按得分降序排列，
+    /// 得分相同时保持候选项的原始顺序；得分为 0 的候选项不会返回。
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use hello_rust::utils::StringUtils;
+    ///
+    /// let matches = StringUtils::fuzzy_search("pyhton", &["java", "python", "rust"], 1);
+    /// assert_eq!(matches[0].0, "python");
+    /// ```
+    pub fn fuzzy_search(query: &str, candidates: &[&str], limit: usize) -> Vec<(String, f64)> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(String, f64)> = candidates
+            .iter()
+            .map(|candidate| {
+                let score = Self::similarity(&query, &candidate.to_lowercase());
+                (candidate.to_string(), score)
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+
+        // sort_by 是稳定排序，得分相同的候选项保持原始顺序
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+        matches.truncate(limit);
+        matches
+    }
+
     /// 计算编辑距离
+    ///
+    /// 以字素簇（用户感知的字符）为单位计算，带组合音标的字母或带肤色修饰的 emoji
+    /// 等由多个码点组成的字符只算一次编辑。
     pub fn levenshtein_distance(s1: &str, s2: &str) -> usize {
-        let s1_chars: Vec<char> = s1.chars().collect();
-        let s2_chars: Vec<char> = s2.chars().collect();
+        let s1_chars: Vec<&str> = s1.graphemes(true).collect();
+        let s2_chars: Vec<&str> = s2.graphemes(true).collect();
         let s1_len = s1_chars.len();
         let s2_len = s2_chars.len();
 
         let mut matrix = vec![vec![0; s2_len + 1]; s1_len + 1];
 
-        for i in 0..=s1_len {
-            matrix[i][0] = i;
+        for (i, row) in matrix.iter_mut().enumerate() {
+            row[0] = i;
         }
 
-        for j in 0..=s2_len {
-            matrix[0][j] = j;
+        for (j, cell) in matrix[0].iter_mut().enumerate() {
+            *cell = j;
         }
 
         for i in 1..=s1_len {
@@ -274,17 +350,56 @@ impl StringUtils {
                 ]
                 .iter()
                 .min()
-                .unwrap()
-                .clone();
+                .copied()
+                .unwrap();
             }
         }
 
         matrix[s1_len][s2_len]
     }
 
+    /// 根据数量选择单数或复数形式
+    ///
+    /// `count` 为 1 时返回 `singular`，其他情况（包括 0 和负数）返回 `plural`。
+    pub fn pluralize<'a>(count: i64, singular: &'a str, plural: &'a str) -> &'a str {
+        if count == 1 {
+            singular
+        } else {
+            plural
+        }
+    }
+
     /// 字符串模板替换
+    ///
+    /// - `{key}`: 替换为变量 `key` 的值
+    /// - `{key:singular|plural}`: 把变量 `key` 的值当作数量，按 `pluralize` 的规则
+    ///   替换为单数或复数形式；变量不存在或不是整数时保留占位符
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use hello_rust::utils::StringUtils;
+    /// use std::collections::HashMap;
+    ///
+    /// let variables = HashMap::from([("count".to_string(), "2".to_string())]);
+    /// assert_eq!(
+    ///     StringUtils::template_replace("{count} {count:item|items}", &variables),
+    ///     "2 items"
+    /// );
+    /// ```
     pub fn template_replace(template: &str, variables: &HashMap<String, String>) -> String {
-        let mut result = template.to_string();
+        let plural_re = Regex::new(r"\{(\w+):([^{}|]*)\|([^{}]*)\}").unwrap();
+        let mut result = plural_re
+            .replace_all(template, |caps: &regex::Captures| {
+                match variables
+                    .get(&caps[1])
+                    .and_then(|value| value.trim().parse::<i64>().ok())
+                {
+                    Some(count) => Self::pluralize(count, &caps[2], &caps[3]).to_string(),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned();
 
         for (key, value) in variables {
             let placeholder = format!("{{{}}}", key);
@@ -294,10 +409,23 @@ impl StringUtils {
         result
     }
 
-    /// 移除 HTML 标签
+    /// 移除 HTML 标签，得到用于显示的纯文本
+    ///
+    /// 依次移除 `<script>`/`<style>` 元素（连同其中的内容，未闭合时移除到末尾）、
+    /// HTML 注释和其余标签，最后用 `unescape_html` 解码实体。
+    ///
+    /// 仅用于把 HTML 规范化为显示文本（如摘要、搜索索引），不能用于安全过滤：
+    /// 解码后的文本可能重新包含 `<`、`>`，输出到 HTML 前仍需要 `escape_html`。
     pub fn strip_html(html: &str) -> String {
-        let re = Regex::new(r"<[^>]*>").unwrap();
-        re.replace_all(html, "").to_string()
+        let hidden_re = Regex::new(
+            r"(?is)<script\b[^>]*>.*?(?:</script\s*>|$)|<style\b[^>]*>.*?(?:</style\s*>|$)|<!--.*?(?:-->|$)",
+        )
+        .unwrap();
+        let tag_re = Regex::new(r"<[^>]*>").unwrap();
+
+        let without_hidden = hidden_re.replace_all(html, "");
+        let text = tag_re.replace_all(&without_hidden, "");
+        Self::unescape_html(&text)
     }
 
     /// 转义 HTML 字符
@@ -309,6 +437,44 @@ impl StringUtils {
             .replace('\'', "&#x27;")
     }
 
+    /// 反转义 HTML 字符
+    ///
+    /// 支持 `&amp;`、`&lt;`、`&gt;`、`&quot;`、`&apos;` 以及十进制（`&#39;`）和
+    /// 十六进制（`&#x27;`）数字实体。无法识别的实体或无效的码点原样保留。
+    /// 只解码一遍，`&amp;lt;` 解码为 `&lt;` 而不是 `<`。
+    pub fn unescape_html(s: &str) -> String {
+        let re = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap();
+        re.replace_all(s, |caps: &regex::Captures| {
+            let entity = &caps[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => {
+                    let code = if let Some(hex) = entity
+                        .strip_prefix("#x")
+                        .or_else(|| entity.strip_prefix("#X"))
+                    {
+                        u32::from_str_radix(hex, 16).ok()
+                    } else if let Some(dec) = entity.strip_prefix('#') {
+                        dec.parse().ok()
+                    } else {
+                        None
+                    };
+                    code.and_then(char::from_u32)
+                }
+            };
+
+            match decoded {
+                Some(c) => c.to_string(),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+    }
+
     /// 单词计数
     pub fn word_count(s: &str) -> usize {
         s.split_whitespace().count()
@@ -363,4 +529,139 @@ mod tests {
         assert!(StringUtils::similarity("hello", "hello") == 1.0);
         assert!(StringUtils::similarity("hello", "world") < 1.0);
     }
+
+    #[test]
+    fn test_levenshtein_distance_uses_graphemes() {
+        assert_eq!(StringUtils::levenshtein_distance("kitten", "sitting"), 3);
+
+        // "e\u{301}" 是由两个码点组成的 "é"，按码点计算时替换成 "a" 需要 2 次编辑
+        let decomposed = "cafe\u{301}";
+        assert_eq!(decomposed.chars().count(), 5);
+        assert_eq!(StringUtils::levenshtein_distance(decomposed, "cafa"), 1);
+        assert_eq!(StringUtils::levenshtein_distance(decomposed, "cafe"), 1);
+        assert_eq!(StringUtils::similarity(decomposed, "cafa"), 0.75);
+
+        // 带肤色修饰的 emoji 同样只算一个字符
+        assert_eq!(StringUtils::levenshtein_distance("👍🏽", "👋"), 1);
+    }
+
+    #[test]
+    fn test_similarity_counts_chars() {
+        // 按字符数归一化：两个汉字中有一个不同，相似度为 0.5
+        assert_eq!(StringUtils::similarity("你好", "你们"), 0.5);
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        let candidates = ["JavaScript", "Java", "TypeScript", "Python", "Rust"];
+
+        // 拼写错误的查询仍然把目标排在第一位
+        let matches = StringUtils::fuzzy_search("Pyhton", &candidates, 3);
+        assert_eq!(matches[0].0, "Python");
+        assert!(matches.len() <= 3);
+        assert!(matches.windows(2).all(|w| w[0].1 >= w[1].1));
+
+        // 忽略大小写，完全匹配得分为 1
+        let matches = StringUtils::fuzzy_search("rust", &candidates, 1);
+        assert_eq!(matches, vec![("Rust".to_string(), 1.0)]);
+
+        let matches = StringUtils::fuzzy_search("javascirpt", &candidates, 2);
+        assert_eq!(matches[0].0, "JavaScript");
+
+        assert!(StringUtils::fuzzy_search("rust", &candidates, 0).is_empty());
+        assert!(StringUtils::fuzzy_search("rust", &[], 5).is_empty());
+        // 没有任何相同字符的候选项不会返回
+        assert!(StringUtils::fuzzy_search("xyz", &["abc"], 5).is_empty());
+    }
+
+    #[test]
+    fn test_pluralize() {
+        assert_eq!(StringUtils::pluralize(1, "item", "items"), "item");
+        assert_eq!(StringUtils::pluralize(0, "item", "items"), "items");
+        assert_eq!(StringUtils::pluralize(2, "item", "items"), "items");
+    }
+
+    #[test]
+    fn test_template_replace_with_counts() {
+        let template = "You have {count} {count:item|items} in {place}";
+        let render = |count: &str| {
+            let variables = HashMap::from([
+                ("count".to_string(), count.to_string()),
+                ("place".to_string(), "cart".to_string()),
+            ]);
+            StringUtils::template_replace(template, &variables)
+        };
+
+        assert_eq!(render("1"), "You have 1 item in cart");
+        assert_eq!(render("0"), "You have 0 items in cart");
+        assert_eq!(render("2"), "You have 2 items in cart");
+
+        // 数量不是整数或变量不存在时保留占位符，普通占位符照常替换
+        assert_eq!(render("many"), "You have many {count:item|items} in cart");
+        let variables = HashMap::from([("place".to_string(), "cart".to_string())]);
+        assert_eq!(
+            StringUtils::template_replace("{n:file|files} in {place}", &variables),
+            "{n:file|files} in cart"
+        );
+    }
+
+    #[test]
+    fn test_strip_html() {
+        assert_eq!(
+            StringUtils::strip_html("<p>Hello <b>World</b></p>"),
+            "Hello World"
+        );
+
+        // script/style 的内容和注释被移除
+        let html = r#"<style>p { color: red; }</style><p>Safe</p><SCRIPT type="text/javascript">alert("x")</script ><!-- note -->"#;
+        let text = StringUtils::strip_html(html);
+        assert_eq!(text, "Safe");
+        assert!(!text.contains("alert"));
+
+        // 未闭合的 script 移除到末尾
+        assert_eq!(StringUtils::strip_html("before<script>alert(1)"), "before");
+
+        // 移除标签后解码实体
+        assert_eq!(
+            StringUtils::strip_html("<p>Tom &amp; Jerry&#39;s &lt;show&gt;</p>"),
+            "Tom & Jerry's <show>"
+        );
+    }
+
+    #[test]
+    fn test_unescape_html() {
+        let original = r#"<a href="/search?q=rust&page=1">Tom's</a>"#;
+        let escaped = StringUtils::escape_html(original);
+        assert_eq!(StringUtils::unescape_html(&escaped), original);
+
+        assert_eq!(StringUtils::unescape_html("it&#39;s"), "it's");
+        assert_eq!(StringUtils::unescape_html("&#20320;&#x597D;"), "你好");
+        assert_eq!(StringUtils::unescape_html("&#X41;&apos;"), "A'");
+
+        // 只解码一遍
+        assert_eq!(StringUtils::unescape_html("&amp;lt;"), "&lt;");
+
+        // 未知实体、无效码点和不完整的实体原样保留
+        assert_eq!(
+            StringUtils::unescape_html("&copy; &bogus;"),
+            "&copy; &bogus;"
+        );
+        assert_eq!(StringUtils::unescape_html("&#xD800;"), "&#xD800;");
+        assert_eq!(StringUtils::unescape_html("a & b &lt"), "a & b &lt");
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(StringUtils::slugify("Hello, World!"), "hello-world");
+        assert_eq!(
+            StringUtils::slugify("  --Rust -- & __Axum__  guide!!  "),
+            "rust-axum-guide"
+        );
+        assert_eq!(StringUtils::slugify("Rust 入门 教程"), "rust-入门-教程");
+        assert_eq!(StringUtils::slugify("!!!"), "");
+        assert_eq!(
+            StringUtils::slugify_with_max_length("Hello World Again", 6),
+            "hello"
+        );
+    }
 }
@@ -2,7 +2,7 @@ use chrono::{
     DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, TimeZone,
     Utc,
 };
-use chrono_tz::{Africa, America, Asia, Australia, Europe, Tz};
+use chrono_tz::{Africa, America, Asia, Australia, Europe, OffsetComponents, Tz};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -142,6 +142,33 @@ impl TimeUtils {
         Utc.from_utc_datetime(&start_date.and_hms_opt(0, 0, 0).unwrap())
     }
 
+    /// 获取周的结束时间（周日 23:59:59）
+    pub fn end_of_week(datetime: &DateTime<Utc>) -> DateTime<Utc> {
+        let weekday = datetime.weekday();
+        let days_to_add = 6 - weekday.num_days_from_monday() as i64;
+        let end_date = datetime.date_naive() + Duration::days(days_to_add);
+        Utc.from_utc_datetime(&end_date.and_hms_opt(23, 59, 59).unwrap())
+    }
+
+    /// 获取 ISO-8601 周年份和周数
+    ///
+    /// 返回 `(ISO 年份, 周数)`，周数范围为 1..=53，每周从周一开始，
+    /// 包含当年第一个周四的那一周为第 1 周。
+    /// 在年初和年末，ISO 年份可能与日历年份不同：
+    /// 例如 2021-01-01 属于 2020 年第 53 周，2024-12-30 属于 2025 年第 1 周。
+    pub fn iso_week(datetime: &DateTime<Utc>) -> (i32, u32) {
+        let week = datetime.iso_week();
+        (week.year(), week.week())
+    }
+
+    /// 获取 ISO-8601 周数（1..=53）
+    ///
+    /// 只返回周数，不包含 ISO 年份；按年份分组统计时应使用 `iso_week`，
+    /// 否则年初属于上一年第 52/53 周的日期会被算到错误的年份。
+    pub fn week_of_year(datetime: &DateTime<Utc>) -> u32 {
+        datetime.iso_week().week()
+    }
+
     /// 获取月的开始时间
     pub fn start_of_month(datetime: &DateTime<Utc>) -> DateTime<Utc> {
         let year = datetime.year();
@@ -150,6 +177,22 @@ impl TimeUtils {
         Utc.from_utc_datetime(&first_day.and_hms_opt(0, 0, 0).unwrap())
     }
 
+    /// 获取月的结束时间（当月最后一天 23:59:59）
+    ///
+    /// 以下个月第一天的前一天作为月末，自动处理大小月和闰年二月。
+    pub fn end_of_month(datetime: &DateTime<Utc>) -> DateTime<Utc> {
+        let (year, month) = if datetime.month() == 12 {
+            (datetime.year() + 1, 1)
+        } else {
+            (datetime.year(), datetime.month() + 1)
+        };
+        let last_day = NaiveDate::from_ymd_opt(year, month, 1)
+            .unwrap()
+            .pred_opt()
+            .unwrap();
+        Utc.from_utc_datetime(&last_day.and_hms_opt(23, 59, 59).unwrap())
+    }
+
     /// 获取年的开始时间
     pub fn start_of_year(datetime: &DateTime<Utc>) -> DateTime<Utc> {
         let year = datetime.year();
@@ -157,11 +200,55 @@ impl TimeUtils {
         Utc.from_utc_datetime(&first_day.and_hms_opt(0, 0, 0).unwrap())
     }
 
+    /// 获取年的结束时间（12 月 31 日 23:59:59）
+    pub fn end_of_year(datetime: &DateTime<Utc>) -> DateTime<Utc> {
+        let year = datetime.year();
+        let last_day = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+        Utc.from_utc_datetime(&last_day.and_hms_opt(23, 59, 59).unwrap())
+    }
+
     /// 判断是否为闰年
     pub fn is_leap_year(year: i32) -> bool {
         (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
     }
 
+    /// 计算在 `on` 这一天的周岁年龄
+    ///
+    /// 当年生日还没到时年龄减一；`on` 早于出生日期时返回 0。
+    /// 2 月 29 日出生的人在平年按 3 月 1 日过生日，即 3 月 1 日才满周岁。
+    pub fn age_in_years(birth: NaiveDate, on: NaiveDate) -> i32 {
+        if on < birth {
+            return 0;
+        }
+
+        let age = on.year() - birth.year();
+        if on < Self::birthday_in_year(birth, on.year()) {
+            age - 1
+        } else {
+            age
+        }
+    }
+
+    /// 获取 `from` 当天或之后的下一个生日
+    ///
+    /// `from` 当天就是生日时返回 `from`。
+    /// 2 月 29 日出生的人在平年的生日为 3 月 1 日（与 `age_in_years` 一致）。
+    pub fn next_birthday(birth: NaiveDate, from: NaiveDate) -> NaiveDate {
+        let this_year = Self::birthday_in_year(birth, from.year());
+        if this_year >= from {
+            this_year
+        } else {
+            Self::birthday_in_year(birth, from.year() + 1)
+        }
+    }
+
+    /// 获取指定年份的生日，平年中的 2 月 29 日生日顺延到 3 月 1 日
+    fn birthday_in_year(birth: NaiveDate, year: i32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, birth.month(), birth.day())
+            .or_else(|| NaiveDate::from_ymd_opt(year, 3, 1))
+            .expect("March 1st exists in every year")
+    }
+
     // ========== 时区相关功能 ==========
 
     /// 将 UTC 时间转换到指定时区
@@ -250,9 +337,15 @@ impl TimeUtils {
     }
 
     /// 根据时区名称获取时区
+    ///
+    /// 优先查找常用时区的中文名称（如 `"北京"`），
+    /// 未命中时按 IANA 时区名称（如 `"Europe/Madrid"`）解析。
     pub fn get_timezone_by_name(name: &str) -> Option<Tz> {
         let timezones = Self::get_common_timezones();
-        timezones.get(name).copied()
+        timezones
+            .get(name)
+            .copied()
+            .or_else(|| name.parse::<Tz>().ok())
     }
 
     /// 获取时区的显示名称
@@ -293,42 +386,14 @@ impl TimeUtils {
     }
 
     /// 获取时区当前的夏令时状态
+    ///
+    /// 直接读取 chrono-tz 时区数据中该时刻的夏令时偏移量（`dst_offset`），
+    /// 不依赖一月/七月采样，因此适用于南半球时区和非整小时的夏令时。
     pub fn is_dst_active(timezone: Tz, datetime: Option<DateTime<Utc>>) -> bool {
-        let dt = datetime.unwrap_or_else(|| Utc::now());
+        let dt = datetime.unwrap_or_else(Utc::now);
         let tz_datetime = dt.with_timezone(&timezone);
-        let offset = tz_datetime.offset();
 
-        // 简化的夏令时检测：比较当前偏移与标准偏移
-        // 这里使用一个简单的启发式方法
-        let jan_dt = timezone
-            .with_ymd_and_hms(dt.year(), 1, 1, 12, 0, 0)
-            .single()
-            .unwrap_or_else(|| {
-                timezone
-                    .with_ymd_and_hms(dt.year(), 1, 2, 12, 0, 0)
-                    .unwrap()
-            });
-        let jul_dt = timezone
-            .with_ymd_and_hms(dt.year(), 7, 1, 12, 0, 0)
-            .single()
-            .unwrap_or_else(|| {
-                timezone
-                    .with_ymd_and_hms(dt.year(), 7, 2, 12, 0, 0)
-                    .unwrap()
-            });
-
-        let jan_offset = jan_dt.offset();
-        let jul_offset = jul_dt.offset();
-
-        if jan_offset.fix().local_minus_utc() != jul_offset.fix().local_minus_utc() {
-            // 有夏令时变化
-            let jan_seconds = jan_offset.fix().local_minus_utc();
-            let jul_seconds = jul_offset.fix().local_minus_utc();
-            let current_seconds = offset.fix().local_minus_utc();
-            current_seconds != jan_seconds.min(jul_seconds)
-        } else {
-            false
-        }
+        tz_datetime.offset().dst_offset() != Duration::zero()
     }
 
     /// 获取时区的标准偏移和夏令时偏移
@@ -356,7 +421,7 @@ impl TimeUtils {
 
     /// 计算两个时区之间的时差
     pub fn timezone_difference(tz1: Tz, tz2: Tz, datetime: Option<DateTime<Utc>>) -> i32 {
-        let dt = datetime.unwrap_or_else(|| Utc::now());
+        let dt = datetime.unwrap_or_else(Utc::now);
         let dt1 = dt.with_timezone(&tz1);
         let dt2 = dt.with_timezone(&tz2);
 
@@ -403,28 +468,89 @@ impl TimeUtils {
     }
 
     /// 获取时间的相对描述
+    ///
+    /// 过去的时间返回 "X分钟前"，未来的时间返回 "X分钟后"，
+    /// 前后一分钟以内返回 "刚刚"。
     pub fn relative_time(datetime: &DateTime<Utc>) -> String {
-        let now = Utc::now();
+        Self::relative_time_from(datetime, &Utc::now())
+    }
+
+    /// 获取时间相对于指定参考时间的描述
+    pub fn relative_time_from(datetime: &DateTime<Utc>, now: &DateTime<Utc>) -> String {
         let diff = now.signed_duration_since(*datetime);
+        let (diff, suffix) = if diff < Duration::zero() {
+            (-diff, "后")
+        } else {
+            (diff, "前")
+        };
 
         if diff.num_seconds() < 60 {
             "刚刚".to_string()
         } else if diff.num_minutes() < 60 {
-            format!("{}分钟前", diff.num_minutes())
+            format!("{}分钟{}", diff.num_minutes(), suffix)
         } else if diff.num_hours() < 24 {
-            format!("{}小时前", diff.num_hours())
+            format!("{}小时{}", diff.num_hours(), suffix)
         } else if diff.num_days() < 30 {
-            format!("{}天前", diff.num_days())
+            format!("{}天{}", diff.num_days(), suffix)
         } else if diff.num_days() < 365 {
-            format!("{}个月前", diff.num_days() / 30)
+            format!("{}个月{}", diff.num_days() / 30, suffix)
         } else {
-            format!("{}年前", diff.num_days() / 365)
+            format!("{}年{}", diff.num_days() / 365, suffix)
+        }
+    }
+
+    /// 解析人类可读的时长字符串
+    ///
+    /// 支持 `d`（天）、`h`（小时）、`m`（分钟）、`s`（秒）单位的组合，
+    /// 如 `"2d3h15m"`、`"1h30m"`、`"90s"`，不区分大小写。
+    /// 输入为空、缺少单位或包含未知单位时返回 `None`。
+    pub fn parse_duration(input: &str) -> Option<Duration> {
+        let input = input.trim().to_ascii_lowercase();
+        if input.is_empty() {
+            return None;
+        }
+
+        let mut total_seconds: i64 = 0;
+        let mut number = String::new();
+
+        for c in input.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+
+            let unit_seconds = match c {
+                'd' => 86400,
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+
+            // 单位前必须有数字
+            let value: i64 = number.parse().ok()?;
+            number.clear();
+            total_seconds = total_seconds.checked_add(value.checked_mul(unit_seconds)?)?;
+        }
+
+        // 末尾的数字缺少单位
+        if !number.is_empty() {
+            return None;
         }
+
+        // 超出 chrono::Duration 可表示的范围
+        if total_seconds > i64::MAX / 1000 {
+            return None;
+        }
+
+        Some(Duration::seconds(total_seconds))
     }
 }
 
 /// 时间范围结构体
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// 表示闭区间 `[start, end]`，首尾两个时刻都包含在范围内。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimeRange {
     pub start: DateTime<Utc>,
     pub end: DateTime<Utc>,
@@ -498,6 +624,33 @@ impl TimeRange {
     pub fn overlaps(&self, other: &TimeRange) -> bool {
         self.start <= other.end && self.end >= other.start
     }
+
+    /// 获取两个时间范围的交集
+    ///
+    /// 不相交时返回 `None`；首尾相接时交集为只包含该时刻的范围（持续时间为 0）。
+    pub fn intersection(&self, other: &TimeRange) -> Option<TimeRange> {
+        if !self.overlaps(other) {
+            return None;
+        }
+        Some(TimeRange::new(
+            self.start.max(other.start),
+            self.end.min(other.end),
+        ))
+    }
+
+    /// 合并两个时间范围
+    ///
+    /// 只有重叠或首尾相接时才能合并，返回覆盖两者的范围；
+    /// 中间存在空隙时返回 `None`，避免把空隙也算进合并结果。
+    pub fn union(&self, other: &TimeRange) -> Option<TimeRange> {
+        if !self.overlaps(other) {
+            return None;
+        }
+        Some(TimeRange::new(
+            self.start.min(other.start),
+            self.end.max(other.end),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -535,6 +688,37 @@ mod tests {
         assert_eq!(range.duration(), Duration::hours(1));
     }
 
+    #[test]
+    fn test_time_range_intersection_and_union() {
+        let base = Utc.with_ymd_and_hms(2026, 10, 16, 9, 0, 0).unwrap();
+        let range = |from: i64, to: i64| {
+            TimeRange::new(
+                TimeUtils::add_hours(&base, from),
+                TimeUtils::add_hours(&base, to),
+            )
+        };
+
+        // 部分重叠
+        assert_eq!(range(0, 3).intersection(&range(2, 5)), Some(range(2, 3)));
+        assert_eq!(range(0, 3).union(&range(2, 5)), Some(range(0, 5)));
+        assert_eq!(range(2, 5).intersection(&range(0, 3)), Some(range(2, 3)));
+
+        // 包含关系
+        assert_eq!(range(0, 8).intersection(&range(2, 3)), Some(range(2, 3)));
+        assert_eq!(range(0, 8).union(&range(2, 3)), Some(range(0, 8)));
+
+        // 首尾相接
+        let touching = range(0, 2).intersection(&range(2, 4)).unwrap();
+        assert_eq!(touching, range(2, 2));
+        assert_eq!(touching.duration(), Duration::zero());
+        assert_eq!(range(0, 2).union(&range(2, 4)), Some(range(0, 4)));
+
+        // 不相交
+        assert_eq!(range(0, 1).intersection(&range(2, 3)), None);
+        assert_eq!(range(0, 1).union(&range(2, 3)), None);
+        assert_eq!(range(2, 3).union(&range(0, 1)), None);
+    }
+
     #[test]
     fn test_timezone_conversion() {
         let utc_time = TimeUtils::now_utc();
@@ -561,8 +745,19 @@ mod tests {
         assert!(beijing_tz.is_some());
         assert_eq!(beijing_tz.unwrap(), Asia::Shanghai);
 
+        // IANA 时区名称
+        assert_eq!(
+            TimeUtils::get_timezone_by_name("Asia/Shanghai"),
+            Some(Asia::Shanghai)
+        );
+        assert_eq!(
+            TimeUtils::get_timezone_by_name("Europe/Madrid"),
+            Some(Europe::Madrid)
+        );
+
         let invalid_tz = TimeUtils::get_timezone_by_name("无效时区");
         assert!(invalid_tz.is_none());
+        assert!(TimeUtils::get_timezone_by_name("Mars/Olympus_Mons").is_none());
     }
 
     #[test]
@@ -588,7 +783,7 @@ mod tests {
         let time_diff = converter.get_time_difference();
 
         // 北京和纽约的时差应该在 12-13 小时之间（取决于夏令时）
-        assert!(time_diff >= 12 && time_diff <= 13);
+        assert!((12..=13).contains(&time_diff));
     }
 
     #[test]
@@ -602,4 +797,219 @@ mod tests {
             .any(|&tz| matches!(tz, Asia::Shanghai | Asia::Singapore));
         assert!(contains_asia);
     }
+
+    #[test]
+    fn test_is_dst_active_southern_hemisphere() {
+        let january = Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap();
+        let july = Utc.with_ymd_and_hms(2024, 7, 15, 0, 0, 0).unwrap();
+
+        // 悉尼一月为夏令时（AEDT），七月为标准时间（AEST）
+        assert!(TimeUtils::is_dst_active(Australia::Sydney, Some(january)));
+        assert!(!TimeUtils::is_dst_active(Australia::Sydney, Some(july)));
+
+        // 豪勋爵岛的夏令时只调整 30 分钟
+        assert!(TimeUtils::is_dst_active(
+            Australia::Lord_Howe,
+            Some(january)
+        ));
+        assert!(!TimeUtils::is_dst_active(Australia::Lord_Howe, Some(july)));
+    }
+
+    #[test]
+    fn test_is_dst_active_without_dst() {
+        let january = Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap();
+        let july = Utc.with_ymd_and_hms(2024, 7, 15, 0, 0, 0).unwrap();
+
+        assert!(!TimeUtils::is_dst_active(Asia::Shanghai, Some(january)));
+        assert!(!TimeUtils::is_dst_active(Asia::Shanghai, Some(july)));
+        assert!(TimeUtils::is_dst_active(America::New_York, Some(july)));
+    }
+
+    #[test]
+    fn test_relative_time_past_and_future() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+
+        let past = TimeUtils::add_hours(&now, -1);
+        assert_eq!(TimeUtils::relative_time_from(&past, &now), "1小时前");
+
+        let future = TimeUtils::add_hours(&now, 1);
+        assert_eq!(TimeUtils::relative_time_from(&future, &now), "1小时后");
+
+        // 一分钟以内无论前后都显示 "刚刚"
+        let soon = now + Duration::seconds(30);
+        assert_eq!(TimeUtils::relative_time_from(&soon, &now), "刚刚");
+        let recent = now - Duration::seconds(30);
+        assert_eq!(TimeUtils::relative_time_from(&recent, &now), "刚刚");
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_age_in_years() {
+        let birth = date(1990, 10, 20);
+        // 今年的生日还没到
+        assert_eq!(TimeUtils::age_in_years(birth, date(2026, 10, 16)), 35);
+        assert_eq!(TimeUtils::age_in_years(birth, date(2026, 10, 20)), 36);
+        assert_eq!(TimeUtils::age_in_years(birth, date(2026, 12, 31)), 36);
+        assert_eq!(TimeUtils::age_in_years(birth, birth), 0);
+        assert_eq!(TimeUtils::age_in_years(birth, date(1980, 1, 1)), 0);
+    }
+
+    #[test]
+    fn test_age_in_years_leap_day_birthday() {
+        let birth = date(2000, 2, 29);
+        // 平年 3 月 1 日才满周岁
+        assert_eq!(TimeUtils::age_in_years(birth, date(2023, 2, 28)), 22);
+        assert_eq!(TimeUtils::age_in_years(birth, date(2023, 3, 1)), 23);
+        // 闰年按 2 月 29 日计算
+        assert_eq!(TimeUtils::age_in_years(birth, date(2024, 2, 28)), 23);
+        assert_eq!(TimeUtils::age_in_years(birth, date(2024, 2, 29)), 24);
+    }
+
+    #[test]
+    fn test_next_birthday() {
+        let birth = date(1990, 10, 20);
+        // 生日在今年晚些时候
+        assert_eq!(
+            TimeUtils::next_birthday(birth, date(2026, 10, 16)),
+            date(2026, 10, 20)
+        );
+        // 当天就是生日
+        assert_eq!(
+            TimeUtils::next_birthday(birth, date(2026, 10, 20)),
+            date(2026, 10, 20)
+        );
+        // 今年的生日已过
+        assert_eq!(
+            TimeUtils::next_birthday(birth, date(2026, 10, 21)),
+            date(2027, 10, 20)
+        );
+
+        let leap_birth = date(2000, 2, 29);
+        assert_eq!(
+            TimeUtils::next_birthday(leap_birth, date(2023, 1, 10)),
+            date(2023, 3, 1)
+        );
+        assert_eq!(
+            TimeUtils::next_birthday(leap_birth, date(2023, 3, 2)),
+            date(2024, 2, 29)
+        );
+    }
+
+    #[test]
+    fn test_end_of_period() {
+        let at = |year, month, day, hour| {
+            Utc.from_utc_datetime(&date(year, month, day).and_hms_opt(hour, 0, 0).unwrap())
+        };
+        let end = |year, month, day| {
+            Utc.from_utc_datetime(&date(year, month, day).and_hms_opt(23, 59, 59).unwrap())
+        };
+
+        // 2026-10-16 是周五，周末为周日
+        assert_eq!(
+            TimeUtils::end_of_week(&at(2026, 10, 16, 8)),
+            end(2026, 10, 18)
+        );
+        assert_eq!(
+            TimeUtils::end_of_week(&at(2026, 10, 12, 0)),
+            end(2026, 10, 18)
+        );
+        assert_eq!(
+            TimeUtils::end_of_week(&at(2026, 10, 18, 23)),
+            end(2026, 10, 18)
+        );
+        // 跨年的一周
+        assert_eq!(
+            TimeUtils::end_of_week(&at(2025, 12, 31, 12)),
+            end(2026, 1, 4)
+        );
+
+        // 闰年与平年的二月
+        assert_eq!(
+            TimeUtils::end_of_month(&at(2024, 2, 10, 12)),
+            end(2024, 2, 29)
+        );
+        assert_eq!(
+            TimeUtils::end_of_month(&at(2023, 2, 10, 12)),
+            end(2023, 2, 28)
+        );
+        assert_eq!(
+            TimeUtils::end_of_month(&at(2000, 2, 1, 0)),
+            end(2000, 2, 29)
+        );
+        assert_eq!(
+            TimeUtils::end_of_month(&at(1900, 2, 1, 0)),
+            end(1900, 2, 28)
+        );
+        assert_eq!(
+            TimeUtils::end_of_month(&at(2026, 4, 30, 23)),
+            end(2026, 4, 30)
+        );
+        assert_eq!(
+            TimeUtils::end_of_month(&at(2026, 12, 1, 0)),
+            end(2026, 12, 31)
+        );
+
+        // 十二月与年末
+        assert_eq!(
+            TimeUtils::end_of_year(&at(2026, 12, 31, 23)),
+            end(2026, 12, 31)
+        );
+        assert_eq!(
+            TimeUtils::end_of_year(&at(2026, 1, 1, 0)),
+            end(2026, 12, 31)
+        );
+        assert_eq!(
+            TimeUtils::end_of_year(&at(2026, 6, 15, 12)),
+            TimeUtils::end_of_month(&at(2026, 12, 15, 12))
+        );
+    }
+
+    #[test]
+    fn test_iso_week() {
+        let at = |year, month, day| {
+            Utc.from_utc_datetime(&date(year, month, day).and_hms_opt(12, 0, 0).unwrap())
+        };
+
+        assert_eq!(TimeUtils::iso_week(&at(2026, 10, 16)), (2026, 42));
+        assert_eq!(TimeUtils::week_of_year(&at(2026, 10, 16)), 42);
+
+        // 1 月 1 日属于上一个 ISO 年份的最后一周
+        assert_eq!(TimeUtils::iso_week(&at(2021, 1, 1)), (2020, 53));
+        assert_eq!(TimeUtils::iso_week(&at(2023, 1, 1)), (2022, 52));
+        assert_eq!(TimeUtils::week_of_year(&at(2021, 1, 1)), 53);
+
+        // 12 月 30 日属于下一个 ISO 年份的第 1 周
+        assert_eq!(TimeUtils::iso_week(&at(2024, 12, 30)), (2025, 1));
+        assert_eq!(TimeUtils::week_of_year(&at(2024, 12, 30)), 1);
+
+        // 第 1 周从周一开始，与 start_of_week 一致
+        assert_eq!(
+            TimeUtils::start_of_week(&at(2025, 1, 1)).date_naive(),
+            date(2024, 12, 30)
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(
+            TimeUtils::parse_duration("1h30m"),
+            Some(Duration::minutes(90))
+        );
+        assert_eq!(
+            TimeUtils::parse_duration("90s"),
+            Some(Duration::seconds(90))
+        );
+        assert_eq!(
+            TimeUtils::parse_duration("2D3H15M"),
+            Some(Duration::days(2) + Duration::hours(3) + Duration::minutes(15))
+        );
+
+        assert_eq!(TimeUtils::parse_duration("abc"), None);
+        assert_eq!(TimeUtils::parse_duration(""), None);
+        assert_eq!(TimeUtils::parse_duration("10"), None);
+        assert_eq!(TimeUtils::parse_duration("h"), None);
+    }
 }